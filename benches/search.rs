@@ -0,0 +1,62 @@
+use bevy::{ecs::system::SystemState, prelude::{Entity, Query, World}};
+use bevy_graph::{graph_functions::{bfs::bfs, dijkstra::{dijkstra_search, dijkstra_search_heap}, spawn_graph, view::GraphView}, graph_vertex::StandardGraphVertex};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+
+/// Spawns a 4-connected grid of the given size, with edge weights between 1 and 9 so that many vertices have their distance improved
+/// several times, returning the entities in row-major order
+fn spawn_weighted_grid(world: &mut World, size: usize) -> Vec<Entity> {
+    let mut edges = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            let index = y * size + x;
+            let weight = |to: usize| (1 + (index * 31 + to * 17) % 9) as f32;
+            if x + 1 < size {
+                edges.push((index, index + 1, weight(index + 1)));
+                edges.push((index + 1, index, weight(index)));
+            }
+            if y + 1 < size {
+                edges.push((index, index + size, weight(index + size)));
+                edges.push((index + size, index, weight(index)));
+            }
+        }
+    }
+    let entities = spawn_graph(&mut world.commands(), &edges, size * size);
+    world.flush();
+    entities
+}
+
+fn dijkstra_heap(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dijkstra_200x200_grid");
+    let mut world = World::new();
+    let grid = spawn_weighted_grid(&mut world, 200);
+    let (start, end) = (grid[0], grid[grid.len() - 1]);
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    group.bench_function("dijkstra_search", |b| b.iter(|| dijkstra_search(&query, start, end).unwrap()));
+    group.bench_function("dijkstra_search_heap", |b| b.iter(|| dijkstra_search_heap(&query, start, end).unwrap()));
+    group.finish();
+}
+
+fn graph_view(c: &mut Criterion) {
+    let mut group = c.benchmark_group("view_200x200_grid");
+    let mut world = World::new();
+    let grid = spawn_weighted_grid(&mut world, 200);
+    let (start, end) = (grid[0], grid[grid.len() - 1]);
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let mut entity_sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let entity_query = entity_sys_state.get(&world);
+    let view = GraphView::new(&entity_query);
+
+    group.bench_function("build", |b| b.iter(|| GraphView::new(&entity_query)));
+    group.bench_function("view_dijkstra", |b| b.iter(|| view.dijkstra(start, end).unwrap()));
+    group.bench_function("query_dijkstra", |b| b.iter(|| dijkstra_search(&query, start, end).unwrap()));
+    group.bench_function("view_bfs", |b| b.iter(|| view.bfs(start, end).unwrap()));
+    group.bench_function("query_bfs", |b| b.iter(|| bfs(&query, start, end).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, dijkstra_heap, graph_view);
+criterion_main!(benches);
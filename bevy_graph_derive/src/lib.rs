@@ -0,0 +1,100 @@
+//! Derive macro for the `GraphVertex` trait of `bevy_graph`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, GenericArgument, PathArguments, Type};
+
+
+/// Derives `GraphVertex` using the field marked with `#[graph_edges]` as the vertex's edges
+/// 
+/// The marked field must be either a `Vec<(Entity, f32)>`, storing each neighbour alongside the weight of the edge to it, or a `Vec<Entity>`,
+/// in which case every edge is given a weight of 1.0. The type must also implement `Component`.
+/// 
+/// ```ignore
+/// #[derive(Component, GraphVertex)]
+/// struct Room {
+///     name: String,
+///     #[graph_edges]
+///     doors: Vec<(Entity, f32)>
+/// }
+/// ```
+#[proc_macro_derive(GraphVertex, attributes(graph_edges))]
+pub fn derive_graph_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match impl_graph_vertex(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into()
+    }
+}
+
+fn impl_graph_vertex(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(input.span(), "GraphVertex can only be derived for structs"));
+    };
+
+    //find the single field marked as holding the edges
+    let mut marked = data.fields.iter().enumerate()
+    .filter(|(_, field)| field.attrs.iter().any(|attr| attr.path().is_ident("graph_edges")));
+    let Some((index, field)) = marked.next() else {
+        return Err(syn::Error::new(input.span(), "GraphVertex requires a field marked with #[graph_edges]"));
+    };
+    if let Some((_, extra)) = marked.next() {
+        return Err(syn::Error::new(extra.span(), "only one field can be marked with #[graph_edges]"));
+    }
+
+    let member = match &data.fields {
+        Fields::Named(_) => {
+            let ident = field.ident.as_ref().expect("named fields have identifiers");
+            quote!(#ident)
+        },
+        _ => {
+            let index = syn::Index::from(index);
+            quote!(#index)
+        }
+    };
+
+    let (neighbours, with_weight) = match edge_kind(&field.ty) {
+        Some(EdgeKind::Weighted) => (
+            quote!(self.#member.iter().map(|(ent, _)| *ent).collect()),
+            quote!(self.#member.clone())
+        ),
+        Some(EdgeKind::Unweighted) => (
+            quote!(self.#member.clone()),
+            quote!(self.#member.iter().map(|ent| (*ent, 1.0)).collect())
+        ),
+        None => return Err(syn::Error::new(field.ty.span(), "#[graph_edges] must be a Vec<(Entity, f32)> or a Vec<Entity>"))
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bevy_graph::graph_vertex::GraphVertex for #name #ty_generics #where_clause {
+            fn get_neighbours(&self) -> ::std::vec::Vec<::bevy::prelude::Entity> {
+                #neighbours
+            }
+            fn get_neighbours_with_weight(&self) -> ::std::vec::Vec<(::bevy::prelude::Entity, f32)> {
+                #with_weight
+            }
+        }
+    })
+}
+
+enum EdgeKind {
+    Weighted,
+    Unweighted
+}
+
+/// Determines whether the type is a `Vec` of (entity, weight) pairs or of entities, based on the shape of its element type
+fn edge_kind(ty: &Type) -> Option<EdgeKind> {
+    let Type::Path(path) = ty else {return None;};
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Vec" {return None;}
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {return None;};
+    let Some(GenericArgument::Type(element)) = args.args.first() else {return None;};
+    match element {
+        Type::Tuple(tuple) if tuple.elems.len() == 2 => Some(EdgeKind::Weighted),
+        Type::Path(_) => Some(EdgeKind::Unweighted),
+        _ => None
+    }
+}
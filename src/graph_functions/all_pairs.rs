@@ -0,0 +1,144 @@
+use bevy::{prelude::{Entity, Query}, utils::HashMap};
+
+use crate::graph_vertex::GraphVertex;
+
+use super::{GraphError, GraphPath};
+
+
+/// Runs the Floyd-Warshall algorithm, returning the shortest distance between every pair of connected vertices
+/// 
+/// The returned map contains an entry for `(a, b)` if and only if there is a path from `a` to `b`, including `(a, a)` with distance 0.
+/// Unlike Dijkstra's algorithm, negative edge weights are allowed, as long as they don't form a negative cycle. Edges to entities outside
+/// of the query are ignored. This takes `O(n^3)` time and `O(n^2)` memory in the number of vertices, so is best suited to small graphs
+/// that are queried often.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::NegativeCycle`]: If the graph contains a cycle with negative total weight, so shortest paths are not defined.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that precomputes the travel time between every pair of towns
+/// fn compute_travel_times(
+///     towns: Query<(Entity, &TownVertex)>,
+///     mut travel_times: ResMut<TravelTimes>
+/// ) {
+///     travel_times.0 = floyd_warshall(&towns).expect("Roads should never have negative travel time");
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`floyd_warshall_with_next`]: For the same algorithm, additionally returning the information needed to reconstruct the paths
+pub fn floyd_warshall<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Result<HashMap<(Entity, Entity), f32>, GraphError> {
+    floyd_warshall_with_next(query).map(|(distances, _)| distances)
+}
+
+/// Runs the Floyd-Warshall algorithm, returning the shortest distance between every pair of connected vertices and the next vertex along each path
+/// 
+/// Identical to [`floyd_warshall`], with the second map giving, for each connected pair `(a, b)` with `a != b`, the vertex following `a` on a 
+/// shortest path to `b`. Any of the paths can then be rebuilt with [`reconstruct_apsp`].
+/// 
+/// # Errors
+/// 
+/// [`GraphError::NegativeCycle`]: If the graph contains a cycle with negative total weight, so shortest paths are not defined.
+/// 
+/// # See also
+/// 
+/// [`reconstruct_apsp`]: For rebuilding a path from the returned maps
+#[allow(clippy::type_complexity)]
+pub fn floyd_warshall_with_next<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Result<(HashMap<(Entity, Entity), f32>, HashMap<(Entity, Entity), Entity>), GraphError> {
+    //map the entities to contiguous indices so the matrices can be stored as flat vectors
+    let entities: Vec<Entity> = query.iter().map(|(ent, _)| ent).collect();
+    let indices: HashMap<Entity, usize> = entities.iter().enumerate().map(|(ind, &ent)| (ent, ind)).collect();
+    let n = entities.len();
+
+    let mut dist = vec![f32::INFINITY; n * n];
+    let mut next: Vec<Option<usize>> = vec![None; n * n];
+    for i in 0..n {dist[i * n + i] = 0.0;}
+
+    //initialise the matrices with the edges, keeping the lightest of any repeated edges
+    for (ent, vert) in query.iter() {
+        let i = indices[&ent];
+        for (neighbour_ent, weight) in vert.get_neighbours_with_weight() {
+            let Some(&j) = indices.get(&neighbour_ent) else {continue;};
+            if weight < dist[i * n + j] {
+                dist[i * n + j] = weight;
+                next[i * n + j] = Some(j);
+            }
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            let dist_ik = dist[i * n + k];
+            if dist_ik == f32::INFINITY {continue;}
+            for j in 0..n {
+                let through_k = dist_ik + dist[k * n + j];
+                if through_k < dist[i * n + j] {
+                    dist[i * n + j] = through_k;
+                    next[i * n + j] = next[i * n + k];
+                }
+            }
+        }
+    }
+
+    //a vertex with a negative distance to itself lies on a negative cycle
+    if (0..n).any(|i| dist[i * n + i] < 0.0) {return Err(GraphError::NegativeCycle);}
+
+    let mut distances = HashMap::new();
+    let mut next_map = HashMap::new();
+    for i in 0..n {
+        for j in 0..n {
+            if dist[i * n + j] == f32::INFINITY {continue;}
+            distances.insert((entities[i], entities[j]), dist[i * n + j]);
+            if let Some(k) = next[i * n + j] {
+                if i != j {next_map.insert((entities[i], entities[j]), entities[k]);}
+            }
+        }
+    }
+    Ok((distances, next_map))
+}
+
+/// Rebuilds the shortest path between two vertices from the output of [`floyd_warshall_with_next`], returning the path in **reverse order**
+/// 
+/// Each vertex in the path is paired with its distance from the start vertex, as with the paths returned by [`dijkstra_search`](super::dijkstra::dijkstra_search).
+/// Returns [`None`] if there is no path between the vertices, or if either vertex wasn't part of the graph.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that looks up the route between two towns from precomputed all-pairs shortest paths
+/// fn route_between_towns(
+///     request: Res<RouteRequest>,
+///     routes: Res<AllRoutes>
+/// ) {
+///     if let Some(path) = reconstruct_apsp(&routes.distances, &routes.next, request.from, request.to) {
+///         println!("Route found with a travel time of {}", path.total_weight());
+///     }
+/// }
+/// ```
+pub fn reconstruct_apsp(
+    distances: &HashMap<(Entity, Entity), f32>,
+    next: &HashMap<(Entity, Entity), Entity>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Option<GraphPath<f32>> {
+    distances.get(&(start_ent, end_ent))?;
+
+    let mut path = vec![(start_ent, 0.0)];
+    let mut current = start_ent;
+    while current != end_ent {
+        current = *next.get(&(current, end_ent))?;
+        path.push((current, *distances.get(&(start_ent, current))?));
+        //a valid next map can never revisit a vertex, so a path longer than the number of vertices must be looping
+        if path.len() > distances.len() {return None;}
+    }
+    path.reverse();
+    Some(GraphPath::new(path))
+}
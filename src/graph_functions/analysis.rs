@@ -0,0 +1,834 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use bevy::{prelude::{Entity, Query}, utils::{HashMap, HashSet}};
+
+use crate::{graph_vertex::GraphVertex, GraphLabel};
+
+use super::{dijkstra::dijkstra_tree, helper::{all_edges, directed_neighbours, get_start, reverse_adjacency, UnionFind}, Direction, GraphError, GraphPath};
+
+
+/// Returns all source vertices, ie those with no incoming edges
+///
+/// An edge only counts as incoming if the vertex it comes from is in the provided query, so vertices excluded by the query's filter
+/// do not prevent another vertex from being a source. Self-loops count as incoming edges.
+///
+/// # Example
+///
+/// ```ignore
+/// //A system that finds the root tasks of a dependency graph
+/// fn find_roots(tasks: Query<(Entity, &TaskVertex)>) {
+///     for root in sources(&tasks) {
+///         println!("Task {:?} has no dependencies", root);
+///     }
+/// }
+/// ```
+///
+/// # See also
+///
+/// [`sinks`]: For vertices with no outgoing edges
+pub fn sources<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<Entity> {
+    //collect every entity that is the destination of some edge
+    let has_incoming: HashSet<Entity> = query.iter()
+    .flat_map(|(_, vert)| vert.get_neighbours())
+    .collect();
+
+    query.iter()
+    .filter_map(|(ent, _)| if has_incoming.contains(&ent) {None} else {Some(ent)})
+    .collect()
+}
+
+/// Returns all sink vertices, ie those with no outgoing edges
+///
+/// # Example
+///
+/// ```ignore
+/// //A system that finds the final tasks of a dependency graph
+/// fn find_leaves(tasks: Query<(Entity, &TaskVertex)>) {
+///     for leaf in sinks(&tasks) {
+///         println!("Nothing depends on task {:?}", leaf);
+///     }
+/// }
+/// ```
+///
+/// # See also
+///
+/// [`sources`]: For vertices with no incoming edges
+pub fn sinks<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<Entity> {
+    query.iter()
+    .filter_map(|(ent, vert)| if vert.is_sink() {Some(ent)} else {None})
+    .collect()
+}
+
+/// Returns every pair of vertices joined by more than one edge in the same direction, as `(source, destination)` pairs
+/// 
+/// Nothing stops a vertex listing the same neighbour twice, eg when a [`StandardGraphVertex`](crate::graph_vertex::StandardGraphVertex) is
+/// built from a list of edges, but most functions assume it doesn't: removing an edge only removes one of the copies, and searches may take
+/// the weight of either. Each pair is given once, in the order of the query and then of each vertex's neighbours. Repeated edges on a
+/// standard vertex can be collapsed with [`dedup_edges`](crate::graph_vertex::StandardGraphVertex::dedup_edges).
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that checks a level file didn't connect any two rooms twice
+/// fn validate_level(rooms: Query<(Entity, &RoomVertex)>) {
+///     for (from, to) in duplicate_edges(&rooms) {
+///         println!("Room {:?} has more than one door to room {:?}", from, to);
+///     }
+/// }
+/// ```
+pub fn duplicate_edges<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<(Entity, Entity)> {
+    let mut duplicates = Vec::new();
+    for (ent, vert) in query.iter() {
+        let mut seen: HashSet<Entity> = HashSet::new();
+        let mut reported: HashSet<Entity> = HashSet::new();
+        for neighbour_ent in vert.get_neighbours() {
+            if !seen.insert(neighbour_ent) && reported.insert(neighbour_ent) {
+                duplicates.push((ent, neighbour_ent));
+            }
+        }
+    }
+    duplicates
+}
+
+
+/// Returns the dead ends of the graph, ie the vertices reachable from the start vertex from which the goal vertex can't be reached
+/// 
+/// Finds everything reachable from the start vertex, then everything that can reach the goal vertex by following edges backwards, and returns
+/// the vertices in the first set but not the second, in the order they were reached from the start. These are the parts of a map that trap
+/// anything that wanders into them. Only vertices in the query are followed in either direction.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided goal vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that checks a level has no rooms that trap the player
+/// fn validate_level(
+///     level: Res<Level>,
+///     rooms: Query<(Entity, &RoomVertex)>
+/// ) {
+///     for room in dead_ends(&rooms, level.spawn_room, level.exit_room).unwrap() {
+///         println!("Room {:?} can be entered, but the exit can't be reached from it", room);
+///     }
+/// }
+/// ```
+pub fn dead_ends<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    start_ent: Entity,
+    goal_ent: Entity
+) -> Result<Vec<Entity>, GraphError> {
+    get_start(query, start_ent)?;
+    query.get(goal_ent)?;
+
+    let incoming = reverse_adjacency(query);
+    let reachable = |from: Entity, direction: Direction| {
+        let mut found = vec![from];
+        let mut seen = HashSet::from([from]);
+        let mut search_queue = VecDeque::from([from]);
+        while let Some(sv_ent) = search_queue.pop_front() {
+            for (neighbour_ent, _) in directed_neighbours(query, &incoming, sv_ent, direction).unwrap_or_default() {
+                if query.get(neighbour_ent).is_err() || !seen.insert(neighbour_ent) {continue;}
+                found.push(neighbour_ent);
+                search_queue.push_back(neighbour_ent);
+            }
+        }
+        found
+    };
+
+    let reaches_goal: HashSet<Entity> = reachable(goal_ent, Direction::Incoming).into_iter().collect();
+    Ok(reachable(start_ent, Direction::Outgoing).into_iter().filter(|ent| !reaches_goal.contains(ent)).collect())
+}
+
+
+/// Returns the total weight of the edges along a path, as given by each vertex's [`GraphVertex::get_neighbours_with_weight`]
+/// 
+/// The path should be in the **reverse order** returned by the searches. This allows the true cost of a path found without considering
+/// weights, eg by [`bfs`](super::bfs::bfs), to be compared with that of a weighted search. If a vertex has several edges to the next vertex
+/// of the path, the smallest weight is used. A path of a single vertex has a weight of 0.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::InvalidEntity`]: If a vertex of the path, other than the end vertex, does not appear in the provided query.
+/// 
+/// [`GraphError::NoPath`]: If a vertex of the path has no edge to the next vertex.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system comparing the cost of the path with the fewest steps to the cheapest path
+/// fn compare_routes(
+///     journey: Res<Journey>,
+///     roads: Query<&RoadVertex>
+/// ) {
+///     let fewest_steps = bfs(&roads, journey.start, journey.end).unwrap();
+///     let cheapest = dijkstra_search(&roads, journey.start, journey.end).unwrap();
+///     let extra_cost = evaluate_path(&roads, &fewest_steps).unwrap() - cheapest.total_weight();
+/// }
+/// ```
+pub fn evaluate_path<V: GraphVertex, D>(
+    query: &Query<&V>,
+    path: &GraphPath<D>
+) -> Result<f32, GraphError> {
+    let entities: Vec<Entity> = path.entities().collect();
+    //as the path is reversed, each edge goes from the later entity of a pair to the earlier one
+    entities.windows(2).try_fold(0.0, |total, pair| {
+        let (to_ent, from_ent) = (pair[0], pair[1]);
+        query.get(from_ent)?.get_neighbours_with_weight().into_iter()
+        .filter(|(neighbour_ent, _)| *neighbour_ent == to_ent)
+        .map(|(_, weight)| weight)
+        .min_by(f32::total_cmp)
+        .map(|weight| total + weight)
+        .ok_or(GraphError::NoPath)
+    })
+}
+
+/// Checks that a path, in the **reverse order** returned by the searches, is a real path of the graph from `start_ent` to `end_ent`
+/// 
+/// The path must begin at the start vertex, end at the end vertex, and each vertex of it must have an edge to the next. Any data paired with
+/// the vertices, such as distances, is not checked. This is a safety net for code that builds or modifies paths, and is useful in tests to check
+/// the output of a search is valid, rather than just that its vertices look right. It can also be run on paths in production behind a debug flag,
+/// eg with `debug_assert!(verify_path(&query, &path, start, end).is_ok())`.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::BrokenPath`]: If the path does not begin at the start vertex or end at the end vertex, or two consecutive vertices have no 
+/// edge between them.
+/// 
+/// [`GraphError::InvalidEntity`]: If a vertex of the path, other than the end vertex, does not appear in the provided query.
+pub fn verify_path<V: GraphVertex, D>(
+    query: &Query<&V>,
+    path: &GraphPath<D>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<(), GraphError> {
+    let entities: Vec<Entity> = path.entities().collect();
+    if entities.last() != Some(&start_ent) || entities.first() != Some(&end_ent) {return Err(GraphError::BrokenPath);}
+    //as the path is reversed, each edge goes from the later entity of a pair to the earlier one
+    for pair in entities.windows(2) {
+        let (to_ent, from_ent) = (pair[0], pair[1]);
+        if !query.get(from_ent)?.get_neighbours().contains(&to_ent) {return Err(GraphError::BrokenPath);}
+    }
+    Ok(())
+}
+
+
+/// Shortens a weighted path by skipping intermediate vertices wherever a direct edge is no more costly, returning the path in **reverse order**
+/// 
+/// Walking forward from the start vertex, each vertex is joined to the furthest later vertex of the path it has a direct edge to, as long as
+/// that edge weighs no more than the part of the path it replaces. This is the usual smoothing applied to paths on navigation meshes, where a
+/// search often zig-zags through vertices that could be cut out. The path should be as returned by a weighted search, ie each vertex paired with
+/// its distance from the start, and the distances of the returned path are recomputed to match the edges it now uses. Vertices not in the query 
+/// are treated as having no edges, so are never shortcut from.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that paths an agent across a navigation mesh, cutting out any unnecessary corners
+/// fn path_agent(
+///     mut agents: Query<(&CurrentPolygon, &Target, &mut AgentPath)>,
+///     polygons: Query<&NavPolygon>
+/// ) {
+///     for (polygon, target, mut agent_path) in agents.iter_mut() {
+///         agent_path.0 = dijkstra_search(&polygons, polygon.0, target.0).ok().map(|path| smooth_path(&polygons, path));
+///     }
+/// }
+/// ```
+pub fn smooth_path<V: GraphVertex>(
+    query: &Query<&V>,
+    path: GraphPath<f32>
+) -> GraphPath<f32> {
+    let forward = path.into_forward();
+    let Some(&first) = forward.first() else {return GraphPath::new(forward);};
+
+    let mut smoothed = vec![first];
+    let mut current = 0;
+    while current + 1 < forward.len() {
+        let (current_ent, current_dist) = forward[current];
+        let smoothed_dist = smoothed.last().expect("The smoothed path always has the start vertex").1;
+        let edges = query.get(current_ent).map(|vert| vert.get_neighbours_with_weight()).unwrap_or_default();
+
+        //look for the furthest vertex of the path that a direct edge reaches at no greater cost
+        let shortcut = (current + 2..forward.len()).rev().find_map(|later| {
+            let (later_ent, later_dist) = forward[later];
+            edges.iter()
+            .filter(|(neighbour_ent, _)| *neighbour_ent == later_ent)
+            .map(|(_, weight)| *weight)
+            .min_by(f32::total_cmp)
+            .filter(|weight| *weight <= later_dist - current_dist)
+            .map(|weight| (later, weight))
+        });
+
+        //otherwise keep the next vertex, with the same edge as before
+        let (next, weight) = shortcut.unwrap_or((current + 1, forward[current + 1].1 - current_dist));
+        smoothed.push((forward[next].0, smoothed_dist + weight));
+        current = next;
+    }
+
+    smoothed.reverse();
+    GraphPath::new(smoothed)
+}
+
+
+/// Returns the closeness centrality of the vertex, ie the reciprocal of its average shortest path distance to the vertices it can reach
+/// 
+/// Computed as `(reachable - 1) / total` with a single run of [`dijkstra_tree`], where `reachable` counts the vertices the given vertex can reach,
+/// including itself, and `total` is the sum of the distances to them. A higher value means the vertex is closer to everything else, ie more central.
+/// Only the reachable vertices are averaged over, so in a disconnected graph a vertex is compared against the part of the graph it can reach.
+/// A vertex that can reach no other vertex has a closeness of 0, while one that reaches others only along edges of weight 0 has infinite closeness.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that places the capital in the most central city
+/// fn choose_capital(
+///     cities: Query<Entity, With<City>>,
+///     roads: Query<&RoadVertex>,
+///     mut commands: Commands
+/// ) {
+///     let capital = cities.iter()
+///     .max_by(|a, b| closeness_centrality(&roads, *a).unwrap().total_cmp(&closeness_centrality(&roads, *b).unwrap()));
+///     commands.entity(capital.unwrap()).insert(Capital);
+/// }
+/// ```
+pub fn closeness_centrality<V: GraphVertex>(
+    query: &Query<&V>,
+    ent: Entity
+) -> Result<f32, GraphError> {
+    let tree = dijkstra_tree(query, ent)?;
+    if tree.len() <= 1 {return Ok(0.0);}
+    let total: f32 = tree.values().map(|(dist, _)| dist).sum();
+    Ok((tree.len() - 1) as f32 / total)
+}
+
+
+/// Labels each vertex with the index of the (weakly) connected component it belongs to
+/// 
+/// Edges are treated as undirected, so two vertices share a label if there is a path between them when ignoring edge direction.
+/// Vertices in different components can never have a path between them, which makes the labels useful for cheaply ruling out searches, 
+/// eg with [`bfs_prepared`](super::bfs::bfs_prepared) and [`dijkstra_search_prepared`](super::dijkstra::dijkstra_search_prepared).
+/// Edges to entities outside the query are ignored. The labels are in the range `0..n` for `n` components, but are otherwise arbitrary.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that recomputes the component labels whenever the map changes
+/// fn update_components(
+///     tiles: Query<(Entity, &VertexType)>,
+///     mut components: ResMut<TileComponents>
+/// ) {
+///     components.0 = connected_components(&tiles);
+/// }
+/// ```
+pub fn connected_components<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> HashMap<Entity, usize> {
+    //assign each vertex an index for the union find
+    let indices: HashMap<Entity, usize> = query.iter().enumerate().map(|(ind, (ent, _))| (ent, ind)).collect();
+    let mut union_find = UnionFind::new(indices.len());
+
+    for (ent, vert) in query.iter() {
+        let ent_ind = indices[&ent];
+        for neighbour_ent in vert.get_neighbours() {
+            let Some(&neighbour_ind) = indices.get(&neighbour_ent) else {continue;};
+            union_find.union(ent_ind, neighbour_ind);
+        }
+    }
+
+    //relabel the union find roots to be contiguous
+    let mut root_labels: HashMap<usize, usize> = HashMap::new();
+    indices.into_iter().map(|(ent, ind)| {
+        let root = union_find.find(ind);
+        let next_label = root_labels.len();
+        (ent, *root_labels.entry(root).or_insert(next_label))
+    }).collect()
+}
+
+
+/// Returns the number of vertices in each (weakly) connected component, largest first
+/// 
+/// Components are as given by [`connected_components`], so edge direction is ignored. This shows at a glance whether a graph is one large 
+/// component or split into fragments, eg `vec![500]` against `vec![300, 150, 50]`. The lengths sum to the number of vertices in the query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that warns when a generated map is not fully connected
+/// fn check_map(tiles: Query<(Entity, &VertexType)>) {
+///     let sizes = component_sizes(&tiles);
+///     if sizes.len() > 1 {
+///         warn!("The map is split into {} parts, the largest has {} tiles", sizes.len(), sizes[0]);
+///     }
+/// }
+/// ```
+pub fn component_sizes<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<usize> {
+    let mut sizes: Vec<usize> = Vec::new();
+    for label in connected_components(query).into_values() {
+        if sizes.len() <= label {sizes.resize(label + 1, 0);}
+        sizes[label] += 1;
+    }
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes
+}
+
+/// Updates component labels, as given by [`connected_components`], after the edges between two vertices have been removed
+/// 
+/// Removing an edge can only split the component it was in, and only if its two vertices are no longer connected, ignoring edge direction.
+/// So rather than recomputing every label, this runs a breadth-first search from `a`, stopping as soon as `b` is found. If `b` isn't found,
+/// every vertex reached from `a` is given a fresh label, one more than the largest label in use, and `true` is returned. Otherwise the labels
+/// are left unchanged and `false` is returned. The edges must already have been removed from the graph, in both directions, before calling
+/// this.
+/// 
+/// Following edges backwards needs the incoming edges of each vertex, given by `incoming` as built by [`reverse_adjacency`]. The map can be
+/// built once and reused over many removals, as each incoming edge is checked against the graph before it is followed, so edges removed
+/// since it was built are skipped. It should be rebuilt after edges are added. With the map prebuilt, the search only covers `a`'s side of
+/// the split, so is far cheaper than [`connected_components`] when that side is small.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If `a` does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If `b` does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that destroys a bridge, then updates which tiles can reach each other
+/// fn destroy_bridge(
+///     mut tiles: Query<(Entity, &mut StandardGraphVertex)>,
+///     destroyed: Res<DestroyedBridge>,
+///     mut components: ResMut<TileComponents>
+/// ) {
+///     let (a, b) = destroyed.0;
+///     tiles.get_mut(a).unwrap().1.remove_edge(b);
+///     tiles.get_mut(b).unwrap().1.remove_edge(a);
+///     //the incoming edges were built when the map was loaded
+///     recompute_component_after_removal(&tiles.to_readonly(), &components.incoming, a, b, &mut components.labels).unwrap();
+/// }
+/// ```
+pub fn recompute_component_after_removal<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    incoming: &HashMap<Entity, Vec<(Entity, f32)>>,
+    a: Entity,
+    b: Entity,
+    labels: &mut HashMap<Entity, usize>
+) -> Result<bool, GraphError> {
+    get_start(query, a)?;
+    query.get(b)?;
+    if a == b {return Ok(false);}
+
+    //an incoming edge is only followed if its source still has the edge, as the map may predate the removal
+    let has_edge = |from: Entity, to: Entity| query.get(from).is_ok_and(|(_, vert)| vert.get_neighbours().contains(&to));
+
+    let mut reached: HashSet<Entity> = HashSet::from([a]);
+    let mut search_queue: VecDeque<Entity> = VecDeque::from([a]);
+    while let Some(sv_ent) = search_queue.pop_front() {
+        let Ok((_, sv_vert)) = query.get(sv_ent) else {continue;};
+        let sources = incoming.get(&sv_ent).into_iter().flatten()
+        .map(|(source_ent, _)| *source_ent)
+        .filter(|source_ent| has_edge(*source_ent, sv_ent));
+        for neighbour_ent in sv_vert.get_neighbours().into_iter().chain(sources) {
+            if neighbour_ent == b {return Ok(false);}
+            if !query.contains(neighbour_ent) || !reached.insert(neighbour_ent) {continue;}
+            search_queue.push_back(neighbour_ent);
+        }
+    }
+
+    //the vertices are no longer connected, so a's side becomes a new component
+    let fresh_label = labels.values().max().map_or(0, |max_label| max_label + 1);
+    for ent in reached {
+        labels.insert(ent, fresh_label);
+    }
+    Ok(true)
+}
+
+/// Summary of the shape of a graph, as returned by [`graph_stats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GraphStats {
+    pub vertex_count: usize,
+    /// The total number of edges, ie the sum of the out-degrees of every vertex
+    pub edge_count: usize,
+    pub max_out_degree: usize,
+    pub has_negative_weight: bool,
+    pub has_self_loop: bool
+}
+
+impl Display for GraphStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} vertices, {} edges (max out-degree {})", self.vertex_count, self.edge_count, self.max_out_degree)?;
+        if self.has_negative_weight {write!(f, ", has negative weights")?;}
+        if self.has_self_loop {write!(f, ", has self-loops")?;}
+        Ok(())
+    }
+}
+
+/// Computes basic statistics about the graph in a single pass over the query
+/// 
+/// Edges to entities outside of the query are still counted, as they are part of each vertex's out-degree.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that logs the shape of the map once it has loaded
+/// fn log_map_stats(tiles: Query<(Entity, &VertexType)>) {
+///     info!("Loaded map: {}", graph_stats(&tiles));
+/// }
+/// ```
+pub fn graph_stats<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> GraphStats {
+    query.iter().fold(GraphStats::default(), |mut stats, (ent, vert)| {
+        let edges = vert.get_neighbours_with_weight();
+        stats.vertex_count += 1;
+        stats.edge_count += edges.len();
+        stats.max_out_degree = stats.max_out_degree.max(edges.len());
+        stats.has_negative_weight |= edges.iter().any(|&(_, weight)| weight < 0.0);
+        stats.has_self_loop |= edges.iter().any(|&(neighbour_ent, _)| neighbour_ent == ent);
+        stats
+    })
+}
+
+
+/// Groups the vertices into strongly connected components, returned in topological order
+/// 
+/// Two vertices are in the same strongly connected component if there is a directed path from each to the other. The components are returned 
+/// in a topological order of the [condensation](condensation), so any edge between two different components goes from an earlier component to a later one.
+/// Edges to entities outside the query are ignored. Computed with Tarjan's algorithm.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds the areas of a one-way road network that can be left and returned to
+/// fn find_districts(roads: Query<(Entity, &RoadVertex)>) {
+///     for district in strongly_connected_components(&roads) {
+///         println!("District with {} junctions", district.len());
+///     }
+/// }
+/// ```
+pub fn strongly_connected_components<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<Vec<Entity>> {
+    //only follow edges between vertices in the query
+    let adjacency: HashMap<Entity, Vec<Entity>> = query.iter()
+    .map(|(ent, vert)| (ent, vert.get_neighbours().into_iter().filter(|neighbour_ent| query.contains(*neighbour_ent)).collect()))
+    .collect();
+
+    //the order each vertex was first found, and the earliest found vertex reachable from it that is still on the stack
+    let mut found_order: HashMap<Entity, usize> = HashMap::new();
+    let mut lowest_link: HashMap<Entity, usize> = HashMap::new();
+    let mut tarjan_stack: Vec<Entity> = Vec::new();
+    let mut on_stack: HashSet<Entity> = HashSet::new();
+    let mut components: Vec<Vec<Entity>> = Vec::new();
+
+    for (root_ent, _) in query.iter() {
+        if found_order.contains_key(&root_ent) {continue;}
+
+        //the vertices currently being searched, and how many of their neighbours have been searched
+        let mut call_stack: Vec<(Entity, usize)> = vec![(root_ent, 0)];
+        found_order.insert(root_ent, 0);
+        lowest_link.insert(root_ent, 0);
+        tarjan_stack.push(root_ent);
+        on_stack.insert(root_ent);
+
+        while let Some(&mut (sv_ent, ref mut neighbours_searched)) = call_stack.last_mut() {
+            if let Some(&neighbour_ent) = adjacency[&sv_ent].get(*neighbours_searched) {
+                *neighbours_searched += 1;
+                if let Some(&neighbour_order) = found_order.get(&neighbour_ent) {
+                    if on_stack.contains(&neighbour_ent) {
+                        let sv_link = lowest_link.get_mut(&sv_ent).unwrap();
+                        *sv_link = (*sv_link).min(neighbour_order);
+                    }
+                } else {
+                    let order = found_order.len();
+                    found_order.insert(neighbour_ent, order);
+                    lowest_link.insert(neighbour_ent, order);
+                    tarjan_stack.push(neighbour_ent);
+                    on_stack.insert(neighbour_ent);
+                    call_stack.push((neighbour_ent, 0));
+                }
+                continue;
+            }
+
+            //all neighbours have been searched, so pass the link back to the previous vertex
+            call_stack.pop();
+            let sv_link = lowest_link[&sv_ent];
+            if let Some(&(prev_ent, _)) = call_stack.last() {
+                let prev_link = lowest_link.get_mut(&prev_ent).unwrap();
+                *prev_link = (*prev_link).min(sv_link);
+            }
+
+            //if this vertex is the first found in its component, the component is everything above it on the stack
+            if sv_link == found_order[&sv_ent] {
+                let mut component = Vec::new();
+                while let Some(member_ent) = tarjan_stack.pop() {
+                    on_stack.remove(&member_ent);
+                    component.push(member_ent);
+                    if member_ent == sv_ent {break;}
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    //Tarjan's algorithm finds the components in reverse topological order
+    components.reverse();
+    components
+}
+
+/// Returns the bridges of the graph, ie the edges whose removal would disconnect the two vertices they join
+/// 
+/// Edges are treated as undirected, so an edge in each direction between two vertices counts as a single edge, and self-loops are ignored. 
+/// The bridges are the single points of failure of a network, as every path between the two sides of a bridge crosses it. Each bridge is
+/// returned once, as the pair of vertices it joins, in the order the depth-first search crossed it. Edges to entities outside the query are
+/// ignored. Computed with the same low-link depth-first search as Tarjan's algorithm for [`strongly_connected_components`].
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that marks the roads that would cut the map in two if destroyed
+/// fn mark_critical_roads(
+///     junctions: Query<(Entity, &JunctionVertex)>,
+///     mut critical: ResMut<CriticalRoads>
+/// ) {
+///     critical.0 = bridges(&junctions);
+/// }
+/// ```
+pub fn bridges<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<(Entity, Entity)> {
+    let adjacency = undirected_adjacency(query);
+
+    //the order each vertex was first found, and the earliest found vertex reachable from it without using the edge it was found along
+    let mut found_order: HashMap<Entity, usize> = HashMap::new();
+    let mut lowest_link: HashMap<Entity, usize> = HashMap::new();
+    let mut bridges: Vec<(Entity, Entity)> = Vec::new();
+
+    for (root_ent, _) in query.iter() {
+        if found_order.contains_key(&root_ent) {continue;}
+
+        //the vertices currently being searched, the vertex each was found from, and how many of their neighbours have been searched
+        let mut call_stack: Vec<(Entity, Option<Entity>, usize)> = vec![(root_ent, None, 0)];
+        found_order.insert(root_ent, found_order.len());
+        lowest_link.insert(root_ent, found_order[&root_ent]);
+
+        while let Some(&mut (sv_ent, parent_ent, ref mut neighbours_searched)) = call_stack.last_mut() {
+            if let Some(&neighbour_ent) = adjacency[&sv_ent].get(*neighbours_searched) {
+                *neighbours_searched += 1;
+                //going straight back along the edge the vertex was found from is not another route
+                if Some(neighbour_ent) == parent_ent {continue;}
+                if let Some(&neighbour_order) = found_order.get(&neighbour_ent) {
+                    let sv_link = lowest_link.get_mut(&sv_ent).unwrap();
+                    *sv_link = (*sv_link).min(neighbour_order);
+                } else {
+                    let order = found_order.len();
+                    found_order.insert(neighbour_ent, order);
+                    lowest_link.insert(neighbour_ent, order);
+                    call_stack.push((neighbour_ent, Some(sv_ent), 0));
+                }
+                continue;
+            }
+
+            //all neighbours have been searched, so pass the link back to the previous vertex
+            call_stack.pop();
+            let Some(parent_ent) = parent_ent else {continue;};
+            let sv_link = lowest_link[&sv_ent];
+            let parent_link = lowest_link.get_mut(&parent_ent).unwrap();
+            *parent_link = (*parent_link).min(sv_link);
+
+            //if nothing below this vertex reaches back above it, the edge it was found along is the only way in
+            if sv_link == found_order[&sv_ent] {bridges.push((parent_ent, sv_ent));}
+        }
+    }
+    bridges
+}
+
+/// Returns the k-core of the graph, ie the largest set of vertices in which every vertex has at least `k` neighbours that are also in the set
+/// 
+/// Edges are treated as undirected, so the degree of a vertex is the number of distinct vertices it has an edge to or from, ignoring self-loops
+/// and edges to entities outside the query. The core is found by repeatedly removing every vertex with fewer than `k` neighbours left, until
+/// none remain to be removed. The cores pick out the most densely connected parts of the graph, with larger values of `k` giving smaller, denser
+/// cores, and the set may be empty. Every vertex is in the 0-core, and the 1-core drops only the isolated vertices.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds the tightly knit groups of a social network
+/// fn find_close_friends(
+///     people: Query<(Entity, &FriendsVertex)>,
+///     mut groups: ResMut<CloseGroups>
+/// ) {
+///     groups.0 = k_core(&people, 3);
+/// }
+/// ```
+pub fn k_core<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    k: usize
+) -> HashSet<Entity> {
+    let adjacency = undirected_adjacency(query);
+    let mut degree: HashMap<Entity, usize> = adjacency.iter().map(|(&ent, neighbours)| (ent, neighbours.len())).collect();
+
+    //the vertices that have been removed from the core, but whose neighbours haven't yet had their degree reduced
+    let mut to_remove: Vec<Entity> = degree.iter().filter(|(_, &deg)| deg < k).map(|(&ent, _)| ent).collect();
+    let mut removed: HashSet<Entity> = to_remove.iter().copied().collect();
+
+    while let Some(ent) = to_remove.pop() {
+        for neighbour_ent in &adjacency[&ent] {
+            if removed.contains(neighbour_ent) {continue;}
+            let neighbour_degree = degree.get_mut(neighbour_ent).unwrap();
+            *neighbour_degree -= 1;
+            if *neighbour_degree < k {
+                removed.insert(*neighbour_ent);
+                to_remove.push(*neighbour_ent);
+            }
+        }
+    }
+
+    adjacency.into_keys().filter(|ent| !removed.contains(ent)).collect()
+}
+
+
+/// Computes the condensation of the graph, where each strongly connected component is collapsed into a single vertex
+/// 
+/// Returns the components, as given by [`strongly_connected_components`], and the set of edges between them by component index.
+/// Edges within a component are dropped, and parallel edges between two components are combined. The condensation is always acyclic, and
+/// as the components are in topological order, every edge `(from, to)` has `from < to`. This makes it suitable for algorithms that require a DAG
+/// on graphs that may contain cycles.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds the districts of a one-way road network that can't be reached from any other
+/// fn find_isolated_districts(roads: Query<(Entity, &RoadVertex)>) {
+///     let (districts, links) = condensation(&roads);
+///     for (ind, district) in districts.iter().enumerate() {
+///         if !links.iter().any(|&(_, to)| to == ind) {
+///             println!("District {:?} has no way in", district);
+///         }
+///     }
+/// }
+/// ```
+pub fn condensation<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> (Vec<Vec<Entity>>, HashSet<(usize, usize)>) {
+    let components = strongly_connected_components(query);
+    let component_of: HashMap<Entity, usize> = components.iter().enumerate()
+    .flat_map(|(ind, component)| component.iter().map(move |&ent| (ent, ind)))
+    .collect();
+
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    for (ent, vert) in query.iter() {
+        let from = component_of[&ent];
+        for neighbour_ent in vert.get_neighbours() {
+            let Some(&to) = component_of.get(&neighbour_ent) else {continue;};
+            if from != to {edges.insert((from, to));}
+        }
+    }
+    (components, edges)
+}
+
+
+/// The changes between two versions of a graph, as returned by [`diff_graphs`]
+/// 
+/// Edges are identified by the [`GraphLabel`] values of their source and destination vertices, and each list is sorted by those values.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphDiff {
+    /// Edges only in the new graph, with their weight
+    pub added: Vec<(usize, usize, f32)>,
+    /// Edges only in the old graph
+    pub removed: Vec<(usize, usize)>,
+    /// Edges in both graphs whose weight has changed, with their new weight
+    pub reweighted: Vec<(usize, usize, f32)>
+}
+
+impl GraphDiff {
+    /// Whether the two graphs had identical edges
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reweighted.is_empty()
+    }
+}
+
+/// Compares two versions of a graph, returning the edges that were added, removed or had their weight changed
+/// 
+/// Vertices are matched between the two graphs by their [`GraphLabel`], which must therefore be unique and stable between versions, while the
+/// entities themselves may differ. Edges to entities outside of a query are ignored, and if a vertex has several edges to the same destination 
+/// only the last is compared. Weights are compared exactly.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that sends only the changed roads to clients
+/// fn sync_roads(
+///     previous: Res<PreviousRoads>,
+///     roads: Query<(&GraphLabel, &RoadVertex)>,
+///     mut server: ResMut<Server>
+/// ) {
+///     let diff = diff_graphs(&previous.query(), &roads);
+///     if !diff.is_empty() {server.broadcast(diff);}
+/// }
+/// ```
+pub fn diff_graphs<V: GraphVertex>(
+    old: &Query<(&GraphLabel, &V)>,
+    new: &Query<(&GraphLabel, &V)>
+) -> GraphDiff {
+    let old_edges = labelled_edges(old);
+    let mut new_edges = labelled_edges(new);
+
+    let mut diff = GraphDiff::default();
+    for (edge, old_weight) in old_edges {
+        match new_edges.remove(&edge) {
+            None => diff.removed.push(edge),
+            Some(new_weight) if new_weight != old_weight => diff.reweighted.push((edge.0, edge.1, new_weight)),
+            Some(_) => {}
+        }
+    }
+    //anything left was not in the old graph
+    diff.added = new_edges.into_iter().map(|((from, to), weight)| (from, to, weight)).collect();
+
+    diff.added.sort_by_key(|&(from, to, _)| (from, to));
+    diff.removed.sort();
+    diff.reweighted.sort_by_key(|&(from, to, _)| (from, to));
+    diff
+}
+
+/// Helper function giving the neighbours of every vertex with edges treated as undirected, without repeats, self-loops or entities outside the query
+fn undirected_adjacency<V: GraphVertex>(query: &Query<(Entity, &V)>) -> HashMap<Entity, Vec<Entity>> {
+    let mut adjacency: HashMap<Entity, Vec<Entity>> = query.iter().map(|(ent, _)| (ent, Vec::new())).collect();
+    let mut edges: HashSet<(Entity, Entity)> = HashSet::new();
+    for (ent, neighbour_ent, _) in all_edges(query) {
+        if neighbour_ent == ent || !adjacency.contains_key(&neighbour_ent) {continue;}
+        let edge = if ent < neighbour_ent {(ent, neighbour_ent)} else {(neighbour_ent, ent)};
+        if !edges.insert(edge) {continue;}
+        adjacency.get_mut(&ent).unwrap().push(neighbour_ent);
+        adjacency.get_mut(&neighbour_ent).unwrap().push(ent);
+    }
+    adjacency
+}
+
+/// Helper function collecting the edges of the graph by the labels of their vertices
+fn labelled_edges<V: GraphVertex>(query: &Query<(&GraphLabel, &V)>) -> HashMap<(usize, usize), f32> {
+    let mut edges = HashMap::new();
+    for (label, vert) in query.iter() {
+        for (neighbour_ent, weight) in vert.get_neighbours_with_weight() {
+            let Ok((neighbour_label, _)) = query.get(neighbour_ent) else {continue;};
+            edges.insert((label.value, neighbour_label.value), weight);
+        }
+    }
+    edges
+}
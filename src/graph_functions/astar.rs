@@ -5,7 +5,7 @@ use priority_queue::PriorityQueue;
 
 use crate::graph_vertex::GraphVertex;
 
-use super::{GraphError, GraphPath, Heuristic, PathWeight, VisitedNodes};
+use super::{helper::{check_start_edges, check_tolerance, get_start, search_preamble}, implicit::{SearchStep, SteppedAStar}, GraphError, GraphPath, Heuristic, PathWeight};
 
 
 /// TODO
@@ -32,56 +32,754 @@ where
     C: Component,
     F: Fn(&C, &C) -> Heuristic
 {
+    a_star_search_weighted_heuristic(query, start_ent, end_ent, heuristic_determiner, 1.0)
+}
+
+/// Runs weighted A*, finding a path between two vertices with the heuristic inflated by a factor of `epsilon`, returning the path in **reverse order**
+/// 
+/// A* on the directed graph with vertices in the provided query, where the search queue is ordered by `g + epsilon * h`, with `g` the distance
+/// from the start vertex and `h` the provided heuristic. Given an admissible heuristic (one that never overestimates the remaining distance) and
+/// `epsilon >= 1`, the resulting path has a total weight of at most `epsilon` times that of the shortest path. Larger values of `epsilon` 
+/// cause the search to favour vertices the heuristic believes are close to the end, typically expanding far fewer vertices.
+/// With `epsilon = 1` this is exactly [`a_star_search`].
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds a good enough route for a large number of units, accepting paths up to 50% longer than the shortest
+/// fn fast_unit_routes(
+///     units: Query<(&UnitTile, &TargetTile)>,
+///     tiles: Query<(&VertexType, &Transform)>
+/// ) {
+///     let heuristic = |a: &Transform, b: &Transform| Heuristic{value: a.translation.distance(b.translation)};
+///     for (start, end) in units.iter() {
+///         match a_star_search_weighted_heuristic(&tiles, start.0, end.0, heuristic, 1.5){
+///             Ok(path) => println!("Found a route within 1.5x of optimal: {}", path),
+///             Err(_) => println!("No route found!")
+///         }
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`a_star_search`]: For an A* search that is guaranteed to find a shortest path given an admissible heuristic
+/// 
+/// [`dijkstra_search`]: For a search that finds a shortest path without a heuristic
+pub fn a_star_search_weighted_heuristic<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    epsilon: f32
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic
+{
+    a_star_checked(query, start_ent, end_ent, heuristic_determiner, epsilon, None, TieBreak::default(), f32::INFINITY, 0.0)
+}
+
+/// Runs A* that gives up after searching the given number of vertices, returning the path in **reverse order**
+/// 
+/// Identical to [`a_star_search`], except that once `max_expansions` vertices have been searched without reaching the end vertex, 
+/// [`GraphError::BudgetExceeded`] is returned. A budget of [`None`] never gives up.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// [`GraphError::BudgetExceeded`]: If the search was stopped after searching `max_expansions` vertices.
+pub fn a_star_search_budgeted<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    max_expansions: Option<usize>
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic
+{
+    a_star_checked(query, start_ent, end_ent, heuristic_determiner, 1.0, max_expansions, TieBreak::default(), f32::INFINITY, 0.0)
+}
+
+/// Runs A* between two vertices, choosing between vertices with equal priority using the given [`TieBreak`], returning the path in **reverse order**
+/// 
+/// Identical to [`a_star_search`], which uses [`TieBreak::HigherG`], except for the order vertices of equal `g + h` are searched in.
+/// The tie break never changes the weight of the path found, only which of several equally short paths is found and how many vertices are searched.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+pub fn a_star_search_with_tie_break<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    tie_break: TieBreak
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic
+{
+    a_star_checked(query, start_ent, end_ent, heuristic_determiner, 1.0, None, tie_break, f32::INFINITY, 0.0)
+}
+
+/// Runs A*, treating distances within `tolerance` of each other as equal, returning the path in **reverse order**
+/// 
+/// Identical to [`a_star_search`], except that a vertex only takes a new path when it is shorter than the path already found by more than
+/// `tolerance`, as in [`dijkstra_search_with_tolerance`](super::dijkstra::dijkstra_search_with_tolerance). This keeps near ties between routes
+/// whose weights are computed from floats from depending on rounding. [`a_star_search`] uses a tolerance of `0.0`.
+/// 
+/// The path found may be longer than the shortest path by up to `tolerance` for each vertex on it, so the tolerance should be far smaller 
+/// than any edge weight. It must not be negative, as then a path could replace one that is no shorter.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::InvalidTolerance`]: If `tolerance` is negative or NaN.
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+pub fn a_star_search_with_tolerance<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    tolerance: f32
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic
+{
+    check_tolerance(tolerance)?;
+    a_star_checked(query, start_ent, end_ent, heuristic_determiner, 1.0, None, TieBreak::default(), f32::INFINITY, tolerance)
+}
+
+/// Runs A* between two vertices, ignoring any route longer than `max_cost`, returning the path in **reverse order**
+/// 
+/// Identical to [`a_star_search`], except that a vertex is only queued if the path to it is no longer than `max_cost`, so the search never 
+/// strays beyond that distance from the start vertex. This finds a route no longer than `max_cost` if one exists, and gives up early otherwise,
+/// with the number of vertices searched bounded by how many lie within `max_cost` of the start.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path no longer than `max_cost` could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that only lets a unit move to the clicked tile if it has enough stamina to get there
+/// fn move_to_click(
+///     mut unit: Query<(&CurrentTile, &Stamina, &mut UnitPath)>,
+///     clicked: Res<ClickedTile>,
+///     tiles: Query<(&VertexType, &Transform)>
+/// ) {
+///     let (tile, stamina, mut unit_path) = unit.single_mut();
+///     let heuristic = |a: &Transform, b: &Transform| Heuristic{value: a.translation.distance(b.translation)};
+///     unit_path.0 = a_star_bounded(&tiles, tile.0, clicked.0, heuristic, stamina.0).ok();
+/// }
+/// ```
+pub fn a_star_bounded<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    max_cost: f32
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic
+{
+    a_star_checked(query, start_ent, end_ent, heuristic_determiner, 1.0, None, TieBreak::default(), max_cost, 0.0)
+}
+
+/// Runs A* with each edge weight given by the provided function, returning the path in **reverse order**
+/// 
+/// Identical to [`a_star_search`], except the weight of each edge is `weight_of(from, to)`, using [`GraphVertex::get_neighbours_weighted_by`], 
+/// rather than the weight stored in the vertex. This allows the vertex to only model connectivity, with the weights kept elsewhere, eg as 
+/// dynamic costs in a resource.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If `weight_of` gives an edge a negative weight
+/// 
+/// # See also
+/// 
+/// [`dijkstra_search_with_weights`](super::dijkstra::dijkstra_search_with_weights): For the same search without a heuristic
+pub fn a_star_search_with_weights<V, C, F, W>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    weight_of: W
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic,
+    W: Fn(Entity, Entity) -> f32
+{
+    a_star_checked_by(query, start_ent, end_ent, heuristic_determiner, 1.0, None, TieBreak::default(), f32::INFINITY, 0.0, |ent, vert| vert.get_neighbours_weighted_by(ent, &weight_of))
+}
+
+/// Weighted A*, which gives up after searching `max_expansions` vertices, chooses between vertices of equal priority with `tie_break`,
+/// never queues paths longer than `max_cost`, and only replaces paths with ones shorter by more than `tolerance`
+#[allow(clippy::too_many_arguments)]
+fn a_star_checked<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    epsilon: f32,
+    max_expansions: Option<usize>,
+    tie_break: TieBreak,
+    max_cost: f32,
+    tolerance: f32
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic
+{
+    a_star_checked_by(query, start_ent, end_ent, heuristic_determiner, epsilon, max_expansions, tie_break, max_cost, tolerance, |_, vert| vert.get_neighbours_with_weight())
+}
+
+/// Identical to [`a_star_checked`], except the weighted neighbours of each searched vertex are given by `neighbours_of`
+#[allow(clippy::too_many_arguments)]
+fn a_star_checked_by<V, C, F, N>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F,
+    epsilon: f32,
+    max_expansions: Option<usize>,
+    tie_break: TieBreak,
+    max_cost: f32,
+    tolerance: f32,
+    neighbours_of: N
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic,
+    N: Fn(Entity, &V) -> Vec<(Entity, f32)>
+{
+    if let Some(path) = search_preamble(query, start_ent, end_ent, 0.0)? {return Ok(path);}
+    check_start_edges(get_start(query, start_ent)?.0)?;
     let (_, end_data)= query.get(end_ent)?;
 
-    let mut visited = VisitedNodes::new_from_start(start_ent);
+    let heuristic = |data: &C| Heuristic{value: epsilon * heuristic_determiner(data, end_data).value};
+    let mut frontier = AStarFrontier::new(start_ent, tie_break, max_expansions);
+    frontier.search.max_cost = PathWeight::new(max_cost);
+    frontier.search.tolerance = tolerance;
+    frontier.run(query, &heuristic, |ent| ent == end_ent, &neighbours_of)
+}
 
-    let mut minimal_dist : HashMap<Entity, (PathWeight, Heuristic)> = HashMap::new();
-    minimal_dist.insert(start_ent, (PathWeight{weight: 0.0}, Heuristic{value: 0.0}));
+/// Runs A* towards the nearest of several goal vertices, returning the path to it in **reverse order**
+/// 
+/// The heuristic is given only the data of the vertex being estimated, and should estimate the distance from it to the nearest goal, eg the
+/// minimum of the distances to each goal. The search ends as soon as any goal is searched, so given an admissible heuristic the path is to the
+/// closest goal, and is a shortest path to it. Ties between equally close goals are resolved by whichever is searched first.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If any of the provided goal entities do not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start vertex is not a goal, and has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found to any goal, including when there are no goals.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that sends a thirsty unit to the closest well
+/// fn find_water(
+///     unit: Query<&CurrentTile, With<Thirsty>>,
+///     wells: Res<Wells>,
+///     tiles: Query<(&VertexType, &Transform)>
+/// ) {
+///     let heuristic = |pos: &Transform| Heuristic{value: wells.positions.iter()
+///         .map(|well| pos.translation.distance(*well))
+///         .fold(f32::INFINITY, f32::min)};
+///     let route = a_star_nearest_goal(&tiles, unit.single().0, &wells.entities, heuristic);
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`a_star_search`]: For A* with a single goal vertex
+pub fn a_star_nearest_goal<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    goals: &[Entity],
+    heuristic_determiner: F
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C) -> Heuristic
+{
+    //check every goal is valid, and for an instant finish
+    for &goal_ent in goals {
+        if let Some(path) = search_preamble(query, start_ent, goal_ent, 0.0)? {return Ok(path);}
+    }
+    if goals.is_empty() {return Err(GraphError::NoPath);}
+    check_start_edges(get_start(query, start_ent)?.0)?;
 
-    let mut search_queue: PriorityQueue<Entity , Reverse<PathWeight>> = PriorityQueue::new();
-    search_queue.push(start_ent, Reverse(PathWeight{weight: 0.0}));
+    //the first goal searched is the closest, as with a single end vertex
+    AStarFrontier::new(start_ent, TieBreak::default(), None)
+    .run(query, &heuristic_determiner, |ent| goals.contains(&ent), &|_, vert: &V| vert.get_neighbours_with_weight())
+}
 
-    while let Some((sv_ent, _)) = search_queue.pop() {
-        //check if we are currently searching the end vertex, as this implies we have already found the minimum path
-        if sv_ent == end_ent {return Ok(visited.determine_path_weighted(sv_ent).expect("The created path should be valid"));}
+/// Runs greedy best-first search, always searching the vertex the heuristic estimates is closest to the end vertex, returning the path in **reverse order**
+/// 
+/// This is A* with the distance from the start vertex left out of each vertex's priority, so the search heads straight for the end vertex and
+/// only backs off when it runs into a dead end. Each vertex keeps the first path found to it. The path found is **not** necessarily a shortest
+/// path, and can be much longer than one on maps with obstacles, but far fewer vertices are typically searched than by [`a_star_search`], making
+/// this suited to fast, approximate pathing. Each vertex of the path is paired with its distance from the start along the path found.
+/// 
+/// The heuristic is given only the data of the vertex being estimated, and should estimate the distance from it to the end vertex.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that sends a swarm of insects roughly towards the player, where the exact route doesn't matter
+/// fn swarm_towards_player(
+///     player: Query<(&CurrentTile, &Transform), With<Player>>,
+///     mut insects: Query<(&CurrentTile, &mut InsectPath), Without<Player>>,
+///     tiles: Query<(&VertexType, &Transform)>
+/// ) {
+///     let (player_tile, player_pos) = player.single();
+///     let heuristic = |pos: &Transform| Heuristic{value: pos.translation.distance(player_pos.translation)};
+///     for (tile, mut insect_path) in insects.iter_mut() {
+///         insect_path.0 = greedy_best_first(&tiles, tile.0, player_tile.0, heuristic).ok();
+///     }
+/// }
+/// ```
+pub fn greedy_best_first<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C) -> Heuristic
+{
+    if let Some(path) = search_preamble(query, start_ent, end_ent, 0.0)? {return Ok(path);}
+    check_start_edges(get_start(query, start_ent)?.0)?;
 
-        let Ok((sv_vert, sv_data)) = query.get(sv_ent) else {continue;};
+    AStarFrontier::new_greedy(start_ent)
+    .run(query, &heuristic_determiner, |ent| ent == end_ent, &|_, vert: &V| vert.get_neighbours_with_weight())
+}
 
-        let sv_dist = minimal_dist.get(&sv_ent).unwrap().0; //true minimum distance to this vertex
+/// Runs A* from both ends at once, meeting in the middle, returning the path in **reverse order**
+/// 
+/// A forward search from the start vertex and a backward search from the end vertex, following edges in reverse, take turns to search a vertex,
+/// with the search whose next vertex has the lower priority going next. Each vertex is prioritised with the average of the heuristic to the end
+/// vertex and the heuristic from the start vertex, with opposite signs in the two searches, so that both searches agree on how promising each
+/// vertex is. The search stops once the best path found through a vertex reached by both searches is no longer than the sum of the two searches'
+/// lowest priorities, at which point no shorter path can exist. On long paths this typically searches fewer vertices than [`a_star_search`].
+/// 
+/// Unlike [`a_star_search`], the heuristic must be **consistent** in both directions for the path to be a shortest path, ie for every edge from 
+/// `u` to `v` with weight `w`, and every vertex `x`, both `h(u, x) <= w + h(v, x)` and `h(x, v) <= w + h(x, u)`. Heuristics given by a distance 
+/// between positions, such as the straight line distance, are consistent as long as no edge is shorter than that distance. The query must 
+/// contain the entity of each vertex, as the incoming edges of every vertex are collected before searching.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds a route across a large open world
+/// fn long_route(
+///     journey: Res<Journey>,
+///     tiles: Query<(Entity, &VertexType, &Transform)>
+/// ) {
+///     let heuristic = |a: &Transform, b: &Transform| Heuristic{value: a.translation.distance(b.translation)};
+///     match bidirectional_a_star(&tiles, journey.start, journey.end, heuristic) {
+///         Ok(path) => println!("Found route of length {}", path.total_weight()),
+///         Err(_) => println!("No route found!")
+///     }
+/// }
+/// ```
+pub fn bidirectional_a_star<V, C, F>(
+    query: &Query<(Entity, &V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    heuristic_determiner: F
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, &C) -> Heuristic
+{
+    if let Some(path) = search_preamble(query, start_ent, end_ent, 0.0)? {return Ok(path);}
+    let (_, start_vert, start_data) = get_start(query, start_ent)?;
+    check_start_edges(start_vert)?;
+    let (_, _, end_data) = query.get(end_ent)?;
+
+    //the edges followed by the backward search
+    let mut incoming: HashMap<Entity, Vec<(Entity, f32)>> = HashMap::new();
+    for (ent, vert, _) in query.iter() {
+        for (neighbour_ent, edge_weight) in vert.get_neighbours_with_weight() {
+            incoming.entry(neighbour_ent).or_default().push((ent, edge_weight));
+        }
+    }
+
+    //the potential of a vertex is added to its priority in the forward search and subtracted in the backward search
+    let potential = |data: &C| (heuristic_determiner(data, end_data).value - heuristic_determiner(start_data, data).value) / 2.0;
+    let signs = [1.0, -1.0];
+    let mut sides = [
+        SearchSide::new(start_ent, PathWeight::new(potential(start_data))),
+        SearchSide::new(end_ent, PathWeight::new(-potential(end_data)))
+    ];
+
+    //the length of the shortest path found so far, and the vertex the two searches met at along it
+    let mut best: Option<(PathWeight, Entity)> = None;
 
-        //loop over this vertex's neighbours
-        for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight(){
+    while let (Some((_, &Reverse((forward_top, _)))), Some((_, &Reverse((backward_top, _))))) = (sides[0].queue.peek(), sides[1].queue.peek()) {
+        if best.is_some_and(|(best_dist, _)| forward_top + backward_top >= best_dist) {break;}
+
+        //search from whichever side has the more promising vertex
+        let side = if forward_top <= backward_top {0} else {1};
+        let (sv_ent, _) = sides[side].queue.pop().expect("The queue was just checked to not be empty");
+        let sv_dist = sides[side].found[&sv_ent].0;
+        let edges = if side == 0 {
+            query.get(sv_ent).map(|(_, vert, _)| vert.get_neighbours_with_weight()).unwrap_or_default()
+        } else {
+            incoming.get(&sv_ent).cloned().unwrap_or_default()
+        };
+
+        for (neighbour_ent, edge_weight) in edges {
+            if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+            let Ok((_, _, neighbour_data)) = query.get(neighbour_ent) else {continue;};
 
-            //Determine the distance to this neighbour via the path to the search vertex
             let total_dist = sv_dist + edge_weight;
-            
-            //check if we have visited this vertex before
-            //if so, compare the cardinalities to see if we should update
-            if let Some((neighbour_dist, neighbour_heuristic)) = minimal_dist.get_mut(&neighbour_ent) {
-                //check if the vertex was visited already at a closer distance
-                //if so we ignore this vertex
-                if total_dist > *neighbour_dist {continue;}
-                //otherwise update the vertex's distance, previous vertex and priority in the queue
-                //we do not need to recalculate the heuristic in this case
-                visited.set_previous(neighbour_ent, sv_ent, total_dist.weight); 
-                todo!("need to also change the value inside visited nodes at this point");
-                search_queue.change_priority(&neighbour_ent, Reverse(total_dist + *neighbour_heuristic));
-                *neighbour_dist = total_dist;
-            } else {
-                //determine the heuristic of this new value
-                let heuristic = heuristic_determiner(&sv_data, &end_data);
-                //otherwise the vertex hasnt been visited before and so we add it to the queue, visited and min distances
-                visited.insert(neighbour_ent, sv_ent, 0, total_dist.weight);
-                search_queue.push(neighbour_ent, Reverse(total_dist + heuristic));
-                minimal_dist.insert(neighbour_ent, (total_dist, heuristic));
-                
+            if sides[side].found.get(&neighbour_ent).is_some_and(|(dist, _)| total_dist >= *dist) {continue;}
+            sides[side].found.insert(neighbour_ent, (total_dist, Some(sv_ent)));
+            //pushing updates the priority if the vertex is still queued, or reopens it if it was already searched
+            let priority = total_dist + signs[side] * potential(neighbour_data);
+            sides[side].queue.push(neighbour_ent, Reverse((priority, neighbour_ent.index())));
+
+            //if the other search has reached this vertex, there is a path through it
+            let Some(&(other_dist, _)) = sides[1 - side].found.get(&neighbour_ent) else {continue;};
+            let through_dist = total_dist + other_dist;
+            if best.is_some_and(|(best_dist, _)| through_dist >= best_dist) {continue;}
+            best = Some((through_dist, neighbour_ent));
+        }
+    }
+
+    let Some((best_dist, meeting_ent)) = best else {return Err(GraphError::NoPath);};
+
+    //the backward search's previous vertices lead from the meeting vertex to the end vertex
+    let mut path = Vec::new();
+    let mut to_follow = Some(meeting_ent);
+    while let Some(current) = to_follow {
+        let (backward_dist, next) = sides[1].found[&current];
+        path.push((current, best_dist.value() - backward_dist.value()));
+        to_follow = next;
+    }
+    path.reverse();
+
+    //while the forward search's lead back to the start vertex
+    let mut to_follow = sides[0].found[&meeting_ent].1;
+    while let Some(current) = to_follow {
+        let (forward_dist, previous) = sides[0].found[&current];
+        path.push((current, forward_dist.value()));
+        to_follow = previous;
+    }
+    Ok(GraphPath::new(path))
+}
+
+/// One direction of [`bidirectional_a_star`]
+struct SearchSide {
+    /// The distance to each found vertex from this side's first vertex, and the vertex it was found from
+    found: HashMap<Entity, (PathWeight, Option<Entity>)>,
+    queue: PriorityQueue<Entity, Reverse<(PathWeight, u32)>>
+}
+
+impl SearchSide {
+    fn new(first_ent: Entity, priority: PathWeight) -> Self {
+        let mut found = HashMap::new();
+        found.insert(first_ent, (PathWeight{weight: 0.0}, None));
+        let mut queue = PriorityQueue::new();
+        queue.push(first_ent, Reverse((priority, first_ent.index())));
+        Self{found, queue}
+    }
+}
+
+/// An A* search that is run one vertex at a time, each call to [`next`](Iterator::next) searching a single vertex and returning its entity
+/// 
+/// This runs the same search as [`a_star_search`], on the same engine, but can be paused between vertices, eg to only search a fixed number of vertices each frame 
+/// or to draw the progress of a long search. While running, [`current_best_path`](AStarIter::current_best_path) gives the most promising 
+/// partial path found so far. Once the iterator returns [`None`], [`result`](AStarIter::result) gives the final path or error.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that continues a long search by at most 100 vertices each frame, drawing the current best path
+/// fn stepped_search(
+///     mut search: Local<Option<AStarIter<...>>>,
+///     mut gizmos: Gizmos,
+///     ...
+/// ) {
+///     let Some(search) = search.as_mut() else {return;};
+///     search.by_ref().take(100).for_each(drop);
+///     match search.result() {
+///         Some(Ok(path)) => println!("Found path {}", path),
+///         Some(Err(_)) => println!("No path!"),
+///         None => draw_path(&mut gizmos, search.current_best_path())
+///     }
+/// }
+/// ```
+pub struct AStarIter<'a, 'w, 's, V: GraphVertex, C: Component, F: Fn(&C, &C) -> Heuristic> {
+    query: &'a Query<'w, 's, (&'static V, &'static C)>,
+    end_ent: Entity,
+    end_data: &'a C,
+    heuristic_determiner: F,
+    frontier: AStarFrontier,
+    result: Option<Result<GraphPath<f32>, GraphError>>
+}
+
+impl<'a, 'w, 's, V: GraphVertex, C: Component, F: Fn(&C, &C) -> Heuristic> AStarIter<'a, 'w, 's, V, C, F> {
+    /// Prepares an A* search between the two vertices, without searching any vertices yet
+    /// 
+    /// # Errors
+    /// 
+    /// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+    /// 
+    /// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+    /// 
+    /// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+    pub fn new(query: &'a Query<'w, 's, (&'static V, &'static C)>, start_ent: Entity, end_ent: Entity, heuristic_determiner: F) -> Result<Self, GraphError> {
+        let result = search_preamble(query, start_ent, end_ent, 0.0)?.map(Ok);
+        if result.is_none() {check_start_edges(get_start(query, start_ent)?.0)?;}
+        let (_, end_data) = query.get(end_ent)?;
+        Ok(Self{query, end_ent, end_data, heuristic_determiner, frontier: AStarFrontier::new(start_ent, TieBreak::default(), None), result})
+    }
+
+    /// The final path found by the search, or [`None`] if the search has not yet finished
+    pub fn result(&self) -> Option<Result<GraphPath<f32>, GraphError>> {
+        self.result.clone()
+    }
+
+    /// The most promising path found so far, in **reverse order**
+    /// 
+    /// While running, this is the path to the vertex that will be searched next, ie the one whose distance plus heuristic is smallest.
+    /// Once finished, this is the final path, or just the start vertex if no path was found.
+    pub fn current_best_path(&self) -> GraphPath<f32> {
+        if let Some(Ok(path)) = &self.result {return path.clone();}
+        GraphPath::new(self.frontier.search.path_to(*self.frontier.search.next_node()))
+    }
+}
+
+impl<'a, 'w, 's, V: GraphVertex, C: Component, F: Fn(&C, &C) -> Heuristic> Iterator for AStarIter<'a, 'w, 's, V, C, F> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.result.is_some() {return None;}
+        let end_data = self.end_data;
+        let heuristic = |data: &C| (self.heuristic_determiner)(data, end_data);
+        let end_ent = self.end_ent;
+        match self.frontier.step(self.query, &heuristic, |ent| ent == end_ent, &|_, vert: &V| vert.get_neighbours_with_weight()) {
+            Ok(SearchStep::Expanded(ent)) => Some(ent),
+            Ok(SearchStep::Reached(ent)) => {
+                self.result = Some(Ok(GraphPath::new(self.frontier.search.path_to(ent))));
+                Some(ent)
+            },
+            Ok(SearchStep::Exhausted) => {
+                self.result = Some(Err(GraphError::NoPath));
+                None
+            },
+            Err(err) => {
+                self.result = Some(Err(err));
+                None
             }
         }
     }
+}
+
+
+/// How A* chooses between vertices in its queue with the same priority `f = g + h`, where `g` is the distance from the start vertex and
+/// `h` the heuristic
+/// 
+/// On graphs with many equally short paths, such as open grids, large numbers of vertices can share the lowest priority. Preferring the
+/// vertex with the higher `g`, which is further along its path and so expected to be closer to the end, lets the search head straight for
+/// the end. Corner to corner on an open 20x20 grid with a Manhattan distance heuristic, this searches only the 38 vertices on a single path, 
+/// while [`TieBreak::LowerG`] and [`TieBreak::ByEntity`] search all 399 vertices other than the end. Any remaining ties are broken by
+/// [`Entity::index`], so the result is always deterministic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Prefer the vertex with the higher distance from the start
+    #[default]
+    HigherG,
+    /// Prefer the vertex with the lower distance from the start, searching the graph more evenly
+    LowerG,
+    /// Prefer the vertex with the lower [`Entity::index`]
+    ByEntity
+}
+
+impl TieBreak {
+    /// The ordering key of a vertex with the given distance and heuristic, where smaller keys are searched first
+    fn key(self, ent: Entity, dist: PathWeight, heuristic: Heuristic) -> (PathWeight, PathWeight, u32) {
+        let tie_key = match self {
+            TieBreak::HigherG => PathWeight{weight: -dist.weight},
+            TieBreak::LowerG => dist,
+            TieBreak::ByEntity => PathWeight{weight: 0.0}
+        };
+        (dist + heuristic, tie_key, ent.index())
+    }
+}
+
 
-    //if we get to this point, then we must have found no path
-    Err(GraphError::NoPath)
+/// An A* search over the vertices in a query, run one vertex at a time by the [`SteppedAStar`] behind every other A* search
+/// 
+/// Shared between [`a_star_checked_by`], [`a_star_nearest_goal`], [`greedy_best_first`] and [`AStarIter`], so that each only decides 
+/// the heuristic, the goal and the limits of the search.
+struct AStarFrontier {
+    search: SteppedAStar<Entity, (PathWeight, PathWeight, u32)>,
+    tie_break: TieBreak,
+    /// Whether to order the queue by the heuristic alone
+    greedy: bool
 }
 
+impl AStarFrontier {
+    fn new(start_ent: Entity, tie_break: TieBreak, max_expansions: Option<usize>) -> Self {
+        let priority = |ent: &Entity, dist: PathWeight, heuristic: Heuristic, _| tie_break.key(*ent, dist, heuristic);
+        Self{search: SteppedAStar::new(start_ent, &priority, max_expansions), tie_break, greedy: false}
+    }
 
+    /// A greedy best-first search, which orders the queue by the heuristic alone, and keeps the first path found to each vertex
+    fn new_greedy(start_ent: Entity) -> Self {
+        let mut frontier = Self::new(start_ent, TieBreak::default(), None);
+        frontier.greedy = true;
+        //no path is shorter than another by more than an infinite tolerance
+        frontier.search.tolerance = f32::INFINITY;
+        frontier
+    }
+
+    /// Searches the next vertex in the queue, with the weighted neighbours of each vertex given by `neighbours_of`
+    fn step<V, C, H, G, N>(&mut self, query: &Query<(&V, &C)>, heuristic_determiner: &H, is_goal: G, neighbours_of: &N) -> Result<SearchStep<Entity>, GraphError>
+    where
+        V: GraphVertex,
+        C: Component,
+        H: Fn(&C) -> Heuristic,
+        G: Fn(Entity) -> bool,
+        N: Fn(Entity, &V) -> Vec<(Entity, f32)>
+    {
+        //edges to entities that aren't in the query are skipped, as they have no data to give a heuristic
+        let graph = |ent: Entity| query.get(ent).map_or_else(|_| Vec::new(), |(vert, _)| {
+            neighbours_of(ent, vert).into_iter().filter(|&(neighbour_ent, _)| query.contains(neighbour_ent)).collect()
+        });
+        let heuristic = |ent: &Entity| query.get(*ent).map_or(Heuristic{value: 0.0}, |(_, data)| heuristic_determiner(data));
+        let (tie_break, greedy) = (self.tie_break, self.greedy);
+        let priority = |ent: &Entity, dist: PathWeight, heuristic: Heuristic, _| {
+            tie_break.key(*ent, if greedy {PathWeight{weight: 0.0}} else {dist}, heuristic)
+        };
+        self.search.step(&graph, &heuristic, &priority, |ent| is_goal(*ent), &mut |_, _, _| Ok(()))
+    }
+
+    /// Searches vertices until a goal is reached, returning the path to it
+    fn run<V, C, H, G, N>(&mut self, query: &Query<(&V, &C)>, heuristic_determiner: &H, is_goal: G, neighbours_of: &N) -> Result<GraphPath<f32>, GraphError>
+    where
+        V: GraphVertex,
+        C: Component,
+        H: Fn(&C) -> Heuristic,
+        G: Fn(Entity) -> bool,
+        N: Fn(Entity, &V) -> Vec<(Entity, f32)>
+    {
+        loop {
+            match self.step(query, heuristic_determiner, &is_goal, neighbours_of)? {
+                SearchStep::Expanded(_) => {},
+                SearchStep::Reached(ent) => return Ok(GraphPath::new(self.search.path_to(ent))),
+                //if we get to this point, then we must have found no path
+                SearchStep::Exhausted => return Err(GraphError::NoPath)
+            }
+        }
+    }
+}
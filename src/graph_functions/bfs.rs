@@ -1,10 +1,10 @@
 use std::{collections::VecDeque, u64};
 
-use bevy::prelude::{Component, Entity, Query};
+use bevy::{prelude::{Component, Entity, Query, With, Without}, utils::{HashMap, HashSet}};
 
 use crate::graph_vertex::GraphVertex;
 
-use super::{GraphError, GraphPath, VisitedNodes};
+use super::{helper::{check_same_component, check_start_edges, get_start, search_preamble}, implicit::{implicit_bfs, implicit_bfs_by}, GraphError, GraphPath, VisitedNodes};
 
 
 
@@ -15,7 +15,11 @@ use super::{GraphError, GraphPath, VisitedNodes};
 /// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
 /// 
 /// [`GraphError::NoPath`]: If a path could not be found.
 /// 
@@ -54,28 +58,422 @@ pub fn bfs<V: GraphVertex>(
     start_ent: Entity,
     end_ent: Entity
 ) -> Result<GraphPath<()>, GraphError> {
+    bfs_checked(query, start_ent, end_ent, false, None)
+}
+
+/// Runs a breadth-first search, returning an error rather than skipping any edge to an entity that is not in the query
+/// 
+/// Identical to [`bfs`], except that the destination of every edge found during the search must be a vertex in the query. This is useful to catch
+/// edges left pointing at despawned entities, which [`bfs`] silently ignores. Note this means vertices excluded by a query filter are also errors,
+/// so this should be used with queries containing every vertex.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity, or the destination of an edge found during the search, does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # See also
+/// 
+/// [`dijkstra_search_strict`](super::dijkstra::dijkstra_search_strict): For the strict version of Dijkstra's algorithm
+pub fn bfs_strict<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<GraphPath<()>, GraphError> {
+    bfs_checked(query, start_ent, end_ent, true, None)
+}
+
+/// Runs a breadth-first search that gives up after searching the given number of vertices, returning the path in **reverse order**
+/// 
+/// Identical to [`bfs`], except that once `max_expansions` vertices have been searched without finding the end vertex, 
+/// [`GraphError::BudgetExceeded`] is returned. This bounds the work done by a search on a huge or untrusted graph, regardless of its shape,
+/// unlike limits on the number of steps or distance. A budget of [`None`] never gives up.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::BudgetExceeded`]: If the search was stopped after searching `max_expansions` vertices.
+/// 
+/// # See also
+/// 
+/// [`dijkstra_search_budgeted`](super::dijkstra::dijkstra_search_budgeted): For Dijkstra's algorithm with a budget
+pub fn bfs_budgeted<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    max_expansions: Option<usize>
+) -> Result<GraphPath<()>, GraphError> {
+    bfs_checked(query, start_ent, end_ent, false, max_expansions)
+}
+
+/// Breadth-first search, which when strict returns [`GraphError::InvalidEntity`] on finding an edge to an entity not in the query,
+/// and gives up after searching `max_expansions` vertices
+fn bfs_checked<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    strict: bool,
+    max_expansions: Option<usize>
+) -> Result<GraphPath<()>, GraphError> {
+
+    if let Some(path) = search_preamble(query, start_ent, end_ent, ())? {return Ok(path);}
+    check_start_edges(get_start(query, start_ent)?)?;
+
+    //entities that aren't in the query have no neighbours, so are found but never searched
+    let graph = |ent: Entity| query.get(ent).map_or_else(|_| Vec::new(), |vert| {
+        vert.get_neighbours().into_iter().map(|neighbour_ent| (neighbour_ent, 1.0)).collect()
+    });
+    let on_found = |ent: &Entity| -> Result<(), GraphError> {
+        if strict {query.get(*ent)?;}
+        Ok(())
+    };
+    let path = implicit_bfs_by(&graph, start_ent, end_ent, max_expansions, on_found)?;
+    Ok(GraphPath::new(path.into_iter().map(|ent| (ent, ())).collect()))
+}
+
+/// Runs a breadth-first search over the neighbours returned by the provided function, returning the path in **reverse order**
+/// 
+/// Identical to [`bfs`], except the neighbours of each vertex are given by `neighbours_of`, which should return [`None`] for any entity that 
+/// isn't a vertex. This allows searching graphs made up of several different [`GraphVertex`] types, or whose edges aren't stored in a component at all.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If `neighbours_of` returns [`None`] for the start vertex.
+/// 
+/// [`GraphError::InvalidEntity`]: If `neighbours_of` returns [`None`] for the end vertex.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and `neighbours_of` returns no neighbours for the start vertex.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds a route using both the road and rail networks
+/// fn plan_journey(
+///     roads: Query<&RoadVertex>,
+///     rails: Query<&RailVertex>,
+///     journey: Res<Journey>
+/// ) {
+///     let route = bfs_by(journey.start, journey.end, |ent| {
+///         roads.get(ent).map(|road| road.get_neighbours())
+///         .or_else(|_| rails.get(ent).map(|rail| rail.get_neighbours()))
+///         .ok()
+///     });
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`dijkstra_search_by`](super::dijkstra::dijkstra_search_by): For Dijkstra's algorithm with a neighbour function
+pub fn bfs_by<N>(
+    start_ent: Entity,
+    end_ent: Entity,
+    neighbours_of: N
+) -> Result<GraphPath<()>, GraphError> 
+where
+    N: Fn(Entity) -> Option<Vec<Entity>>
+{
+    let start_neighbours = neighbours_of(start_ent).ok_or(GraphError::StartBlocked)?;
+    neighbours_of(end_ent).ok_or(GraphError::InvalidEntity(end_ent))?;
+    if start_ent == end_ent {return Ok(GraphPath::single(start_ent, ()));}
+    if start_neighbours.is_empty() {return Err(GraphError::IsolatedStart);}
+
+    //entities that aren't vertices have no neighbours, so are found but never searched
+    let graph = |ent: Entity| neighbours_of(ent).unwrap_or_default().into_iter().map(|neighbour_ent| (neighbour_ent, 1.0)).collect();
+    let path = implicit_bfs(&graph, start_ent, end_ent, None)?;
+    Ok(GraphPath::new(path.into_iter().map(|ent| (ent, ())).collect()))
+}
+
+/// Runs a breadth-first search using only the vertices with the marker component `M`, returning the path in **reverse order**
+/// 
+/// Identical to [`bfs`], except the query is filtered to vertices that also have an `M` component. Edges to vertices without the marker are
+/// skipped, as they would be for any other filtered query. This is a shorthand for the common case of a [`With`] filter, for other filters
+/// see [`GraphFunctionExt`](super::GraphFunctionExt), which accepts a query with any filter.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not have the marker or does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not have the marker or does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds a path using only the tiles that have been explored
+/// fn explored_path_finder(
+///     tiles: Query<&VertexType, With<ExploredMarker>>,
+///     journey: Res<Journey>
+/// ) {
+///     if bfs_with_filter(&tiles, journey.start, journey.end).is_err() {
+///         println!("No known path yet, keep exploring!");
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`bfs_without`]: For a breadth-first search avoiding the vertices with a marker
+pub fn bfs_with_filter<V: GraphVertex, M: Component>(
+    query: &Query<&V, With<M>>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<GraphPath<()>, GraphError> {
+    bfs_by(start_ent, end_ent, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours()))
+}
+
+/// Runs a breadth-first search avoiding the vertices with the marker component `M`, returning the path in **reverse order**
+/// 
+/// Identical to [`bfs`], except the query is filtered to vertices that don't have an `M` component, so the path never passes through a marked vertex.
+/// This is a shorthand for the common case of a [`Without`] filter.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity has the marker or does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity has the marker or does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # See also
+/// 
+/// [`bfs_with_filter`]: For a breadth-first search using only the vertices with a marker
+pub fn bfs_without<V: GraphVertex, M: Component>(
+    query: &Query<&V, Without<M>>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<GraphPath<()>, GraphError> {
+    bfs_by(start_ent, end_ent, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours()))
+}
+
+/// Runs a breadth-first search using only the vertices in the given set of entities, returning the path in **reverse order**
+/// 
+/// Identical to [`bfs`], except any vertex not in `allowed` is treated as if it weren't in the query, so the path only passes through allowed
+/// vertices. This suits restrictions that are a concrete set of entities rather than a component, eg the territory a player currently owns, 
+/// where adding and removing marker components just to filter the query would be wasteful.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity is not in the set or does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity is not in the set or does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that moves supplies only through the player's own territory
+/// fn route_supplies(
+///     territory: Res<OwnedTerritory>,
+///     tiles: Query<&VertexType>,
+///     mut convoys: Query<(&CurrentTile, &Target, &mut ConvoyPath)>
+/// ) {
+///     for (tile, target, mut convoy_path) in convoys.iter_mut() {
+///         convoy_path.0 = bfs_within_set(&tiles, tile.0, target.0, &territory.0).ok();
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`bfs_with_filter`]: For a breadth-first search using only the vertices with a marker
+pub fn bfs_within_set<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    allowed: &HashSet<Entity>
+) -> Result<GraphPath<()>, GraphError> {
+    bfs_by(start_ent, end_ent, |ent| if allowed.contains(&ent) {query.get(ent).ok().map(|vert| vert.get_neighbours())} else {None})
+}
 
-    if start_ent == end_ent {return Ok(GraphPath::single(start_ent, ()))};
+/// Runs a breadth-first search over every vertex reachable from the start vertex, returning the search tree
+/// 
+/// Each reached vertex is mapped to the vertex it was first reached from, its parent in the tree, with the start vertex mapped to [`None`].
+/// Following the parents from any reached vertex gives a path back to the start with the fewest possible steps, and the whole map can be
+/// used to draw how the search explored the graph. Edges to entities that aren't in the query are skipped.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # See also
+/// 
+/// [`dijkstra_tree`](super::dijkstra::dijkstra_tree): For the tree of shortest paths by total edge weight
+/// 
+/// [`bfs_spanning_tree`]: For the same tree as a list of edges
+pub fn bfs_tree<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity
+) -> Result<HashMap<Entity, Option<Entity>>, GraphError> {
+    get_start(query, start_ent)?;
 
     let mut search_queue: VecDeque<Entity> = VecDeque::from([start_ent]);
     let mut visited: VisitedNodes = VisitedNodes::new_from_start(start_ent);
 
-    //loop while still vertices to check
     while let Some(sv_ent) = search_queue.pop_front() {
-        let Ok(sv_vert) = query.get(sv_ent) else {continue;};
-
-        for neighbour_ent in sv_vert.get_neighbours(){
-            
-            if visited.is_visited(&neighbour_ent) {continue;}
+        for neighbour_ent in query.get(sv_ent).map(|vert| vert.get_neighbours()).unwrap_or_default() {
+            //only vertices in the query are part of the tree
+            if visited.is_visited(&neighbour_ent) || query.get(neighbour_ent).is_err() {continue;}
             visited.insert(neighbour_ent, sv_ent, 0, 0.0);
+            search_queue.push_back(neighbour_ent);
+        }
+    }
+    Ok(visited.into_previous_map())
+}
 
-            if neighbour_ent == end_ent {return Ok(visited.determine_path(neighbour_ent).expect("The created path should be valid"));}
+/// Runs a breadth-first search over every vertex reachable from the start vertex, returning the edges of the search tree
+/// 
+/// Each edge is given as `(parent, child)`, in the order the children were discovered, so every parent appears as a child, or is the start 
+/// vertex, before its own children. This is the same tree as [`bfs_tree`], given as a list of edges rather than a map of parents, which suits 
+/// generating mazes or laying out a hierarchy. The tree has one edge fewer than the number of vertices reached. Edges to entities that aren't 
+/// in the query are skipped.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that carves a maze by opening a wall for each edge of the search tree of the grid
+/// fn carve_maze(
+///     entrance: Query<Entity, With<Entrance>>,
+///     cells: Query<&CellVertex>,
+///     mut walls: ResMut<Walls>
+/// ) {
+///     for (parent, child) in bfs_spanning_tree(&cells, entrance.single()).unwrap() {
+///         walls.open(parent, child);
+///     }
+/// }
+/// ```
+pub fn bfs_spanning_tree<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity
+) -> Result<Vec<(Entity, Entity)>, GraphError> {
+    get_start(query, start_ent)?;
 
+    let mut search_queue: VecDeque<Entity> = VecDeque::from([start_ent]);
+    let mut reached: HashSet<Entity> = HashSet::from([start_ent]);
+    let mut tree_edges: Vec<(Entity, Entity)> = Vec::new();
+
+    while let Some(sv_ent) = search_queue.pop_front() {
+        for neighbour_ent in query.get(sv_ent).map(|vert| vert.get_neighbours()).unwrap_or_default() {
+            //only vertices in the query are part of the tree
+            if !query.contains(neighbour_ent) || !reached.insert(neighbour_ent) {continue;}
+            tree_edges.push((sv_ent, neighbour_ent));
             search_queue.push_back(neighbour_ent);
         }
     }
-    //if we get to this point, then we must have found no path
-    Err(GraphError::NoPath)
+    Ok(tree_edges)
+}
+
+/// Runs a single breadth-first search from the start vertex, returning a path to every vertex it can reach in **reverse order**
+/// 
+/// Each path has the fewest possible steps, and is the same path as [`bfs`] would find between the start vertex and that vertex. The start vertex
+/// is included, with the single vertex path. Edges to entities that aren't in the query are skipped. This is useful for precomputing the routes
+/// from a hub to everywhere else, as the search is only run once and every path is rebuilt from its tree of previous vertices.
+/// 
+/// Every path is stored in full, so the memory used is the total length of all the paths, rather than one entry per vertex. On a long chain
+/// of `n` vertices this is around `n * n / 2` entries. When that is too much, use [`bfs_tree`] and rebuild only the paths that are needed.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that precomputes the route from the depot to every shop
+/// fn plan_deliveries(
+///     depot: Query<Entity, With<Depot>>,
+///     streets: Query<&StreetVertex>,
+///     mut routes: ResMut<DeliveryRoutes>
+/// ) {
+///     routes.0 = bfs_all_paths(&streets, depot.single()).unwrap();
+/// }
+/// ```
+pub fn bfs_all_paths<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity
+) -> Result<HashMap<Entity, GraphPath<()>>, GraphError> {
+    get_start(query, start_ent)?;
+
+    let mut search_queue: VecDeque<Entity> = VecDeque::from([start_ent]);
+    let mut visited: VisitedNodes = VisitedNodes::new_from_start(start_ent);
+    //the reached vertices in the order they were found
+    let mut reached: Vec<Entity> = vec![start_ent];
+
+    while let Some(sv_ent) = search_queue.pop_front() {
+        for neighbour_ent in query.get(sv_ent).map(|vert| vert.get_neighbours()).unwrap_or_default() {
+            if visited.is_visited(&neighbour_ent) || query.get(neighbour_ent).is_err() {continue;}
+            visited.insert(neighbour_ent, sv_ent, 0, 0.0);
+            search_queue.push_back(neighbour_ent);
+            reached.push(neighbour_ent);
+        }
+    }
+
+    Ok(reached.into_iter().map(|ent| (ent, visited.determine_path(ent).expect("The created path should be valid"))).collect())
+}
+
+/// Runs a breadth-first search between the two vertices, first checking whether they are in the same component, returning the path in **reverse order**
+/// 
+/// Identical to [`bfs`], except that if the provided component labels (eg from [`connected_components`](super::analysis::connected_components)) 
+/// place the start and end vertex in different components, [`GraphError::NoPath`] is returned immediately rather than after exploring 
+/// everything reachable from the start. Vertices missing from the labels are searched as normal.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found, or the vertices are in different components.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds paths for many units on a map made of separate islands
+/// fn island_path_finder(
+///     units: Query<(&UnitTile, &TargetTile)>,
+///     tiles: Query<&VertexType>,
+///     components: Res<TileComponents>
+/// ) {
+///     for (start, end) in units.iter() {
+///         if bfs_prepared(&tiles, start.0, end.0, &components.0).is_err() {
+///             println!("That unit can't reach its target!");
+///         }
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`bfs`]: For a breadth-first search without the component check
+pub fn bfs_prepared<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    components: &HashMap<Entity, usize>
+) -> Result<GraphPath<()>, GraphError> {
+    check_same_component(components, start_ent, end_ent)?;
+    bfs(query, start_ent, end_ent)
 }
 
 /// Runs a breadth-first search, starting at the start vertex and ending at the first vertex for which the provided function returns true, 
@@ -86,10 +484,14 @@ pub fn bfs<V: GraphVertex>(
 /// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
 /// 
 /// [`GraphError::NoPath`]: If a path could not be found.
 /// 
+/// Entities missing either component don't match the query, so are skipped as if they weren't vertices. A goal entity missing its vertex
+/// component can therefore never be found, and the search reports [`GraphError::NoPath`]. Use [`bfs_computed_end_strict`] to have this
+/// reported as [`GraphError::IncompleteVertex`] instead.
+/// 
 /// # Example
 /// 
 /// ```ignore
@@ -134,7 +536,7 @@ where
     C: Component,
     F: Fn(&C) -> bool,
 {
-    let start_vert = query.get(start_ent)?;
+    let start_vert = get_start(query, start_ent)?;
     if end_determiner(start_vert.1) {return Ok(GraphPath::single(start_ent, ()))};
 
     let mut search_queue: VecDeque<BreadthNode<V>> = VecDeque::from([BreadthNode::new(start_ent, start_vert.0, 0)]);
@@ -146,12 +548,68 @@ where
         for neighbour_ent in node.vertex.get_neighbours(){
 
             if visited.is_visited(&neighbour_ent) {continue;}
-            visited.insert(neighbour_ent, node.ent, node.step + 1, 0.0);
+            visited.insert(neighbour_ent, node.ent, node.step.saturating_add(1), 0.0);
+
+            let Ok((neighbour_vert, neighbour_data)) = query.get(neighbour_ent) else {continue;};
+
+            if end_determiner(neighbour_data) {return Ok(visited.determine_path(neighbour_ent).expect("The created path sould be valid"));}
+            search_queue.push_back(BreadthNode::new(neighbour_ent, neighbour_vert, node.step.saturating_add(1)));
+        }
+    }
+
+    Err(GraphError::NoPath)
+}
+
+/// Identical to [`bfs_computed_end`], except that an entity with only one of the vertex and data components is an error rather than skipped
+///
+/// As the query of [`bfs_computed_end`] requires both components, an entity missing either one simply doesn't match it, so a goal vertex
+/// that is missing its vertex component is skipped and the search silently reports [`GraphError::NoPath`]. This version queries both
+/// components optionally, so it can tell such an entity apart from one that is not part of the graph at all.
+///
+/// # Errors
+///
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity has neither component, or does not appear in the provided query.
+///
+/// [`GraphError::IncompleteVertex`]: If the start vertex, or a vertex reached by an edge, has exactly one of the two components.
+///
+/// [`GraphError::InvalidEntity`]: If a vertex reached by an edge has neither component, or does not appear in the provided query.
+///
+/// [`GraphError::NoPath`]: If a path could not be found.
+pub fn bfs_computed_end_strict<V, C, F> (
+    query: &Query<(Option<&V>, Option<&C>)>,
+    start_ent: Entity,
+    end_determiner: F
+) -> Result<GraphPath<()>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C) -> bool,
+{
+    let (start_vert, start_data) = match query.get(start_ent) {
+        Ok((Some(vert), Some(data))) => (vert, data),
+        Ok((None, None)) | Err(_) => return Err(GraphError::StartBlocked),
+        Ok(_) => return Err(GraphError::IncompleteVertex(start_ent))
+    };
+    if end_determiner(start_data) {return Ok(GraphPath::single(start_ent, ()))};
+
+    let mut search_queue: VecDeque<BreadthNode<V>> = VecDeque::from([BreadthNode::new(start_ent, start_vert, 0)]);
+    let mut visited: VisitedNodes = VisitedNodes::new_from_start(start_ent);
+
+    while let Some(node) = search_queue.pop_front() {
+
+        for neighbour_ent in node.vertex.get_neighbours(){
+
+            if visited.is_visited(&neighbour_ent) {continue;}
+            visited.insert(neighbour_ent, node.ent, node.step.saturating_add(1), 0.0);
 
-            let Ok((neighbour_vert, neighbour_data)) = query.get(start_ent) else {continue;};
+            let (neighbour_vert, neighbour_data) = match query.get(neighbour_ent) {
+                Ok((Some(vert), Some(data))) => (vert, data),
+                Ok((None, None)) | Err(_) => return Err(GraphError::InvalidEntity(neighbour_ent)),
+                Ok(_) => return Err(GraphError::IncompleteVertex(neighbour_ent))
+            };
 
             if end_determiner(neighbour_data) {return Ok(visited.determine_path(neighbour_ent).expect("The created path sould be valid"));}
-            search_queue.push_back(BreadthNode::new(neighbour_ent, neighbour_vert, node.step + 1));
+            search_queue.push_back(BreadthNode::new(neighbour_ent, neighbour_vert, node.step.saturating_add(1)));
         }
     }
 
@@ -175,7 +633,7 @@ where
 
     let mut found_paths = Vec::new();
 
-    let start_vert = query.get(start_ent)?;
+    let start_vert = get_start(query, start_ent)?;
     if end_determiner(start_vert.1) {found_paths.push(GraphPath::single(start_ent, ()))};
 
     let mut search_queue: VecDeque<BreadthNode<V>> = VecDeque::from([BreadthNode::new(start_ent, start_vert.0, 0)]);
@@ -184,19 +642,19 @@ where
     //loop while still vertices to check
     while let Some(node) = search_queue.pop_front() {
 
-        if node.step == max_steps {continue;}
+        if node.step >= max_steps {continue;}
 
         for neighbour_ent in node.vertex.get_neighbours(){
 
             if found_paths.len() == max_paths {return Ok(found_paths)}
 
             if visited.is_visited(&neighbour_ent) {continue;}
-            visited.insert(neighbour_ent, node.ent, node.step + 1, 0.0);
+            visited.insert(neighbour_ent, node.ent, node.step.saturating_add(1), 0.0);
 
-            let Ok((neighbour_vert, neighbour_data)) = query.get(start_ent) else {continue;};
+            let Ok((neighbour_vert, neighbour_data)) = query.get(neighbour_ent) else {continue;};
 
             if end_determiner(neighbour_data) {found_paths.push(visited.determine_path(neighbour_ent).expect("The created path sould be valid"));}
-            search_queue.push_back(BreadthNode::new(neighbour_ent, neighbour_vert, node.step + 1));
+            search_queue.push_back(BreadthNode::new(neighbour_ent, neighbour_vert, node.step.saturating_add(1)));
         }
     }
 
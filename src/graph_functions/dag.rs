@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::{Component, Entity, Query}, utils::{HashMap, HashSet}};
+
+use crate::graph_vertex::GraphVertex;
+
+use super::{helper::{get_start, search_preamble}, GraphError, GraphPath};
+
+
+/// Orders the vertices so that every edge goes from an earlier vertex to a later one
+/// 
+/// Uses Kahn's algorithm, only considering edges between vertices in the query. When several vertices could come next, they are taken in the
+/// order they were made available, starting with the query's iteration order, so the result is deterministic for a given query.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::NotAcyclic`]: If the graph contains a cycle, so no such order exists.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that runs the tasks of a dependency graph in an order that respects the dependencies
+/// fn run_tasks(tasks: Query<(Entity, &TaskVertex)>) {
+///     for task in topological_sort(&tasks).expect("Tasks should not depend on themselves") {
+///         //run the task
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`condensation`](super::analysis::condensation): For a DAG that can be sorted even when the graph has cycles
+pub fn topological_sort<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Result<Vec<Entity>, GraphError> {
+    let vertices: Vec<Entity> = query.iter().map(|(ent, _)| ent).collect();
+    topological_sort_by(&vertices, |ent| query.get(ent).map(|(_, vert)| vert.get_neighbours()).unwrap_or_default())
+}
+
+/// Kahn's algorithm over the given vertices, with the neighbours of each vertex given by the provided function, as in [`topological_sort`]
+fn topological_sort_by<N>(
+    vertices: &[Entity],
+    neighbours_of: N
+) -> Result<Vec<Entity>, GraphError> 
+where
+    N: Fn(Entity) -> Vec<Entity>
+{
+    //count the incoming edges of every vertex, ignoring those from outside the vertices
+    let mut incoming_count: HashMap<Entity, usize> = vertices.iter().map(|ent| (*ent, 0)).collect();
+    for &ent in vertices {
+        for neighbour_ent in neighbours_of(ent) {
+            if let Some(count) = incoming_count.get_mut(&neighbour_ent) {*count += 1;}
+        }
+    }
+
+    //start from the vertices with no incoming edges
+    let mut ready: VecDeque<Entity> = vertices.iter().copied().filter(|ent| incoming_count[ent] == 0).collect();
+    let mut order: Vec<Entity> = Vec::with_capacity(incoming_count.len());
+
+    while let Some(sv_ent) = ready.pop_front() {
+        order.push(sv_ent);
+        for neighbour_ent in neighbours_of(sv_ent) {
+            let Some(count) = incoming_count.get_mut(&neighbour_ent) else {continue;};
+            *count -= 1;
+            if *count == 0 {ready.push_back(neighbour_ent);}
+        }
+    }
+
+    //any vertex never made ready must be on or after a cycle
+    if order.len() != incoming_count.len() {return Err(GraphError::NotAcyclic);}
+    Ok(order)
+}
+
+/// Finds the path maximising total edge weight between two vertices of a directed acyclic graph, returning the path in **reverse order**
+/// 
+/// Relaxes the edges of each vertex in [topological order](topological_sort), keeping the longest distance found to each vertex. This gives the
+/// critical path of a schedule, where vertices are tasks and edge weights are the time taken before the next task can start. Negative weights
+/// are allowed. Each vertex of the path is paired with its distance from the start vertex.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NotAcyclic`]: If the graph contains a cycle, in which case the longest path is not well defined.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that reports how long the project will take
+/// fn project_length(
+///     tasks: Query<(Entity, &TaskVertex)>,
+///     project: Res<Project>
+/// ) {
+///     let critical_path = longest_path_dag(&tasks, project.kickoff, project.release).unwrap();
+///     println!("The project will take {} days", critical_path.total_weight());
+/// }
+/// ```
+pub fn longest_path_dag<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<GraphPath<f32>, GraphError> {
+    let order = topological_sort(query)?;
+    if let Some(path) = search_preamble(query, start_ent, end_ent, 0.0)? {return Ok(path);}
+
+    //the longest distance found to each vertex reachable from the start, and the previous vertex of that path
+    let mut longest: HashMap<Entity, (f32, Option<Entity>)> = HashMap::new();
+    longest.insert(start_ent, (0.0, None));
+
+    //vertices before the start in the order can't be reached from it
+    let start_pos = order.iter().position(|ent| *ent == start_ent).expect("The start vertex should be in the topological order");
+    for &sv_ent in order[start_pos..].iter() {
+        let Some(&(sv_dist, _)) = longest.get(&sv_ent) else {continue;};
+        let Ok((_, sv_vert)) = query.get(sv_ent) else {continue;};
+        for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight() {
+            let total_dist = sv_dist + edge_weight;
+            if longest.get(&neighbour_ent).is_some_and(|&(dist, _)| total_dist <= dist) {continue;}
+            longest.insert(neighbour_ent, (total_dist, Some(sv_ent)));
+        }
+    }
+
+    //follow the previous vertices back from the end
+    let mut path = Vec::new();
+    let mut to_follow = Some(end_ent);
+    while let Some(current) = to_follow {
+        let Some(&(dist, previous)) = longest.get(&current) else {return Err(GraphError::NoPath);};
+        path.push((current, dist));
+        to_follow = previous;
+    }
+    Ok(GraphPath::new(path))
+}
+
+/// Whether a path should minimise or maximise its total cost
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathObjective {
+    Min,
+    Max
+}
+
+/// Finds the path between two vertices of a directed acyclic graph minimising or maximising the summed cost of its vertices, returning the path
+/// in **reverse order**
+/// 
+/// Unlike [`longest_path_dag`], the weights are on the vertices rather than the edges, with the cost of each vertex given by `cost` applied to its 
+/// `C` component, and the edge weights ignored. The cost of a path includes both its start and end vertex. Each vertex of the path is paired with the
+/// summed cost of the path up to and including it, so [`GraphPath::total_weight`] is the cost of the whole path. Node weighted DAGs are common in 
+/// scheduling, where each vertex is a task taking some time. Negative costs are allowed.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NotAcyclic`]: If the graph contains a cycle.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds the chain of tasks that decides how long the project takes
+/// fn critical_tasks(
+///     tasks: Query<(Entity, &TaskVertex, &Duration)>,
+///     project: Res<Project>
+/// ) {
+///     let critical_path = dag_path(&tasks, project.kickoff, project.release, PathObjective::Max, |duration| duration.days).unwrap();
+///     println!("The project will take {} days", critical_path.total_weight());
+/// }
+/// ```
+pub fn dag_path<V, C, F>(
+    query: &Query<(Entity, &V, &C)>,
+    start_ent: Entity,
+    end_ent: Entity,
+    objective: PathObjective,
+    cost: F
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C) -> f32
+{
+    let vertices: Vec<Entity> = query.iter().map(|(ent, _, _)| ent).collect();
+    let order = topological_sort_by(&vertices, |ent| query.get(ent).map(|(_, vert, _)| vert.get_neighbours()).unwrap_or_default())?;
+    let (_, _, start_data) = get_start(query, start_ent)?;
+    query.get(end_ent)?;
+    let start_cost = cost(start_data);
+    if start_ent == end_ent {return Ok(GraphPath::single(start_ent, start_cost));}
+
+    //whether a new total is better than the current best
+    let improves = |new: f32, current: f32| match objective {
+        PathObjective::Min => new < current,
+        PathObjective::Max => new > current
+    };
+
+    //the best total cost found to each vertex reachable from the start, and the previous vertex of that path
+    let mut best: HashMap<Entity, (f32, Option<Entity>)> = HashMap::new();
+    best.insert(start_ent, (start_cost, None));
+
+    //vertices before the start in the order can't be reached from it
+    let start_pos = order.iter().position(|ent| *ent == start_ent).expect("The start vertex should be in the topological order");
+    for &sv_ent in order[start_pos..].iter() {
+        let Some(&(sv_total, _)) = best.get(&sv_ent) else {continue;};
+        let Ok((_, sv_vert, _)) = query.get(sv_ent) else {continue;};
+        for neighbour_ent in sv_vert.get_neighbours() {
+            let Ok((_, _, neighbour_data)) = query.get(neighbour_ent) else {continue;};
+            let total = sv_total + cost(neighbour_data);
+            if best.get(&neighbour_ent).is_some_and(|&(current, _)| !improves(total, current)) {continue;}
+            best.insert(neighbour_ent, (total, Some(sv_ent)));
+        }
+    }
+
+    //follow the previous vertices back from the end
+    let mut path = Vec::new();
+    let mut to_follow = Some(end_ent);
+    while let Some(current) = to_follow {
+        let Some(&(total, previous)) = best.get(&current) else {return Err(GraphError::NoPath);};
+        path.push((current, total));
+        to_follow = previous;
+    }
+    Ok(GraphPath::new(path))
+}
+
+/// Returns the transitive reduction of a directed acyclic graph, ie the fewest edges that keep every vertex able to reach the same vertices
+/// 
+/// An edge is removed when its destination can also be reached from its source by a longer path, as the edge then adds nothing to which
+/// vertices depend on which. This is useful for drawing dependency graphs without the clutter of every implied dependency. Only edges between
+/// vertices in the query are considered, and parallel edges are combined, so each kept edge appears once. The edges are returned in 
+/// [topological order](topological_sort) of their source vertex, and then of their destination. The set of vertices reachable from each vertex 
+/// is stored while running, so memory use can grow with the square of the number of vertices on large, deep graphs.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::NotAcyclic`]: If the graph contains a cycle, so the reduction is not unique.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that draws the build graph, leaving out dependencies that are already implied by others
+/// fn draw_build_graph(
+///     targets: Query<(Entity, &BuildTarget)>,
+///     positions: Query<&Transform>,
+///     mut gizmos: Gizmos
+/// ) {
+///     for (from, to) in transitive_reduction(&targets).unwrap() {
+///         let (from, to) = (positions.get(from).unwrap(), positions.get(to).unwrap());
+///         gizmos.arrow(from.translation, to.translation, Color::WHITE);
+///     }
+/// }
+/// ```
+pub fn transitive_reduction<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Result<Vec<(Entity, Entity)>, GraphError> {
+    let order = topological_sort(query)?;
+    let position: HashMap<Entity, usize> = order.iter().enumerate().map(|(pos, ent)| (*ent, pos)).collect();
+
+    //the vertices reachable from each vertex, excluding itself, filled in from the end of the order so each neighbour's set is already known
+    let mut reachable: HashMap<Entity, HashSet<Entity>> = HashMap::new();
+    let mut kept_edges: Vec<Vec<Entity>> = vec![Vec::new(); order.len()];
+
+    for (pos, &sv_ent) in order.iter().enumerate().rev() {
+        let (_, sv_vert) = query.get(sv_ent).expect("Sorted vertices should be in the query");
+        let mut neighbours: Vec<Entity> = sv_vert.get_neighbours().into_iter().filter(|ent| position.contains_key(ent)).collect();
+        //a neighbour reachable through another neighbour always comes later in the order, so is checked after it
+        neighbours.sort_by_key(|ent| position[ent]);
+        neighbours.dedup();
+
+        let mut sv_reachable: HashSet<Entity> = HashSet::new();
+        for neighbour_ent in neighbours {
+            if sv_reachable.contains(&neighbour_ent) {continue;}
+            kept_edges[pos].push(neighbour_ent);
+            sv_reachable.insert(neighbour_ent);
+            sv_reachable.extend(reachable[&neighbour_ent].iter().copied());
+        }
+        reachable.insert(sv_ent, sv_reachable);
+    }
+
+    Ok(order.into_iter().zip(kept_edges).flat_map(|(ent, neighbours)| neighbours.into_iter().map(move |neighbour_ent| (ent, neighbour_ent))).collect())
+}
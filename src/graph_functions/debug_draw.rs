@@ -0,0 +1,45 @@
+use bevy::{color::Color, math::Vec3, prelude::{Gizmos, Query, Transform}};
+
+use super::GraphPath;
+
+
+/// Draws the path as a series of line segments between the translations of its vertices, for debugging
+/// 
+/// Vertices without a [`Transform`] in the provided query are skipped, with the line instead joining the vertices either side of them.
+/// Paths are drawn in the order they are stored in, but as lines have no direction the reversed order of search results does not matter.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that draws every agent's current path in red
+/// fn draw_agent_paths(
+///     mut gizmos: Gizmos,
+///     agents: Query<&CurrentPath>,
+///     transforms: Query<&Transform>
+/// ) {
+///     for path in agents.iter() {
+///         draw_path(&mut gizmos, &path.0, &transforms, Color::srgb(1.0, 0.0, 0.0));
+///     }
+/// }
+/// ```
+pub fn draw_path<D>(
+    gizmos: &mut Gizmos,
+    path: &GraphPath<D>,
+    transforms: &Query<&Transform>,
+    color: Color
+) {
+    for (start, end) in path_segments(path, transforms) {
+        gizmos.line(start, end, color);
+    }
+}
+
+/// Determines the line segments [`draw_path`] draws, skipping any vertices without a [`Transform`]
+pub(crate) fn path_segments<D>(
+    path: &GraphPath<D>,
+    transforms: &Query<&Transform>
+) -> Vec<(Vec3, Vec3)> {
+    let points: Vec<Vec3> = path.entities()
+    .filter_map(|ent| transforms.get(ent).ok().map(|transform| transform.translation))
+    .collect();
+    points.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
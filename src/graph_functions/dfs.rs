@@ -1,10 +1,10 @@
 use std::usize;
 
-use bevy::prelude::{Component, Entity, Query};
+use bevy::{prelude::{Component, Entity, Query}, utils::HashSet};
 
 use crate::graph_vertex::GraphVertex;
 
-use super::{GraphError, GraphPath, VisitedNodes};
+use super::{helper::{check_start_edges, get_start, search_preamble, ExpansionBudget}, GraphError, GraphPath, VisitedNodes};
 
 
 /// Runs a depth-first search, starting at the start vertex and ending at the end vertex, returning the path in **reverse order**
@@ -15,7 +15,11 @@ use super::{GraphError, GraphPath, VisitedNodes};
 /// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
 /// 
 /// [`GraphError::NoPath`]: If a path could not be found.
 /// 
@@ -51,11 +55,42 @@ pub fn dfs<V: GraphVertex>(
     start_ent: Entity,
     end_ent: Entity
 ) -> Result<GraphPath<()>, GraphError> {
-    let start_vert = query.get(start_ent)?;
-
-    if start_ent == end_ent {return Ok(GraphPath::single(start_ent, ()))}; //check for instant finish
+    dfs_budgeted(query, start_ent, end_ent, None)
+}
 
-    let mut search_queue: Vec<DepthNode<V>> = vec![DepthNode::new(start_ent, start_vert)];
+/// Runs a depth-first search that gives up after searching the given number of vertices, returning the path in **reverse order**
+/// 
+/// Identical to [`dfs`], except that once `max_expansions` vertices have been searched without finding the end vertex, 
+/// [`GraphError::BudgetExceeded`] is returned. A budget of [`None`] never gives up.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::BudgetExceeded`]: If the search was stopped after searching `max_expansions` vertices.
+pub fn dfs_budgeted<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    max_expansions: Option<usize>
+) -> Result<GraphPath<()>, GraphError> {
+    if let Some(path) = search_preamble(query, start_ent, end_ent, ())? {return Ok(path);} //check for instant finish
+    let start_vert = get_start(query, start_ent)?;
+    check_start_edges(start_vert)?;
+
+    //a vertex is searched once it is added to the stack, as that is when its neighbours are fetched, so that is when the budget is spent
+    let mut budget = ExpansionBudget::new(max_expansions);
+    let mut expand = |ent: Entity, vert: &V| -> Result<DepthNode, GraphError> {
+        budget.spend()?;
+        Ok(DepthNode::new(ent, vert))
+    };
+    let mut search_queue: Vec<DepthNode> = vec![expand(start_ent, start_vert)?];
     let mut visited = VisitedNodes::new_from_start(start_ent);
 
     while let Some(mut node) = search_queue.pop() {
@@ -72,13 +107,234 @@ pub fn dfs<V: GraphVertex>(
         if neighbour_ent == end_ent {return Ok(visited.determine_path(neighbour_ent).expect("The created path should be valid"))}
 
         let Ok(neighbour_vert) = query.get(neighbour_ent) else {continue;};
-        search_queue.push(DepthNode::new(neighbour_ent, neighbour_vert));
+        //a sink can't lead to the end vertex, so there is no need to search it
+        if neighbour_vert.is_sink() {continue;}
+        search_queue.push(expand(neighbour_ent, neighbour_vert)?);
+    }
+
+    //if we get to this point, then we must have found no path
+    Err(GraphError::NoPath)
+}
+
+/// The order in which [`dfs_ordered`] tries the edges of each vertex
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeOrder {
+    /// Follow the edge with the smallest weight first
+    CheapestFirst,
+    /// Follow the edge with the largest weight first
+    ExpensiveFirst
+}
+
+/// Runs a depth-first search that tries the edges of each vertex in order of weight, returning the path in **reverse order**
+/// 
+/// Identical to [`dfs`], except the neighbours of each vertex are sorted by the weight of the edge to them before the search descends,
+/// rather than being taken in the order given by [`GraphVertex::get_neighbours`]. Edges of equal weight keep that order. With 
+/// [`EdgeOrder::CheapestFirst`] this tends to find a reasonably cheap path quickly on weighted graphs, without the cost of a full 
+/// [`dijkstra_search`](super::dijkstra::dijkstra_search), though the path is not guaranteed to be the cheapest. Each vertex's neighbours
+/// are fetched and sorted once, when it is first searched.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that quickly finds a route for a unit that doesn't need the very best one
+/// fn rough_route(
+///     mut units: Query<(&CurrentTile, &Target, &mut UnitPath), With<Civilian>>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     for (tile, target, mut unit_path) in units.iter_mut() {
+///         unit_path.0 = dfs_ordered(&tiles, tile.0, target.0, EdgeOrder::CheapestFirst).ok();
+///     }
+/// }
+/// ```
+pub fn dfs_ordered<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    by: EdgeOrder
+) -> Result<GraphPath<()>, GraphError> {
+    if let Some(path) = search_preamble(query, start_ent, end_ent, ())? {return Ok(path);} //check for instant finish
+    let start_vert = get_start(query, start_ent)?;
+    check_start_edges(start_vert)?;
+
+    let sorted_neighbours = |vert: &V| {
+        let mut neighbours = vert.get_neighbours_with_weight();
+        match by {
+            EdgeOrder::CheapestFirst => neighbours.sort_by(|(_, weight1), (_, weight2)| weight1.total_cmp(weight2)),
+            EdgeOrder::ExpensiveFirst => neighbours.sort_by(|(_, weight1), (_, weight2)| weight2.total_cmp(weight1))
+        }
+        neighbours.into_iter().map(|(ent, _)| ent)
+    };
+
+    //each vertex on the stack with the neighbours it has left to try
+    let mut search_stack = vec![(start_ent, sorted_neighbours(start_vert))];
+    let mut visited = VisitedNodes::new_from_start(start_ent);
+
+    while let Some((sv_ent, remaining)) = search_stack.last_mut() {
+        let sv_ent = *sv_ent;
+        //once every neighbour has been tried, backtrack
+        let Some(neighbour_ent) = remaining.next() else {search_stack.pop(); continue;};
+
+        if visited.is_visited(&neighbour_ent) {continue;}
+        visited.insert(neighbour_ent, sv_ent, 0, 0.0);
+
+        if neighbour_ent == end_ent {return Ok(visited.determine_path(neighbour_ent).expect("The created path should be valid"))}
+
+        let Ok(neighbour_vert) = query.get(neighbour_ent) else {continue;};
+        //a sink can't lead to the end vertex, so there is no need to search it
+        if neighbour_vert.is_sink() {continue;}
+        search_stack.push((neighbour_ent, sorted_neighbours(neighbour_vert)));
+    }
+
+    //if we get to this point, then we must have found no path
+    Err(GraphError::NoPath)
+}
+
+/// Runs a depth-first search that only remembers the vertices of its current path, returning the path in **reverse order**
+/// 
+/// Unlike [`dfs`], which remembers every vertex it has visited, this only avoids vertices already on the path it is currently following, so its
+/// memory use is proportional to `max_depth` rather than to the size of the graph. The cost is time: a vertex reachable by several routes is
+/// searched again for each of them, which can take exponentially longer than [`dfs`] on graphs with many cycles or crossing routes. The search 
+/// never follows more than `max_depth` edges from the start vertex, so the end vertex is only found if it has a path of at most that many
+/// edges. This suits huge graphs where the end vertex is known to be close by but storing every visited vertex is not affordable.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NoPath`]: If a path of at most `max_depth` edges could not be found.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system checking whether a nearby door can be reached on a vast, generated map
+/// fn check_door(
+///     player: Query<&CurrentTile, With<Player>>,
+///     door: Query<&CurrentTile, With<Door>>,
+///     tiles: Query<&TileVertex>
+/// ) {
+///     if dfs_path_only(&tiles, player.single().0, door.single().0, 20).is_ok() {
+///         println!("The door is within 20 tiles!");
+///     }
+/// }
+/// ```
+pub fn dfs_path_only<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    max_depth: usize
+) -> Result<GraphPath<()>, GraphError> {
+    if let Some(path) = search_preamble(query, start_ent, end_ent, ())? {return Ok(path);} //check for instant finish
+    let start_vert = get_start(query, start_ent)?;
+
+    //the current path, and the set of vertices on it
+    let mut search_path: Vec<DepthNode> = vec![DepthNode::new(start_ent, start_vert)];
+    let mut on_path: HashSet<Entity> = HashSet::new();
+    on_path.insert(start_ent);
+
+    loop {
+        //once every neighbour has been tried, or the path can go no deeper, step back off this vertex
+        let depth = search_path.len();
+        let Some(node) = search_path.last_mut() else {break;};
+        let next_neighbour = if depth <= max_depth {node.get_next_neighbour()} else {None};
+        let Some(neighbour_ent) = next_neighbour else {
+            let node = search_path.pop().expect("The path should not be empty");
+            on_path.remove(&node.ent);
+            continue;
+        };
+
+        if neighbour_ent == end_ent {
+            let mut path: Vec<(Entity, ())> = search_path.iter().map(|node| (node.ent, ())).collect();
+            path.push((end_ent, ()));
+            path.reverse();
+            return Ok(GraphPath::new(path));
+        }
+
+        if on_path.contains(&neighbour_ent) {continue;}
+        let Ok(neighbour_vert) = query.get(neighbour_ent) else {continue;};
+        on_path.insert(neighbour_ent);
+        search_path.push(DepthNode::new(neighbour_ent, neighbour_vert));
     }
 
     //if we get to this point, then we must have found no path
     Err(GraphError::NoPath)
 }
 
+/// Runs a depth-first search, returning the path to the end vertex if found, or otherwise the path to the deepest vertex reached, in **reverse order**
+/// 
+/// Identical to [`dfs`], except that when the end vertex can't be reached, the path to the vertex the most steps along the search's paths is 
+/// returned instead of an error, useful for digging as deep as possible towards an unreachable goal. The returned flag is true only if the path
+/// ends at the end vertex. When several vertices are equally deep, the first reached is used. As for [`dfs`], the depth is that of the path the
+/// search happened to follow to the vertex, which need not be the fewest steps to it.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that sends a mole towards the treasure, digging as deep as it can if the treasure is out of reach
+/// fn dig_for_treasure(
+///     mut mole: Query<(&CurrentTunnel, &mut TunnelPlan), With<Mole>>,
+///     treasure: Query<&CurrentTunnel, With<Treasure>>,
+///     tunnels: Query<&TunnelVertex>
+/// ) {
+///     let (tunnel, mut plan) = mole.single_mut();
+///     let (path, found_treasure) = dfs_best_effort(&tunnels, tunnel.0, treasure.single().0).unwrap();
+///     plan.0 = path;
+///     if !found_treasure {println!("The treasure can't be reached, digging deep instead");}
+/// }
+/// ```
+pub fn dfs_best_effort<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<(GraphPath<()>, bool), GraphError> {
+    if let Some(path) = search_preamble(query, start_ent, end_ent, ())? {return Ok((path, true));} //check for instant finish
+    let start_vert = get_start(query, start_ent)?;
+
+    let mut search_queue: Vec<DepthNode> = vec![DepthNode::new(start_ent, start_vert)];
+    let mut visited = VisitedNodes::new_from_start(start_ent);
+    //the deepest vertex in the query reached so far, and its depth
+    let mut deepest = (start_ent, 0);
+
+    while let Some(mut node) = search_queue.pop() {
+
+        //check if we have any neighbours left to search from this vertex
+        let Some(neighbour_ent) = node.get_next_neighbour() else {continue;};
+
+        let previous = node.ent;
+        search_queue.push(node); //push back onto queue to be checked again later
+
+        if visited.is_visited(&neighbour_ent) {continue;}
+        let depth = visited.step_of(&previous).expect("The previous vertex should be visited").saturating_add(1);
+        visited.insert(neighbour_ent, previous, depth, 0.0);
+
+        if neighbour_ent == end_ent {return Ok((visited.determine_path(neighbour_ent).expect("The created path should be valid"), true))}
+
+        let Ok(neighbour_vert) = query.get(neighbour_ent) else {continue;};
+        if depth > deepest.1 {deepest = (neighbour_ent, depth);}
+        search_queue.push(DepthNode::new(neighbour_ent, neighbour_vert));
+    }
+
+    //the end was never reached, so settle for the deepest vertex
+    Ok((visited.determine_path(deepest.0).expect("The created path should be valid"), false))
+}
+
 /// Runs a depth-first search, starting at the start vertex and ending at the first vertex for which the provided function returns true, 
 /// returning the path in **reverse order**
 /// 
@@ -86,7 +342,7 @@ pub fn dfs<V: GraphVertex>(
 /// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
 /// 
 /// [`GraphError::NoPath`]: If a path could not be found.
 /// 
@@ -131,10 +387,10 @@ where
     CE: Component,
     FE: Fn(&CE) -> bool,
 {
-    let start_vert = query.get(start_ent)?;
+    let start_vert = get_start(query, start_ent)?;
     if end_determiner(start_vert.1) {return Ok(GraphPath::single(start_ent, ()))}; //check for instant finish
 
-    let mut search_queue: Vec<DepthNode<V>> = vec![DepthNode::new(start_ent, start_vert.0)];
+    let mut search_queue: Vec<DepthNode> = vec![DepthNode::new(start_ent, start_vert.0)];
     let mut visited = VisitedNodes::new_from_start(start_ent);
 
     while let Some(mut node) = search_queue.pop() {
@@ -170,12 +426,12 @@ where
     FE: Fn(&CE) -> bool,
 {
     let max_paths = max_ends.unwrap_or(usize::MAX);
-    let start_vert = query.get(start_ent)?;
+    let start_vert = get_start(query, start_ent)?;
 
     let mut found_paths = Vec::new();
     if end_determiner(start_vert.1) {found_paths.push(GraphPath::single(start_ent, ()))};
 
-    let mut search_queue: Vec<DepthNode<V>> = vec![DepthNode::new(start_ent, start_vert.0)];
+    let mut search_queue: Vec<DepthNode> = vec![DepthNode::new(start_ent, start_vert.0)];
     let mut visited = VisitedNodes::new_from_start(start_ent);
 
     while let Some(mut node) = search_queue.pop() {
@@ -202,21 +458,319 @@ where
 }
 
 
+/// Determines whether there is a path from the start vertex to the end vertex, without constructing it
+/// 
+/// Runs a depth-first search that only tracks which vertices have been visited, rather than the previous vertex of each, so is cheaper than 
+/// [`dfs`] when the path itself is not needed. Returns `true` as soon as the end vertex is reached.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that checks whether the exit can still be reached
+/// fn check_exit_reachable(
+///     player: Query<&CurrentTile, With<Player>>,
+///     exit: Query<Entity, With<ExitMarker>>,
+///     tiles: Query<&VertexType, Without<Blocked>>
+/// ) {
+///     if !are_connected(&tiles, player.single().0, exit.single()).unwrap() {
+///         println!("The exit has been cut off!");
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`dfs`]: For a depth-first search that returns the path found
+pub fn are_connected<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<bool, GraphError> {
+    if search_preamble(query, start_ent, end_ent, ())?.is_some() {return Ok(true);}
+
+    let mut to_search: Vec<Entity> = vec![start_ent];
+    let mut visited: HashSet<Entity> = HashSet::new();
+    visited.insert(start_ent);
+
+    while let Some(sv_ent) = to_search.pop() {
+        let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+        for neighbour_ent in sv_vert.get_neighbours() {
+            if neighbour_ent == end_ent {return Ok(true);}
+            if visited.insert(neighbour_ent) {to_search.push(neighbour_ent);}
+        }
+    }
+
+    Ok(false)
+}
+
+
+
+/// Determines whether there is a path from the start vertex to the end vertex using only edges with weight at most `max_edge_weight`
+/// 
+/// Identical to [`are_connected`], except that any edge heavier than `max_edge_weight` is treated as if it doesn't exist. Unlike a bound on
+/// the total distance, this limits each edge individually, eg whether a vehicle can reach the end without crossing a bridge that is too weak for it.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that checks whether a heavy truck can reach the depot
+/// fn check_truck_route(
+///     truck: Query<(&CurrentJunction, &Truck)>,
+///     depot: Query<Entity, With<Depot>>,
+///     roads: Query<&RoadVertex>
+/// ) {
+///     let (junction, truck) = truck.single();
+///     if !reachable_with_max_edge(&roads, junction.0, depot.single(), truck.max_load).unwrap() {
+///         println!("The truck is too heavy to reach the depot!");
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`are_connected`]: For the same check using every edge
+pub fn reachable_with_max_edge<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    max_edge_weight: f32
+) -> Result<bool, GraphError> {
+    if search_preamble(query, start_ent, end_ent, ())?.is_some() {return Ok(true);}
+
+    let mut to_search: Vec<Entity> = vec![start_ent];
+    let mut visited: HashSet<Entity> = HashSet::new();
+    visited.insert(start_ent);
+
+    while let Some(sv_ent) = to_search.pop() {
+        let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+        for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight() {
+            //refuse to cross any edge that is too heavy
+            if edge_weight > max_edge_weight {continue;}
+            if neighbour_ent == end_ent {return Ok(true);}
+            if visited.insert(neighbour_ent) {to_search.push(neighbour_ent);}
+        }
+    }
+
+    Ok(false)
+}
 
 
 
+/// Counts the vertices reachable from the start vertex, including the start vertex itself
+/// 
+/// This is the cheapest traversal in the crate, as it only keeps the set of visited vertices rather than any paths or distances. Useful 
+/// for quick connectivity metrics, eg how large the component containing a vertex is. Edges to entities not in the query are ignored.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that checks how much of the map a newly spawned unit can reach
+/// fn check_spawn(
+///     units: Query<&CurrentTile, Added<Unit>>,
+///     tiles: Query<&TileVertex>
+/// ) {
+///     for tile in units.iter() {
+///         let reachable = reachable_count(&tiles, tile.0).unwrap();
+///         if reachable < 10 {println!("Unit spawned in a tiny area of only {} tiles!", reachable);}
+///     }
+/// }
+/// ```
+pub fn reachable_count<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity
+) -> Result<usize, GraphError> {
+    let start_vert = get_start(query, start_ent)?;
+
+    let mut to_search: Vec<&V> = vec![start_vert];
+    let mut visited: HashSet<Entity> = HashSet::new();
+    visited.insert(start_ent);
+
+    while let Some(sv_vert) = to_search.pop() {
+        for neighbour_ent in sv_vert.get_neighbours() {
+            if visited.contains(&neighbour_ent) {continue;}
+            //only count vertices that are in the query
+            let Ok(neighbour_vert) = query.get(neighbour_ent) else {continue;};
+            visited.insert(neighbour_ent);
+            if !neighbour_vert.is_sink() {to_search.push(neighbour_vert);}
+        }
+    }
+
+    Ok(visited.len())
+}
+
+
+/// Returns every vertex reachable from the start vertex, in the order a depth-first search first discovers them
+/// 
+/// This is the pre-order of the depth-first search: the start vertex comes first, and each vertex is followed by everything first discovered
+/// beneath it before the search backs up. Neighbours are searched in the order given by [`GraphVertex::get_neighbours`], as in [`dfs`], so
+/// the order is the same as a recursive depth-first search would give. Useful for serialising or walking a graph, eg a tree, in a consistent
+/// order. Edges to entities outside the query are ignored.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that prints a skill tree, with each skill after the skill it is unlocked by
+/// fn print_skill_tree(
+///     root: Res<RootSkill>,
+///     skills: Query<(&SkillVertex, &Name)>
+/// ) {
+///     let mut lens = skills.transmute_lens::<&SkillVertex>();
+///     for skill in dfs_preorder(&lens.query(), root.0).unwrap() {
+///         println!("{}", skills.get(skill).unwrap().1);
+///     }
+/// }
+/// ```
+pub fn dfs_preorder<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity
+) -> Result<Vec<Entity>, GraphError> {
+    let start_vert = get_start(query, start_ent)?;
+
+    let mut search_queue: Vec<DepthNode> = vec![DepthNode::new(start_ent, start_vert)];
+    let mut visited: HashSet<Entity> = HashSet::new();
+    visited.insert(start_ent);
+    let mut order: Vec<Entity> = vec![start_ent];
+
+    while let Some(node) = search_queue.last_mut() {
+        //once every neighbour has been tried, back up to the previous vertex
+        let Some(neighbour_ent) = node.get_next_neighbour() else {
+            search_queue.pop();
+            continue;
+        };
+
+        if visited.contains(&neighbour_ent) {continue;}
+        let Ok(neighbour_vert) = query.get(neighbour_ent) else {continue;};
+        visited.insert(neighbour_ent);
+        order.push(neighbour_ent);
+        search_queue.push(DepthNode::new(neighbour_ent, neighbour_vert));
+    }
+
+    Ok(order)
+}
+
+/// The result of advancing a [`DfsState`]
+pub enum DfsProgress {
+    /// The search used its budget without finishing, so should be stepped again
+    InProgress,
+    /// The end vertex was found, with the path to it in **reverse order**
+    Found(GraphPath<()>),
+    /// Every vertex reachable from the start vertex was searched without finding the end vertex
+    Exhausted
+}
+
+/// A depth-first search that can be paused and resumed, to spread a large search over several frames
+/// 
+/// Each call to [`DfsState::step`] searches at most a given number of vertices before returning, keeping the stack of vertices being searched
+/// and the vertices already visited for the next call. Stepping until the search finishes gives the same path as [`dfs`]. Unlike a search 
+/// with a budget, such as [`dfs_budgeted`], no work is thrown away when the budget runs out.
+/// 
+/// The state doesn't borrow the query, so it can be kept in a component or resource between frames. The neighbours of each vertex on the
+/// stack are copied when it is searched, so the graph should not change while the search is in progress, otherwise the search may follow
+/// edges that no longer exist or miss ones that were added.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that searches a huge maze for the exit, searching at most 100 rooms each frame
+/// fn search_maze(
+///     mut search: ResMut<MazeSearch>,
+///     rooms: Query<&RoomVertex>
+/// ) {
+///     match search.state.step(&rooms, search.exit, 100) {
+///         DfsProgress::InProgress => {},
+///         DfsProgress::Found(path) => println!("Found the exit: {}", path),
+///         DfsProgress::Exhausted => println!("The maze has no exit!")
+///     }
+/// }
+/// ```
+pub struct DfsState {
+    start_ent: Entity,
+    /// The vertices currently being searched, with their neighbours and how many of them have been tried so far
+    search_stack: Vec<DepthNode>,
+    visited: VisitedNodes
+}
+
+impl DfsState {
+    /// Creates a search starting at the given vertex, which has not yet searched any vertices
+    /// 
+    /// # Errors
+    /// 
+    /// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+    pub fn new<V: GraphVertex>(query: &Query<&V>, start_ent: Entity) -> Result<Self, GraphError> {
+        let start_vert = get_start(query, start_ent)?;
+        Ok(Self {
+            start_ent,
+            search_stack: vec![DepthNode::new(start_ent, start_vert)],
+            visited: VisitedNodes::new_from_start(start_ent)
+        })
+    }
+
+    /// Continues the search for the end vertex, returning once it is found, there is nothing left to search, or `budget` vertices have 
+    /// been searched during this call
+    /// 
+    /// The same end vertex should be given to every call. An end vertex that isn't in the query is never found, so the search is 
+    /// [`Exhausted`](DfsProgress::Exhausted) once every reachable vertex has been searched. Once the search has finished it should not be 
+    /// stepped again.
+    pub fn step<V: GraphVertex>(&mut self, query: &Query<&V>, end_ent: Entity, budget: usize) -> DfsProgress {
+        if end_ent == self.start_ent {return DfsProgress::Found(GraphPath::single(self.start_ent, ()));}
+        let mut expansions = 0;
+
+        while let Some(node) = self.search_stack.last_mut() {
+            //check if we have any neighbours left to search from this vertex
+            let Some(&neighbour_ent) = node.neighbours.get(node.neighbours_visited) else {self.search_stack.pop(); continue;};
+            let previous = node.ent;
+            if self.visited.is_visited(&neighbour_ent) {node.neighbours_visited += 1; continue;}
+
+            //a vertex outside the query or a sink can't lead to the end vertex, so there is no need to search it
+            let to_search = if neighbour_ent == end_ent {None} else {query.get(neighbour_ent).ok().filter(|vert| !vert.is_sink())};
+            //pause before searching another vertex once the budget is spent, leaving it to be searched by the next call
+            if to_search.is_some() && expansions >= budget {return DfsProgress::InProgress;}
+            node.neighbours_visited += 1;
+            self.visited.insert(neighbour_ent, previous, 0, 0.0);
+
+            if neighbour_ent == end_ent {return DfsProgress::Found(self.visited.determine_path(neighbour_ent).expect("The created path should be valid"));}
+
+            let Some(neighbour_vert) = to_search else {continue;};
+            expansions += 1;
+            self.search_stack.push(DepthNode::new(neighbour_ent, neighbour_vert));
+        }
+
+        DfsProgress::Exhausted
+    }
+}
 
-struct DepthNode<'a, V>{
+/// A vertex on the stack of a depth-first search, with its neighbours fetched once when it is added
+struct DepthNode{
     pub ent: Entity,
-    pub vertex: &'a V,
+    pub neighbours: Vec<Entity>,
     pub neighbours_visited: usize,
 }
 
-impl<'a, V:GraphVertex> DepthNode<'a, V>{
-    fn new(ent: Entity, vertex: &'a V) -> Self {
+impl DepthNode{
+    fn new<V: GraphVertex>(ent: Entity, vertex: &V) -> Self {
         Self {
             ent, 
-            vertex, 
+            neighbours: vertex.get_neighbours(), 
             neighbours_visited: 0
         }
     }
@@ -224,6 +778,6 @@ impl<'a, V:GraphVertex> DepthNode<'a, V>{
     fn get_next_neighbour(&mut self) -> Option<Entity>{
         let to_visit =  self.neighbours_visited;
         self.neighbours_visited += 1;
-        self.vertex.get_neighbours().get(to_visit).map(|val| *val)
+        self.neighbours.get(to_visit).copied()
     }
 }
@@ -1,11 +1,11 @@
-use std::cmp::Reverse;
+use std::{cmp::Reverse, collections::BinaryHeap};
 
-use bevy::{prelude::{Component, Entity, Query}, utils::HashMap};
+use bevy::{prelude::{Component, Entity, Query}, utils::{hashbrown::hash_map::Entry, HashMap, HashSet}};
 use priority_queue::PriorityQueue;
 
 use crate::graph_vertex::GraphVertex;
 
-use super::{GraphError, GraphPath, PathWeight};
+use super::{helper::{check_same_component, check_start_edges, check_tolerance, directed_neighbours, get_start, reverse_adjacency, search_preamble}, implicit::implicit_a_star_by, Direction, GraphError, GraphPath, Heuristic, InvalidPathError, PathWeight, VisitedNodes};
 
 
 /// Runs Dijkstra's algorithm to find the path minimising total edge weight between two vertices, returning the path in **reverse order**
@@ -13,9 +13,17 @@ use super::{GraphError, GraphPath, PathWeight};
 /// Dijkstra's algorithm on the directed graph with vertices in the provided query, this will result in a path that minimises distance,
 /// not one that minimises distance. This algorithm does not work for graphs with negative weight edges.
 /// 
+/// The result is deterministic: when several vertices are at the same distance they are searched in order of their [`Entity::index`], 
+/// and a vertex keeps the first path found to it when later paths are no shorter. This means the same graph always gives the same path,
+/// regardless of the order vertices list their neighbours in, which matters for lockstep multiplayer.
+/// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start or end vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
 /// 
 /// [`GraphError::NoPath`]: If a path could not be found.
 /// 
@@ -56,9 +64,240 @@ pub fn dijkstra_search<V: GraphVertex>(
     start_ent: Entity,
     end_ent: Entity
 ) -> Result<GraphPath<f32>, GraphError> {
-    //test for invalid start or end
-    query.get(start_ent)?;
-    query.get(end_ent)?;
+    dijkstra_search_checked(query, start_ent, end_ent, false, None, 0.0, |_, _, _| {})
+}
+
+/// Runs Dijkstra's algorithm, returning an error rather than skipping any edge to an entity that is not in the query
+/// 
+/// Identical to [`dijkstra_search`], except that the destination of every edge found during the search must be a vertex in the query. 
+/// This is useful to catch edges left pointing at despawned entities, which [`dijkstra_search`] silently ignores. Note this means vertices 
+/// excluded by a query filter are also errors, so this should be used with queries containing every vertex.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity, or the destination of an edge found during the search, does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # See also
+/// 
+/// [`bfs_strict`](super::bfs::bfs_strict): For the strict version of breadth-first search
+pub fn dijkstra_search_strict<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<GraphPath<f32>, GraphError> {
+    dijkstra_search_checked(query, start_ent, end_ent, true, None, 0.0, |_, _, _| {})
+}
+
+/// Runs Dijkstra's algorithm that gives up after searching the given number of vertices, returning the path in **reverse order**
+/// 
+/// Identical to [`dijkstra_search`], except that once `max_expansions` vertices have been searched without reaching the end vertex, 
+/// [`GraphError::BudgetExceeded`] is returned. This bounds the work done by a search on a huge or untrusted graph, regardless of its shape,
+/// unlike a maximum distance. A budget of [`None`] never gives up.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// [`GraphError::BudgetExceeded`]: If the search was stopped after searching `max_expansions` vertices.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds paths on player made maps, giving up rather than stalling the frame on a huge map
+/// fn custom_map_path_finder(
+///     journey: Res<Journey>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     match dijkstra_search_budgeted(&tiles, journey.start, journey.end, Some(10_000)) {
+///         Ok(path) => println!("Found path: {}", path),
+///         Err(GraphError::BudgetExceeded) => println!("The map is too large to path find on!"),
+///         Err(_) => println!("No path found!")
+///     }
+/// }
+/// ```
+pub fn dijkstra_search_budgeted<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    max_expansions: Option<usize>
+) -> Result<GraphPath<f32>, GraphError> {
+    dijkstra_search_checked(query, start_ent, end_ent, false, max_expansions, 0.0, |_, _, _| {})
+}
+
+/// Runs Dijkstra's algorithm, calling `on_relax` every time a shorter path to a vertex is found, returning the path in **reverse order**
+/// 
+/// Identical to [`dijkstra_search`], except that `on_relax(from, to, new_dist)` is called whenever the distance to `to` improves, including
+/// when it is first found, where `from` is the vertex being searched and `new_dist` the new distance from the start vertex. This exposes how
+/// the search progresses, eg to log it, count how much work it does, or draw the frontier while tuning a map.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that draws every improvement Dijkstra's algorithm makes, to show how it spreads out from the start
+/// fn show_search(
+///     journey: Res<Journey>,
+///     tiles: Query<&VertexType>,
+///     positions: Query<&Transform>,
+///     mut gizmos: Gizmos
+/// ) {
+///     let _ = dijkstra_with_relax_hook(&tiles, journey.start, journey.end, |from, to, _| {
+///         let (from_pos, to_pos) = (positions.get(from).unwrap(), positions.get(to).unwrap());
+///         gizmos.line(from_pos.translation, to_pos.translation, Color::WHITE);
+///     });
+/// }
+/// ```
+pub fn dijkstra_with_relax_hook<V, F>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    on_relax: F
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    F: FnMut(Entity, Entity, f32)
+{
+    dijkstra_search_checked(query, start_ent, end_ent, false, None, 0.0, on_relax)
+}
+
+/// Runs Dijkstra's algorithm, treating distances within `tolerance` of each other as equal, returning the path in **reverse order**
+/// 
+/// Identical to [`dijkstra_search`], except that a vertex only takes a new path when it is shorter than the path already found by more than
+/// `tolerance`. Summing float weights in a different order can give totals that differ in the last few bits, so two routes that should tie
+/// may not, and which is returned then depends on rounding. With a small tolerance such near ties keep the first path found instead, as exact 
+/// ties do. [`dijkstra_search`] uses a tolerance of `0.0`.
+/// 
+/// The path found may be longer than the shortest path by up to `tolerance` for each vertex on it, so the tolerance should be far smaller 
+/// than any edge weight. It must not be negative, as then a path could replace one that is no shorter.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::InvalidTolerance`]: If `tolerance` is negative or NaN.
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that paths units over a map with fractional road lengths, where every client must pick the same route
+/// fn path_units(
+///     roads: Query<&RoadVertex>,
+///     mut units: Query<(&CurrentJunction, &Target, &mut UnitPath)>
+/// ) {
+///     for (junction, target, mut unit_path) in units.iter_mut() {
+///         unit_path.0 = dijkstra_search_with_tolerance(&roads, junction.0, target.0, 1e-4).ok();
+///     }
+/// }
+/// ```
+pub fn dijkstra_search_with_tolerance<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    tolerance: f32
+) -> Result<GraphPath<f32>, GraphError> {
+    check_tolerance(tolerance)?;
+    dijkstra_search_checked(query, start_ent, end_ent, false, None, tolerance, |_, _, _| {})
+}
+
+/// Dijkstra's algorithm, which when strict returns [`GraphError::InvalidEntity`] on finding an edge to an entity not in the query,
+/// gives up after searching `max_expansions` vertices, only replaces paths with ones shorter by more than `tolerance`, 
+/// and calls `on_relax` whenever a vertex's distance improves
+fn dijkstra_search_checked<V, F>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    strict: bool,
+    max_expansions: Option<usize>,
+    tolerance: f32,
+    mut on_relax: F
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    F: FnMut(Entity, Entity, f32)
+{
+    //test for invalid start or end, and an instant finish
+    if let Some(path) = search_preamble(query, start_ent, end_ent, 0.0)? {return Ok(path);}
+    check_start_edges(get_start(query, start_ent)?)?;
+
+    //entities that aren't vertices have no neighbours, so are found but never searched
+    let graph = |ent: Entity| query.get(ent).map_or_else(|_| Vec::new(), |vert| vert.get_neighbours_with_weight());
+    //ties in distance are broken by the entity index
+    let priority = |ent: &Entity, dist: PathWeight, _, _| (dist, ent.index());
+    let path = implicit_a_star_by(&graph, start_ent, end_ent, |_| Heuristic{value: 0.0}, priority, PathWeight::new(f32::INFINITY), tolerance, max_expansions, |&from, &to, dist| {
+        if strict {query.get(to)?;}
+        on_relax(from, to, dist);
+        Ok(())
+    })?;
+    Ok(GraphPath::new(path))
+}
+
+/// Runs Dijkstra's algorithm using a binary heap with lazy deletion, returning the path in **reverse order**
+/// 
+/// Gives the same result as [`dijkstra_search`], including how ties are broken, but rather than updating a vertex's priority when a shorter path 
+/// to it is found, the vertex is pushed to the heap again and the outdated entry is skipped when it is popped. This avoids the hash lookups of
+/// [`PriorityQueue::change_priority`] at the cost of a larger heap.
+/// 
+/// Searching corner to corner on a 200x200 grid with edge weights between 1 and 9, this takes about 20.0ms against 23.9ms for [`dijkstra_search`]
+/// (see `cargo bench --bench search`).
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # See also
+/// 
+/// [`dijkstra_search`]: For the priority queue based implementation
+pub fn dijkstra_search_heap<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<GraphPath<f32>, GraphError> {
+    //test for invalid start or end, and an instant finish
+    if let Some(path) = search_preamble(query, start_ent, end_ent, 0.0)? {return Ok(path);}
+    check_start_edges(get_start(query, start_ent)?)?;
 
     //hashmap that stores the previous vertex of the path for a given vertex
     let mut path_previous: HashMap<Entity, Option<Entity>> = HashMap::new();
@@ -68,14 +307,17 @@ pub fn dijkstra_search<V: GraphVertex>(
     let mut minimal_dist : HashMap<Entity, PathWeight> = HashMap::new();
     minimal_dist.insert(start_ent, PathWeight{weight: 0.0});
 
-    //create the search queue
-    let mut search_queue: PriorityQueue<Entity , Reverse<PathWeight>> = PriorityQueue::new();
-    search_queue.push(start_ent, Reverse(PathWeight{weight: 0.0}));
+    //create the search heap, with ties in distance broken by the entity index
+    let mut search_heap: BinaryHeap<Reverse<(PathWeight, u32, Entity)>> = BinaryHeap::new();
+    search_heap.push(Reverse((PathWeight{weight: 0.0}, start_ent.index(), start_ent)));
+
+    while let Some(Reverse((sv_dist, _, sv_ent))) = search_heap.pop() {
+        //skip entries that were superseded by a shorter path after being pushed
+        if sv_dist > minimal_dist[&sv_ent] {continue;}
 
-    while let Some((sv_ent, Reverse(sv_dist))) = search_queue.pop() {
         //check if we are currently searching the end vertex, as this implies we have already found the minimum path
         if sv_ent == end_ent {
-            return Ok(determine_path(path_previous, sv_ent).unwrap());
+            return Ok(determine_path(&path_previous, &minimal_dist, sv_ent).expect("The created path should be valid"));
         }
 
         //get the GraphVertex info of the search vertex
@@ -83,27 +325,140 @@ pub fn dijkstra_search<V: GraphVertex>(
 
         //loop over this vertex's neighbours
         for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight(){
+            if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+
+            //Determine the distance to this neighbour via the path to the search vertex
+            let total_dist = sv_dist + edge_weight;
+
+            //only keep strictly shorter paths, so ties keep the first path found as in dijkstra_search
+            if minimal_dist.get(&neighbour_ent).is_some_and(|dist| total_dist >= *dist) {continue;}
+            path_previous.insert(neighbour_ent, Some(sv_ent));
+            minimal_dist.insert(neighbour_ent, total_dist);
+            search_heap.push(Reverse((total_dist, neighbour_ent.index(), neighbour_ent)));
+        }
+    }
+
+    //if we get to this point, then we must have found no path
+    Err(GraphError::NoPath)
+}
+
+/// Runs Dijkstra's algorithm with each edge weight given by the provided function, returning the path in **reverse order**
+/// 
+/// Identical to [`dijkstra_search`], except the weight of each edge is `weight_of(from, to)`, using [`GraphVertex::get_neighbours_weighted_by`], 
+/// rather than the weight stored in the vertex. This allows the vertex to only model connectivity, with the weights kept elsewhere.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If `weight_of` gives an edge a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that paths units around the current congestion, which changes every frame and is kept in a resource
+/// fn path_around_traffic(
+///     roads: Query<&Junction>,
+///     traffic: Res<Congestion>,
+///     mut units: Query<(&CurrentJunction, &Target, &mut UnitPath)>
+/// ) {
+///     for (junction, target, mut unit_path) in units.iter_mut() {
+///         unit_path.0 = dijkstra_search_with_weights(&roads, junction.0, target.0, |from, to| traffic.cost(from, to)).ok();
+///     }
+/// }
+/// ```
+pub fn dijkstra_search_with_weights<V, W>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    weight_of: W
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    V: GraphVertex,
+    W: Fn(Entity, Entity) -> f32
+{
+    dijkstra_search_by(start_ent, end_ent, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours_weighted_by(ent, &weight_of)))
+}
 
+/// Runs Dijkstra's algorithm over the neighbours returned by the provided function, returning the path in **reverse order**
+/// 
+/// Identical to [`dijkstra_search`], except the weighted neighbours of each vertex are given by `neighbours_of`, which should return [`None`] for 
+/// any entity that isn't a vertex. This allows searching graphs made up of several different [`GraphVertex`] types in a single call.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If `neighbours_of` returns [`None`] for the start vertex.
+/// 
+/// [`GraphError::InvalidEntity`]: If `neighbours_of` returns [`None`] for the end vertex.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and `neighbours_of` returns no neighbours for the start vertex.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If `neighbours_of` provides an edge with a negative weight
+/// 
+/// # See also
+/// 
+/// [`bfs_by`](super::bfs::bfs_by): For a breadth-first search with a neighbour function
+pub fn dijkstra_search_by<N>(
+    start_ent: Entity,
+    end_ent: Entity,
+    neighbours_of: N
+) -> Result<GraphPath<f32>, GraphError> 
+where
+    N: Fn(Entity) -> Option<Vec<(Entity, f32)>>
+{
+    let start_neighbours = neighbours_of(start_ent).ok_or(GraphError::StartBlocked)?;
+    neighbours_of(end_ent).ok_or(GraphError::InvalidEntity(end_ent))?;
+    if start_ent == end_ent {return Ok(GraphPath::single(start_ent, 0.0));}
+    if start_neighbours.is_empty() {return Err(GraphError::IsolatedStart);}
+
+    //hashmap that stores the previous vertex of the path for a given vertex
+    let mut path_previous: HashMap<Entity, Option<Entity>> = HashMap::new();
+    path_previous.insert(start_ent, None);
+
+    //The list of visited entities. stores the cardinality (current minimum found distance to the vertex)
+    let mut minimal_dist : HashMap<Entity, PathWeight> = HashMap::new();
+    minimal_dist.insert(start_ent, PathWeight{weight: 0.0});
+
+    //create the search queue, with ties in distance broken by the entity index
+    let mut search_queue: PriorityQueue<Entity , Reverse<(PathWeight, u32)>> = PriorityQueue::new();
+    search_queue.push(start_ent, Reverse((PathWeight{weight: 0.0}, start_ent.index())));
+
+    while let Some((sv_ent, Reverse((sv_dist, _)))) = search_queue.pop() {
+        //check if we are currently searching the end vertex, as this implies we have already found the minimum path
+        if sv_ent == end_ent {
+            return Ok(determine_path(&path_previous, &minimal_dist, sv_ent).expect("The created path should be valid"));
+        }
+
+        //get the neighbours of the search vertex
+        let Some(sv_neighbours) = neighbours_of(sv_ent) else {continue;};
+
+        //loop over this vertex's neighbours
+        for (neighbour_ent, edge_weight) in sv_neighbours {
             if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
 
-            //Determine the distance to this neighbour via the path to the searvh vertex
+            //Determine the distance to this neighbour via the path to the search vertex
             let total_dist = sv_dist + edge_weight;
 
             //check if we have visited this vertex before
             //if so, compare the cardinalities to see if we should update
             if let Some(dist) = minimal_dist.get_mut(&neighbour_ent) {
-                //check if the vertex was visited already at a closer distance
-                //if so we ignore this vertex
-                if total_dist > *dist {continue;}
-                //otherwise update the vertex's distance, previous vertex and priority in the queue
+                //keep the first path found when later paths are no shorter, so ties are resolved deterministically
+                if total_dist >= *dist {continue;}
                 *path_previous.get_mut(&neighbour_ent).unwrap() = Some(sv_ent);
                 *dist = total_dist;
-                search_queue.change_priority(&neighbour_ent, Reverse(total_dist));
+                search_queue.change_priority(&neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
             } else {
-                //otherwise the vertex hasnt been visited before and so we add it to the queue, path_previous and the visited entities
                 path_previous.insert(neighbour_ent, Some(sv_ent));
                 minimal_dist.insert(neighbour_ent, total_dist);
-                search_queue.push(neighbour_ent, Reverse(total_dist));
+                search_queue.push(neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
             }
         }
     }
@@ -112,17 +467,521 @@ pub fn dijkstra_search<V: GraphVertex>(
     Err(GraphError::NoPath)
 }
 
+/// Finds a path minimising its heaviest edge between two vertices, returning the path in **reverse order** alongside the weight of that edge
+/// 
+/// A modified Dijkstra's algorithm where the priority of a path is its heaviest edge, rather than the sum of its edges. This gives minimax, or
+/// bottleneck, paths: eg with edge weights as congestion, the route avoiding the single worst stretch of road. The returned path is not necessarily
+/// the shortest among those with the same bottleneck. If the start and end are the same vertex, the bottleneck is 0.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::IsolatedStart`]: If the start and end vertex differ, and the start vertex has no outgoing edges.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that routes a convoy to avoid the most dangerous roads
+/// fn route_convoy(
+///     convoy: Query<(&CurrentJunction, &Destination), With<Convoy>>,
+///     roads: Query<&DangerVertex>
+/// ) {
+///     let (junction, destination) = convoy.single();
+///     if let Ok((route, worst_danger)) = bottleneck_path(&roads, junction.0, destination.0) {
+///         println!("The most dangerous road on the route has danger {}", worst_danger);
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`dijkstra_search`]: For the path minimising the total edge weight
+/// 
+/// [`bottleneck_tree`]: For the bottleneck to every vertex at once
+pub fn bottleneck_path<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<(GraphPath<()>, f32), GraphError> {
+    if let Some(path) = search_preamble(query, start_ent, end_ent, ())? {return Ok((path, 0.0));}
+    check_start_edges(get_start(query, start_ent)?)?;
+
+    let (visited, bottlenecks) = bottleneck_search(query, start_ent, Some(end_ent))?;
+    let Some(&bottleneck) = bottlenecks.get(&end_ent) else {return Err(GraphError::NoPath);};
+    Ok((visited.determine_path(end_ent).expect("The created path should be valid"), bottleneck))
+}
+
+/// Finds the smallest possible heaviest edge of a path from the start vertex to every vertex it can reach
+/// 
+/// The single source version of [`bottleneck_path`], giving for each reachable vertex, including the start vertex at 0, the least value of the
+/// heaviest edge over all paths to it from the start. With edge weights as costs this is the cheapest each vertex can be reached when only the
+/// worst edge matters, and with the edges of a minimum spanning tree it gives the single-linkage distance used in clustering. Vertices that
+/// can't be reached, or that don't appear in the query, are left out.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds which towns a convoy can reach from its base without using a road more dangerous than it can survive
+/// fn reachable_towns(
+///     convoy: Query<(&Base, &Armour), With<Convoy>>,
+///     roads: Query<&DangerVertex>
+/// ) {
+///     let (base, armour) = convoy.single();
+///     let bottlenecks = bottleneck_tree(&roads, base.0).unwrap();
+///     let safe: Vec<Entity> = bottlenecks.into_iter().filter(|(_, danger)| *danger <= armour.0).map(|(ent, _)| ent).collect();
+/// }
+/// ```
+pub fn bottleneck_tree<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity
+) -> Result<HashMap<Entity, f32>, GraphError> {
+    get_start(query, start_ent)?;
+    Ok(bottleneck_search(query, start_ent, None)?.1)
+}
+
+/// Dijkstra's algorithm minimising the heaviest edge of each path rather than its total weight, stopping once the end vertex is searched if
+/// one is given, returning the paths found and the final bottleneck of every vertex that was searched
+fn bottleneck_search<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Option<Entity>
+) -> Result<(VisitedNodes, HashMap<Entity, f32>), GraphError> {
+    let mut visited = VisitedNodes::new_from_start(start_ent);
+
+    //the current smallest heaviest edge of any path found to each vertex
+    let mut minimal_bottleneck: HashMap<Entity, PathWeight> = HashMap::new();
+    minimal_bottleneck.insert(start_ent, PathWeight{weight: 0.0});
+
+    //the vertices that have been searched, whose bottleneck is therefore final
+    let mut searched: HashMap<Entity, f32> = HashMap::new();
+
+    //create the search queue, with ties in bottleneck broken by the entity index
+    let mut search_queue: PriorityQueue<Entity , Reverse<(PathWeight, u32)>> = PriorityQueue::new();
+    search_queue.push(start_ent, Reverse((PathWeight{weight: 0.0}, start_ent.index())));
+
+    while let Some((sv_ent, Reverse((sv_bottleneck, _)))) = search_queue.pop() {
+        //the first time the end vertex is searched, no path can have a smaller bottleneck
+        if Some(sv_ent) == end_ent {
+            searched.insert(sv_ent, sv_bottleneck.value());
+            break;
+        }
+
+        let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+        searched.insert(sv_ent, sv_bottleneck.value());
+
+        for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight(){
+            if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+
+            //the heaviest edge of the path to this neighbour via the search vertex
+            let path_bottleneck = sv_bottleneck.max(PathWeight::new(edge_weight));
+
+            if let Some(bottleneck) = minimal_bottleneck.get_mut(&neighbour_ent) {
+                if path_bottleneck >= *bottleneck {continue;}
+                *bottleneck = path_bottleneck;
+                search_queue.change_priority(&neighbour_ent, Reverse((path_bottleneck, neighbour_ent.index())));
+            } else {
+                minimal_bottleneck.insert(neighbour_ent, path_bottleneck);
+                search_queue.push(neighbour_ent, Reverse((path_bottleneck, neighbour_ent.index())));
+            }
+            visited.insert(neighbour_ent, sv_ent, 0, path_bottleneck.value());
+        }
+    }
+    Ok((visited, searched))
+}
+
+/// Runs Dijkstra's algorithm from the start vertex to every vertex it can reach, returning the shortest path tree
+/// 
+/// The result maps each reachable vertex, including the start vertex, to its distance from the start and the previous vertex on a shortest path
+/// to it, which is [`None`] only for the start vertex. Ties are broken in the same way as [`dijkstra_search`].
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that precomputes the distance from the town hall to every building
+/// fn town_hall_distances(
+///     town_hall: Query<Entity, With<TownHall>>,
+///     roads: Query<&RoadVertex>,
+///     mut distances: ResMut<TownHallDistances>
+/// ) {
+///     distances.0 = dijkstra_tree(&roads, town_hall.single()).unwrap();
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`meeting_point`]: For combining the trees of two vertices
+pub fn dijkstra_tree<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity
+) -> Result<HashMap<Entity, (f32, Option<Entity>)>, GraphError> {
+    get_start(query, start_ent)?;
+    dijkstra_tree_by(start_ent, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours_with_weight()))
+}
+
+/// Dijkstra's algorithm over the neighbours returned by the provided function, which should return [`None`] for any entity that isn't a vertex,
+/// returning the shortest path tree as in [`dijkstra_tree`]
+pub(crate) fn dijkstra_tree_by<N>(
+    start_ent: Entity,
+    neighbours_of: N
+) -> Result<HashMap<Entity, (f32, Option<Entity>)>, GraphError> 
+where
+    N: Fn(Entity) -> Option<Vec<(Entity, f32)>>
+{
+    //the finished tree, containing every vertex that has been searched
+    let mut tree: HashMap<Entity, (f32, Option<Entity>)> = HashMap::new();
+
+    //the current best distance and previous vertex of every vertex found but not yet searched
+    let mut frontier: HashMap<Entity, (PathWeight, Option<Entity>)> = HashMap::new();
+    frontier.insert(start_ent, (PathWeight{weight: 0.0}, None));
+
+    //create the search queue, with ties in distance broken by the entity index
+    let mut search_queue: PriorityQueue<Entity , Reverse<(PathWeight, u32)>> = PriorityQueue::new();
+    search_queue.push(start_ent, Reverse((PathWeight{weight: 0.0}, start_ent.index())));
+
+    while let Some((sv_ent, Reverse((sv_dist, _)))) = search_queue.pop() {
+        let (_, sv_previous) = frontier.remove(&sv_ent).expect("Queued vertices should be in the frontier");
+
+        //vertices outside the query aren't part of the tree
+        let Some(sv_neighbours) = neighbours_of(sv_ent) else {continue;};
+        tree.insert(sv_ent, (sv_dist.value(), sv_previous));
+
+        for (neighbour_ent, edge_weight) in sv_neighbours {
+            if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+            if tree.contains_key(&neighbour_ent) {continue;}
+
+            //Determine the distance to this neighbour via the path to the search vertex
+            let total_dist = sv_dist + edge_weight;
+
+            if let Some((dist, previous)) = frontier.get_mut(&neighbour_ent) {
+                //keep the first path found when later paths are no shorter
+                if total_dist >= *dist {continue;}
+                *dist = total_dist;
+                *previous = Some(sv_ent);
+                search_queue.change_priority(&neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
+            } else {
+                frontier.insert(neighbour_ent, (total_dist, Some(sv_ent)));
+                search_queue.push(neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
+            }
+        }
+    }
+
+    Ok(tree)
+}
+
+/// Returns the shortest distance between each listed pair of (source, target) vertices, in the same order as the pairs
+/// 
+/// The pairs are grouped by source, so [`dijkstra_tree`] is run only once for each distinct source, however many targets it is paired with.
+/// This is far cheaper than running [`dijkstra_search`] for every pair when sources repeat. Unreachable targets give [`None`].
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If a source vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If a target vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that prices every delivery by the distance from its depot
+/// fn price_deliveries(
+///     mut deliveries: Query<(&Depot, &Destination, &mut Price)>,
+///     roads: Query<&RoadVertex>
+/// ) {
+///     let pairs: Vec<(Entity, Entity)> = deliveries.iter().map(|(depot, dest, _)| (depot.0, dest.0)).collect();
+///     let distances = batch_distances(&roads, &pairs).unwrap();
+///     for ((_, _, mut price), dist) in deliveries.iter_mut().zip(distances) {
+///         price.0 = dist.map(|dist| dist * PRICE_PER_DISTANCE);
+///     }
+/// }
+/// ```
+pub fn batch_distances<V: GraphVertex>(
+    query: &Query<&V>,
+    pairs: &[(Entity, Entity)]
+) -> Result<Vec<Option<f32>>, GraphError> {
+    for &(_, target_ent) in pairs {query.get(target_ent)?;}
+
+    let mut trees: HashMap<Entity, HashMap<Entity, (f32, Option<Entity>)>> = HashMap::new();
+    pairs.iter().map(|&(source_ent, target_ent)| {
+        let tree = match trees.entry(source_ent) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(dijkstra_tree(query, source_ent)?)
+        };
+        Ok(tree.get(&target_ent).map(|&(dist, _)| dist))
+    })
+    .collect()
+}
+
+/// Finds the vertex minimising the sum of its distances from two vertices, returning it alongside that sum
+/// 
+/// Runs [`dijkstra_tree`] from both vertices, so gives the best place for two agents to meet when the time taken is the sum of both journeys.
+/// Distances are measured along outgoing edges from each vertex. Ties are broken by the smaller [`Entity::index`]. Returns [`None`] if no vertex
+/// can be reached from both.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If either vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that picks where two scouts should regroup
+/// fn regroup_scouts(
+///     scouts: Res<ScoutTiles>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     if let Some((tile, _)) = meeting_point(&tiles, scouts.first, scouts.second).unwrap() {
+///         //send both scouts to the tile
+///     }
+/// }
+/// ```
+pub fn meeting_point<V: GraphVertex>(
+    query: &Query<&V>,
+    a_ent: Entity,
+    b_ent: Entity
+) -> Result<Option<(Entity, f32)>, GraphError> {
+    let a_tree = dijkstra_tree(query, a_ent)?;
+    let b_tree = dijkstra_tree(query, b_ent)?;
+
+    Ok(a_tree.iter()
+    .filter_map(|(ent, (a_dist, _))| b_tree.get(ent).map(|(b_dist, _)| (*ent, a_dist + b_dist)))
+    .min_by(|(ent, dist), (other_ent, other_dist)| dist.total_cmp(other_dist).then(ent.index().cmp(&other_ent.index()))))
+}
+
+/// Returns every vertex that lies on at least one shortest path from the start vertex to the end vertex
+/// 
+/// Combines the distances from the start vertex with the distances to the end vertex, found by following edges backwards, and keeps any vertex 
+/// whose two distances sum to the length of the shortest path. The start and end vertex are always included. Useful for showing the whole corridor 
+/// of equally good routes, rather than the single path chosen by [`dijkstra_search`]. As distances are summed in different orders, sums within
+/// a small relative tolerance of the shortest path length are accepted.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that highlights every tile the unit could walk through on a shortest route
+/// fn highlight_corridor(
+///     unit: Query<(&CurrentTile, &TargetTile)>,
+///     tiles: Query<(Entity, &VertexType)>,
+///     mut commands: Commands
+/// ) {
+///     let (current, target) = unit.single();
+///     for tile in shortest_path_vertices(&tiles, current.0, target.0).unwrap() {
+///         commands.entity(tile).insert(Highlighted);
+///     }
+/// }
+/// ```
+pub fn shortest_path_vertices<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<HashSet<Entity>, GraphError> {
+    if search_preamble(query, start_ent, end_ent, ())?.is_some() {return Ok(HashSet::from([start_ent]));}
+
+    let incoming = reverse_adjacency(query);
+    let from_start = dijkstra_tree_by(start_ent, |ent| directed_neighbours(query, &incoming, ent, Direction::Outgoing))?;
+    let Some(&(shortest, _)) = from_start.get(&end_ent) else {return Err(GraphError::NoPath);};
+    let to_end = dijkstra_tree_by(end_ent, |ent| directed_neighbours(query, &incoming, ent, Direction::Incoming))?;
+
+    //allow for rounding, as the two distances are summed along the path in a different order to the shortest distance
+    let tolerance = shortest.abs() * 1e-5;
+    Ok(from_start.into_iter()
+    .filter_map(|(ent, (start_dist, _))| {
+        let (end_dist, _) = to_end.get(&ent)?;
+        if start_dist + end_dist <= shortest + tolerance {Some(ent)} else {None}
+    })
+    .collect())
+}
+
+/// Finds up to `k` shortest loopless paths between two vertices using Yen's algorithm, returning each path in **reverse order**
+/// 
+/// The paths are returned in increasing order of total weight, starting with the path found by [`dijkstra_search`]. Each further path is found by
+/// branching off an earlier path at one of its vertices and searching for the shortest way to the end vertex that doesn't repeat a previous path.
+/// Fewer than `k` paths are returned if there are no more loopless paths.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NoPath`]: If no path could be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # See also
+/// 
+/// [`k_shortest_within`]: For only keeping the paths that aren't much longer than the shortest
+pub fn k_shortest_paths<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    k: usize
+) -> Result<Vec<GraphPath<f32>>, GraphError> {
+    k_shortest_within(query, start_ent, end_ent, k, f32::INFINITY)
+}
+
+/// Finds up to `k` shortest loopless paths between two vertices whose total weight is at most `max_ratio` times that of the shortest path,
+/// returning each path in **reverse order**
+/// 
+/// Identical to [`k_shortest_paths`], except that the search stops at the first path longer than the limit. This avoids offering alternate
+/// routes that are absurdly long compared to the best one, and saves searching for them.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NoPath`]: If no path could be found.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that offers the player up to 3 routes, none more than 25% longer than the fastest
+/// fn offer_routes(
+///     journey: Res<Journey>,
+///     roads: Query<&RoadVertex>,
+///     mut route_menu: ResMut<RouteMenu>
+/// ) {
+///     route_menu.routes = k_shortest_within(&roads, journey.start, journey.end, 3, 1.25).unwrap_or_default();
+/// }
+/// ```
+pub fn k_shortest_within<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    k: usize,
+    max_ratio: f32
+) -> Result<Vec<GraphPath<f32>>, GraphError> {
+    if k == 0 {return Ok(Vec::new());}
+    let shortest = dijkstra_search(query, start_ent, end_ent)?;
+    let limit = shortest.total_weight() * max_ratio;
+
+    //the chosen paths, and the candidates for the next path, both from start to end
+    let mut found: Vec<Vec<(Entity, f32)>> = vec![shortest.into_forward()];
+    let mut candidates: Vec<Vec<(Entity, f32)>> = Vec::new();
+
+    while found.len() < k {
+        let previous = found.last().unwrap().clone();
+        for spur_index in 0..previous.len() - 1 {
+            let (spur_ent, spur_dist) = previous[spur_index];
+            let root = &previous[..=spur_index];
+
+            //block the next edge of every found path sharing this root, so the spur path must branch off
+            let removed_edges: HashSet<(Entity, Entity)> = found.iter()
+            .filter(|path| path.len() > spur_index + 1 && path[..=spur_index].iter().map(|(ent, _)| ent).eq(root.iter().map(|(ent, _)| ent)))
+            .map(|path| (path[spur_index].0, path[spur_index + 1].0))
+            .collect();
+            //block the rest of the root, so the path stays loopless
+            let removed_vertices: HashSet<Entity> = root[..spur_index].iter().map(|&(ent, _)| ent).collect();
+
+            let spur_path = dijkstra_search_by(spur_ent, end_ent, |ent| {
+                if removed_vertices.contains(&ent) {return None;}
+                let vert = query.get(ent).ok()?;
+                Some(vert.get_neighbours_with_weight().into_iter().filter(|(neighbour_ent, _)| !removed_edges.contains(&(ent, *neighbour_ent))).collect())
+            });
+            let spur_path = match spur_path {
+                Ok(path) => path,
+                //with its next edges blocked the spur vertex may have none left, which just means there is no spur path from it
+                Err(GraphError::NoPath | GraphError::IsolatedStart) => continue,
+                Err(err) => return Err(err)
+            };
+
+            let mut candidate = root.to_vec();
+            candidate.extend(spur_path.into_forward().into_iter().skip(1).map(|(ent, dist)| (ent, spur_dist + dist)));
+            let same_path = |path: &Vec<(Entity, f32)>| path.iter().map(|(ent, _)| ent).eq(candidate.iter().map(|(ent, _)| ent));
+            if !found.iter().any(same_path) && !candidates.iter().any(same_path) {candidates.push(candidate);}
+        }
+
+        //take the shortest candidate, keeping the first found on ties
+        let Some((best_index, _)) = candidates.iter().enumerate()
+        .min_by(|(_, a), (_, b)| a.last().unwrap().1.total_cmp(&b.last().unwrap().1)) else {break;};
+        //candidates are taken in increasing order of weight, so every later path would also be too long
+        if candidates[best_index].last().unwrap().1 > limit {break;}
+        found.push(candidates.remove(best_index));
+    }
+
+    Ok(found.into_iter().map(|mut path| {path.reverse(); GraphPath::new(path)}).collect())
+}
+
+/// Runs Dijkstra's algorithm between the two vertices, first checking whether they are in the same component, returning the path in **reverse order**
+/// 
+/// Identical to [`dijkstra_search`], except that if the provided component labels (eg from [`connected_components`](super::analysis::connected_components)) 
+/// place the start and end vertex in different components, [`GraphError::NoPath`] is returned immediately rather than after exploring 
+/// everything reachable from the start. Vertices missing from the labels are searched as normal.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided end vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NoPath`]: If a path could not be found, or the vertices are in different components.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
+/// 
+/// # See also
+/// 
+/// [`dijkstra_search`]: For Dijkstra's algorithm without the component check
+pub fn dijkstra_search_prepared<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    end_ent: Entity,
+    components: &HashMap<Entity, usize>
+) -> Result<GraphPath<f32>, GraphError> {
+    check_same_component(components, start_ent, end_ent)?;
+    dijkstra_search(query, start_ent, end_ent)
+}
+
 /// Run's Dijkstra's algorithm until an endpoint, which satisfies the end determiner returns true, is found at a minimal distance from the start vertex, 
 /// returning the path in **reverse order**
 /// 
 /// Dijkstra's algorithm on the directed graph with vertices in the provided query, this will result in a path to the closest valid endpoint to the start vertex
 /// with the minimal distance achieved by said path. This algorithm does not work for graphs with negative weight edges.
+/// Ties are broken deterministically, in the same way as [`dijkstra_search`].
+/// 
+/// If a `max_distance` is provided, the search gives up once every remaining vertex is further than it from the start, bounding the cost of 
+/// searching for an endpoint that may be far away or not exist. Endpoints at exactly `max_distance` are still found.
 /// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start or end vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
 /// 
-/// [`GraphError::NoPath`]: If a path could not be found.
+/// [`GraphError::NoPath`]: If a path could not be found, including when every valid endpoint is further than `max_distance`.
 /// 
 /// [`GraphError::NegativeWeight`]: If a vertex provides an edge with a negative weight
 /// 
@@ -140,14 +999,15 @@ pub fn dijkstra_search<V: GraphVertex>(
 ///     match dijkstra_computed_end(
 ///         start_entity, 
 ///         |building: &Building|{building.is_store()}, 
-///         tiles
+///         tiles,
+///         Some(100.0)
 ///     ){
 ///         Ok(path) => {
 ///             //reverse the path to get it to start at the start entity
 ///             path.reverse();
 ///             println!("Shortest route to a store via these entities: {}", path)
 ///         },
-///         Err(_) => {println!("No Path to safety found!")} 
+///         Err(_) => {println!("No store within 100m!")} 
 ///     }
 /// }
 /// ```
@@ -161,13 +1021,15 @@ pub fn dijkstra_computed_end<V, C, F>(
     query: &Query<(&V, &C)>,
     start_ent: Entity,
     end_determiner: F,
+    max_distance: Option<f32>
 ) -> Result<GraphPath<f32>, GraphError> 
 where
     V: GraphVertex, 
     C: Component,
     F: Fn(&C) -> bool,
 {
-    query.get(start_ent)?;
+    get_start(query, start_ent)?;
+    let max_distance = PathWeight::new(max_distance.unwrap_or(f32::INFINITY));
 
     //hashmap that stores the previous vertex of the path for a given vertex
     let mut path_previous: HashMap<Entity, Option<Entity>> = HashMap::new();
@@ -177,16 +1039,19 @@ where
     let mut minimal_dist : HashMap<Entity, PathWeight> = HashMap::new();
     minimal_dist.insert(start_ent, PathWeight{weight: 0.0});
 
-    //create the search queue
-    let mut search_queue: PriorityQueue<Entity , Reverse<PathWeight>> = PriorityQueue::new();
-    search_queue.push(start_ent, Reverse(PathWeight{weight: 0.0}));
+    //create the search queue, with ties in distance broken by the entity index
+    let mut search_queue: PriorityQueue<Entity , Reverse<(PathWeight, u32)>> = PriorityQueue::new();
+    search_queue.push(start_ent, Reverse((PathWeight{weight: 0.0}, start_ent.index())));
+
+    while let Some((sv_ent, Reverse((sv_dist, _)))) = search_queue.pop() {
+        //as vertices are searched in order of distance, every remaining vertex is also too far away
+        if sv_dist > max_distance {break;}
 
-    while let Some((sv_ent, Reverse(sv_dist))) = search_queue.pop() {
         //get the info of the search vertex
         let Ok((sv_vert, sv_data)) = query.get(sv_ent) else {continue;};
 
         //check if we are currently searching a valid end vertex, as this implies we have already found a minimum path
-        if end_determiner(sv_data) {return Ok(determine_path(path_previous, sv_ent).unwrap());}
+        if end_determiner(sv_data) {return Ok(determine_path(&path_previous, &minimal_dist, sv_ent).expect("The created path should be valid"));}
 
         //loop over this vertex's neighbours
         for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight(){
@@ -198,18 +1063,18 @@ where
             //check if we have visited this vertex before
             //if so, compare the cardinalities to see if we should update
             if let Some(dist) = minimal_dist.get_mut(&neighbour_ent) {
-                //check if the vertex was visited already at a closer distance
-                //if so we ignore this vertex
-                if total_dist > *dist {continue;}
+                //check if the vertex was visited already at a closer or equal distance
+                //if so we ignore this vertex, keeping the first path found so ties are resolved deterministically
+                if total_dist >= *dist {continue;}
                 //otherwise update the vertex's distance, previous vertex and priority in the queue
                 *path_previous.get_mut(&neighbour_ent).unwrap() = Some(sv_ent);
                 *dist = total_dist;
-                search_queue.change_priority(&neighbour_ent, Reverse(total_dist));
+                search_queue.change_priority(&neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
             } else {
                 //otherwise the vertex hasnt been visited before and so we add it to the queue, path_previous and the visited entities
                 path_previous.insert(neighbour_ent, Some(sv_ent));
                 minimal_dist.insert(neighbour_ent, total_dist);
-                search_queue.push(neighbour_ent, Reverse(total_dist));
+                search_queue.push(neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
             }
         }
     }
@@ -217,3 +1082,24 @@ where
     //if we get to this point, then we must have found no path
     Err(GraphError::NoPath)
 }
+
+
+/// Follows the previous vertices back from the final vertex to build the path, with each vertex paired with its own distance from the start
+/// 
+/// Returns an [`InvalidPathError`] if there is a loop, or a vertex on the path is missing a previous vertex or distance.
+pub(crate) fn determine_path(
+    path_previous: &HashMap<Entity, Option<Entity>>,
+    minimal_dist: &HashMap<Entity, PathWeight>,
+    final_vert: Entity
+) -> Result<GraphPath<f32>, InvalidPathError> {
+    let mut path = Vec::new();
+    let mut to_follow = Some(final_vert);
+    while let Some(current) = to_follow {
+        let (Some(&previous), Some(dist)) = (path_previous.get(&current), minimal_dist.get(&current)) else {return Err(InvalidPathError)};
+        path.push((current, dist.value()));
+        to_follow = previous;
+        //check for a loop
+        if path.len() > path_previous.len() {return Err(InvalidPathError)}
+    }
+    Ok(GraphPath::new(path))
+}
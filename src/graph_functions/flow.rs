@@ -0,0 +1,334 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::{Component, Entity, Query}, utils::{HashMap, HashSet}};
+
+use crate::graph_vertex::GraphVertex;
+
+use super::{dijkstra::dijkstra_tree_by, helper::search_preamble, GraphError, GraphPath};
+
+
+/// Determines the maximum number of edge-disjoint paths from the source vertex to the sink vertex
+/// 
+/// Two paths are edge-disjoint if they never use the same edge, though they may share vertices. This is the number of edges that must be
+/// removed to disconnect the sink from the source, so is a measure of how redundant the connection between them is. Computed with the
+/// Edmonds-Karp max-flow algorithm, giving every edge a capacity of 1 and ignoring edge weights. If the source and sink are the same vertex, 0 is returned.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided source vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided sink vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that warns when a town only has a single road to the capital
+/// fn check_redundancy(
+///     capital: Query<Entity, With<Capital>>,
+///     towns: Query<Entity, With<Town>>,
+///     roads: Query<&RoadVertex>
+/// ) {
+///     for town in towns.iter() {
+///         if edge_disjoint_paths(&roads, capital.single(), town).unwrap() < 2 {
+///             println!("Town {:?} can be cut off by a single blocked road!", town);
+///         }
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`edge_disjoint_path_set`]: For a set of paths achieving this maximum
+pub fn edge_disjoint_paths<V: GraphVertex>(
+    query: &Query<&V>,
+    source_ent: Entity,
+    sink_ent: Entity
+) -> Result<usize, GraphError> {
+    if search_preamble(query, source_ent, sink_ent, ())?.is_some() {return Ok(0);}
+    let mut residual = UnitResidual::new(query, source_ent);
+    Ok(residual.max_flow(source_ent, sink_ent))
+}
+
+/// Returns a maximum set of edge-disjoint paths from the source vertex to the sink vertex, each in **reverse order**
+/// 
+/// The number of paths returned is given by [`edge_disjoint_paths`]. There are usually many such sets of paths, with no guarantee on which is returned.
+/// If the source and sink are the same vertex, no paths are returned.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided source vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided sink vertex entity does not appear in the provided query.
+/// 
+/// # See also
+/// 
+/// [`edge_disjoint_paths`]: For only the number of paths
+pub fn edge_disjoint_path_set<V: GraphVertex>(
+    query: &Query<&V>,
+    source_ent: Entity,
+    sink_ent: Entity
+) -> Result<Vec<GraphPath<()>>, GraphError> {
+    if search_preamble(query, source_ent, sink_ent, ())?.is_some() {return Ok(Vec::new());}
+    let mut residual = UnitResidual::new(query, source_ent);
+    let path_count = residual.max_flow(source_ent, sink_ent);
+    Ok((0..path_count).filter_map(|_| residual.take_flow_path(source_ent, sink_ent)).collect())
+}
+
+/// Determines the minimum total cost of sending `amount` units of flow from the source vertex to the sink vertex
+/// 
+/// Each edge's weight is the cost per unit of flow sent along it, and its capacity, the most flow it can carry, is given by 
+/// `capacity_determiner`, which is passed the capacity component of the edge's source vertex and the entity of its destination. Parallel
+/// edges are each given that capacity. The flow may be split across any number of routes, so this models eg goods moving through a road network
+/// where each road has a toll and a limit on its traffic. Capacities below 0 are treated as 0. If the source and sink are the same vertex, or the
+/// amount is not positive, the cost is 0.
+/// 
+/// Computed by successive shortest paths: flow is repeatedly sent along the cheapest route with spare capacity, found by Dijkstra's algorithm
+/// using reduced costs so that the reverse edges of the residual graph are never negative.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided source vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::InvalidEntity`]: If the provided sink vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If an edge reachable from the source has a negative weight.
+/// 
+/// [`GraphError::NoPath`]: If the capacity between the source and sink is less than `amount`, so the flow is infeasible.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds the cheapest way to ship the harvest to the market each season
+/// fn plan_shipping(
+///     farm: Query<Entity, With<Farm>>,
+///     market: Query<Entity, With<Market>>,
+///     roads: Query<(&RoadVertex, &RoadLimits)>,
+///     harvest: Res<Harvest>
+/// ) {
+///     let limit_of = |limits: &RoadLimits, to: Entity| limits.0.get(&to).copied().unwrap_or(0.0);
+///     match min_cost_flow(&roads, farm.single(), market.single(), harvest.0, limit_of) {
+///         Ok(cost) => println!("Shipping the harvest will cost {}", cost),
+///         Err(_) => println!("The roads can't carry the whole harvest!")
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`edge_disjoint_paths`]: For the maximum flow when every edge has a capacity of 1
+pub fn min_cost_flow<V, C, F>(
+    query: &Query<(&V, &C)>,
+    source_ent: Entity,
+    sink_ent: Entity,
+    amount: f32,
+    capacity_determiner: F
+) -> Result<f32, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C, Entity) -> f32
+{
+    if search_preamble(query, source_ent, sink_ent, ())?.is_some() || amount <= 0.0 {return Ok(0.0);}
+    let mut residual = CostResidual::new(query, source_ent, capacity_determiner)?;
+
+    //the potential of each vertex, which makes every residual edge's reduced cost non-negative
+    let mut potentials: HashMap<Entity, f32> = HashMap::new();
+    let mut remaining = amount;
+    let mut total_cost = 0.0;
+
+    while remaining > 0.0 {
+        let tree = dijkstra_tree_by(source_ent, |ent| Some(residual.reduced_edges(ent, &potentials)))?;
+        if !tree.contains_key(&sink_ent) {return Err(GraphError::NoPath);}
+        //vertices that weren't reached can't be reached by any later search, so their potential no longer matters
+        for (&ent, &(dist, _)) in tree.iter() {
+            *potentials.entry(ent).or_insert(0.0) += dist;
+        }
+
+        //the cheapest edge joining each pair of the path, which all have a reduced cost of 0 with the new potentials
+        let mut path_edges = Vec::new();
+        let mut current = sink_ent;
+        while let Some(previous) = tree[&current].1 {
+            path_edges.push(residual.cheapest_edge(previous, current).expect("Edges of the tree should have spare capacity"));
+            current = previous;
+        }
+
+        let sent = path_edges.iter().map(|&edge| residual.edges[edge].capacity).fold(remaining, f32::min);
+        for edge in path_edges {
+            residual.edges[edge].capacity -= sent;
+            residual.edges[edge ^ 1].capacity += sent;
+            total_cost += sent * residual.edges[edge].cost;
+        }
+        remaining -= sent;
+    }
+    Ok(total_cost)
+}
+
+
+/// Residual graph of the part of the graph reachable from the source, with every edge having a capacity of 1
+struct UnitResidual {
+    /// Original capacity of each edge, counting parallel edges separately
+    capacity: HashMap<(Entity, Entity), i32>,
+    /// Remaining capacity of each edge, including the reverse edges
+    residual: HashMap<(Entity, Entity), i32>,
+    /// Neighbours in the residual graph, in either direction
+    adjacency: HashMap<Entity, Vec<Entity>>
+}
+
+impl UnitResidual {
+    fn new<V: GraphVertex>(query: &Query<&V>, source_ent: Entity) -> Self {
+        let mut capacity: HashMap<(Entity, Entity), i32> = HashMap::new();
+        let mut adjacency: HashMap<Entity, Vec<Entity>> = HashMap::new();
+
+        //explore the graph from the source, as nothing else can carry any flow
+        let mut to_search = vec![source_ent];
+        let mut seen: HashSet<Entity> = HashSet::from([source_ent]);
+        while let Some(sv_ent) = to_search.pop() {
+            let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+            for neighbour_ent in sv_vert.get_neighbours() {
+                if neighbour_ent == sv_ent {continue;}
+                let edge_cap = capacity.entry((sv_ent, neighbour_ent)).or_insert(0);
+                //only add to the adjacency lists the first time a pair of vertices is seen
+                if *edge_cap == 0 && !capacity.contains_key(&(neighbour_ent, sv_ent)) {
+                    adjacency.entry(sv_ent).or_default().push(neighbour_ent);
+                    adjacency.entry(neighbour_ent).or_default().push(sv_ent);
+                }
+                *capacity.get_mut(&(sv_ent, neighbour_ent)).unwrap() += 1;
+                if seen.insert(neighbour_ent) {to_search.push(neighbour_ent);}
+            }
+        }
+
+        let residual = capacity.clone();
+        Self{capacity, residual, adjacency}
+    }
+
+    fn residual_of(&self, from: Entity, to: Entity) -> i32 {
+        self.residual.get(&(from, to)).copied().unwrap_or(0)
+    }
+
+    /// Repeatedly augments along shortest residual paths, returning the total flow
+    fn max_flow(&mut self, source_ent: Entity, sink_ent: Entity) -> usize {
+        let mut flow = 0;
+        while let Some(parents) = self.augmenting_path(source_ent, sink_ent) {
+            let mut current = sink_ent;
+            while current != source_ent {
+                let previous = parents[&current];
+                *self.residual.entry((previous, current)).or_insert(0) -= 1;
+                *self.residual.entry((current, previous)).or_insert(0) += 1;
+                current = previous;
+            }
+            flow += 1;
+        }
+        flow
+    }
+
+    /// Breadth-first search for a path with remaining capacity, returning the previous vertex of each vertex reached
+    fn augmenting_path(&self, source_ent: Entity, sink_ent: Entity) -> Option<HashMap<Entity, Entity>> {
+        let mut parents: HashMap<Entity, Entity> = HashMap::new();
+        let mut search_queue = VecDeque::from([source_ent]);
+        while let Some(sv_ent) = search_queue.pop_front() {
+            for &neighbour_ent in self.adjacency.get(&sv_ent).into_iter().flatten() {
+                if neighbour_ent == source_ent || parents.contains_key(&neighbour_ent) {continue;}
+                if self.residual_of(sv_ent, neighbour_ent) <= 0 {continue;}
+                parents.insert(neighbour_ent, sv_ent);
+                if neighbour_ent == sink_ent {return Some(parents);}
+                search_queue.push_back(neighbour_ent);
+            }
+        }
+        None
+    }
+
+    /// Follows and removes a single unit of flow from the source to the sink, returning the path in reverse order
+    fn take_flow_path(&mut self, source_ent: Entity, sink_ent: Entity) -> Option<GraphPath<()>> {
+        let mut path = vec![source_ent];
+        let mut positions: HashMap<Entity, usize> = HashMap::from([(source_ent, 0)]);
+        let mut current = source_ent;
+        while current != sink_ent {
+            //the net flow along an edge is how much of its original capacity has been used
+            let next = self.adjacency.get(&current)?.iter().copied().find(|&next| {
+                self.capacity.get(&(current, next)).copied().unwrap_or(0) - self.residual_of(current, next) > 0
+            })?;
+            //remove the unit of flow so it isn't followed again
+            *self.residual.entry((current, next)).or_insert(0) += 1;
+            *self.residual.entry((next, current)).or_insert(0) -= 1;
+            //drop any cycle in the flow, as it doesn't contribute to the path
+            if let Some(&pos) = positions.get(&next) {
+                for removed in path.drain(pos + 1..) {positions.remove(&removed);}
+            } else {
+                positions.insert(next, path.len());
+                path.push(next);
+            }
+            current = next;
+        }
+        Some(GraphPath::new(path.into_iter().rev().map(|ent| (ent, ())).collect()))
+    }
+}
+
+
+/// An edge of a [`CostResidual`], with its remaining capacity and cost per unit of flow
+struct ResidualEdge {
+    from: Entity,
+    to: Entity,
+    capacity: f32,
+    cost: f32
+}
+
+/// Residual graph of the part of the graph reachable from the source, with a capacity and cost for every edge
+/// 
+/// Each edge is stored next to its reverse edge, so the reverse of the edge at index `i` is at `i ^ 1`. The reverse edge starts with no capacity
+/// and the negated cost, so sending flow back along it refunds the cost.
+struct CostResidual {
+    edges: Vec<ResidualEdge>,
+    /// The indices of the edges leaving each vertex, including reverse edges
+    outgoing: HashMap<Entity, Vec<usize>>
+}
+
+impl CostResidual {
+    fn new<V, C, F>(query: &Query<(&V, &C)>, source_ent: Entity, capacity_determiner: F) -> Result<Self, GraphError> 
+    where
+        V: GraphVertex,
+        C: Component,
+        F: Fn(&C, Entity) -> f32
+    {
+        let mut edges: Vec<ResidualEdge> = Vec::new();
+        let mut outgoing: HashMap<Entity, Vec<usize>> = HashMap::new();
+
+        //explore the graph from the source, as nothing else can carry any flow
+        let mut to_search = vec![source_ent];
+        let mut seen: HashSet<Entity> = HashSet::from([source_ent]);
+        while let Some(sv_ent) = to_search.pop() {
+            let Ok((sv_vert, sv_capacities)) = query.get(sv_ent) else {continue;};
+            for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight() {
+                if neighbour_ent == sv_ent || !query.contains(neighbour_ent) {continue;}
+                if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+                let capacity = capacity_determiner(sv_capacities, neighbour_ent).max(0.0);
+
+                outgoing.entry(sv_ent).or_default().push(edges.len());
+                edges.push(ResidualEdge{from: sv_ent, to: neighbour_ent, capacity, cost: edge_weight});
+                outgoing.entry(neighbour_ent).or_default().push(edges.len());
+                edges.push(ResidualEdge{from: neighbour_ent, to: sv_ent, capacity: 0.0, cost: -edge_weight});
+
+                if seen.insert(neighbour_ent) {to_search.push(neighbour_ent);}
+            }
+        }
+        Ok(Self{edges, outgoing})
+    }
+
+    /// The edges with spare capacity leaving the vertex, weighted by their reduced cost
+    /// 
+    /// Reduced costs are never negative in exact arithmetic, so any slightly negative cost from rounding is raised to 0.
+    fn reduced_edges(&self, ent: Entity, potentials: &HashMap<Entity, f32>) -> Vec<(Entity, f32)> {
+        let potential_of = |ent: Entity| potentials.get(&ent).copied().unwrap_or(0.0);
+        self.outgoing.get(&ent).into_iter().flatten()
+        .map(|&index| &self.edges[index])
+        .filter(|edge| edge.capacity > 0.0)
+        .map(|edge| (edge.to, (edge.cost + potential_of(edge.from) - potential_of(edge.to)).max(0.0)))
+        .collect()
+    }
+
+    /// The index of the cheapest edge with spare capacity between the two vertices
+    fn cheapest_edge(&self, from: Entity, to: Entity) -> Option<usize> {
+        self.outgoing.get(&from)?.iter().copied()
+        .filter(|&index| self.edges[index].to == to && self.edges[index].capacity > 0.0)
+        .min_by(|&index1, &index2| self.edges[index1].cost.total_cmp(&self.edges[index2].cost))
+    }
+}
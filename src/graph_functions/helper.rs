@@ -1,24 +1,55 @@
 use std::{fs::File, io::{BufRead, BufReader}};
 
-use bevy::prelude::{Entity, World};
+use bevy::{ecs::query::{QueryData, QueryFilter, QueryIter, ROQueryItem}, prelude::{Commands, Entity, Query, World}, utils::HashMap};
 
-use crate::graph_vertex::StandardGraphVertex;
+use crate::graph_vertex::{GraphVertex, StandardGraphVertex};
 
-use super::GraphLabel;
+use super::{Direction, GraphError, GraphLabel, GraphPath};
 
 
 
 
-/// Takes the last vertex in the provided vector and follows it's [optional](Option) value to the previous entity in the path.
-/// Terminates once it hits a [None]. Returns an [`InvalidPathError`] if there is a loop or a previous [entity](Entity) is not in the vector.
-/// 
-/// Returns the path as a vector in reverse order
+/// How repeated edges between the same pair of vertices are combined when loading a graph
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateEdgePolicy {
+    /// Keep the weight of the first listed edge
+    #[default]
+    First,
+    /// Keep the weight of the last listed edge
+    Last,
+    /// Keep the smallest weight
+    Min,
+    /// Keep the largest weight
+    Max,
+    /// Sum the weights of every listed edge
+    Sum
+}
+
+impl DuplicateEdgePolicy {
+    /// Combines the weight of an existing edge with that of a repeated one
+    fn combine(self, existing: f32, repeated: f32) -> f32 {
+        match self {
+            DuplicateEdgePolicy::First => existing,
+            DuplicateEdgePolicy::Last => repeated,
+            DuplicateEdgePolicy::Min => existing.min(repeated),
+            DuplicateEdgePolicy::Max => existing.max(repeated),
+            DuplicateEdgePolicy::Sum => existing + repeated,
+        }
+    }
+}
 
-#[allow(dead_code)]
 /// Helper function to load a graph from a file into a world
 /// 
 /// Used for testing the graph algorithms, reads from a graph file and adds a [StandardGraphVertex] and [GraphLabel] for each vertex in the file.
-pub(crate) fn load_graph(world: &mut World, graph_file: &str) -> Vec<Entity>{
+/// Repeated edges are collapsed using [`DuplicateEdgePolicy::First`].
+pub fn load_graph(world: &mut World, graph_file: &str) -> Vec<Entity>{
+    load_graph_with_policy(world, graph_file, DuplicateEdgePolicy::default())
+}
+
+/// Helper function to load a graph from a file into a world, using the given policy to combine edges that are listed more than once
+/// 
+/// Identical to [`load_graph`], except that repeated `dest|weight` entries on a vertex's line are combined according to the [`DuplicateEdgePolicy`].
+pub fn load_graph_with_policy(world: &mut World, graph_file: &str, policy: DuplicateEdgePolicy) -> Vec<Entity>{
     let file = File::open(graph_file).unwrap();
     let reader = BufReader::new(file);
     let mut inter_graph_rep : Vec<Vec<(usize, f32)>> = Vec::new();
@@ -33,7 +64,11 @@ pub(crate) fn load_graph(world: &mut World, graph_file: &str) -> Vec<Entity>{
             let pair : Vec<&str> = pair.split("|").collect();
             let Ok(dest) = pair[0].parse::<usize>() else {break;};
             let Ok(weight) = pair[1].parse::<f32>() else {break;};
-            inter_graph_rep[pos].push((dest, weight));
+            //combine with any previous edge to the same destination
+            match inter_graph_rep[pos].iter_mut().find(|(existing_dest, _)| *existing_dest == dest) {
+                Some((_, existing_weight)) => *existing_weight = policy.combine(*existing_weight, weight),
+                None => inter_graph_rep[pos].push((dest, weight))
+            }
         }
         //TODO, we need to construct the graph from the inter_graph_rep
     }
@@ -48,3 +83,272 @@ pub(crate) fn load_graph(world: &mut World, graph_file: &str) -> Vec<Entity>{
     entity_vec
 }
 
+
+
+/// Spawns a graph from a list of `(from, to, weight)` edges between vertex indices, returning the vertex entities by index
+/// 
+/// Spawns `vertex_count` entities, each with a [StandardGraphVertex] and a [GraphLabel] holding its index, then adds each edge to its source vertex.
+/// This is the in-memory analogue of [`load_graph`]. As this uses [`Commands`], the vertices will not exist until the commands are applied.
+/// 
+/// # Panics
+/// 
+/// If an edge refers to a vertex index that is not less than `vertex_count`.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A startup system that builds a small triangle graph
+/// fn build_triangle(mut commands: Commands) {
+///     let vertices = spawn_graph(&mut commands, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0)], 3);
+///     commands.entity(vertices[0]).insert(StartMarker);
+/// }
+/// ```
+pub fn spawn_graph(commands: &mut Commands, edges: &[(usize, usize, f32)], vertex_count: usize) -> Vec<Entity> {
+    let entity_vec: Vec<Entity> = (0..vertex_count).map(|_| commands.spawn_empty().id()).collect();
+    let mut adjacency: Vec<Vec<(Entity, f32)>> = vec![Vec::new(); vertex_count];
+    for &(from, to, weight) in edges {
+        adjacency[from].push((entity_vec[to], weight));
+    }
+    for (pos, vert_edges) in adjacency.into_iter().enumerate() {
+        commands.entity(entity_vec[pos]).insert((StandardGraphVertex::new_with_edges(vert_edges), GraphLabel{value: pos}));
+    }
+    entity_vec
+}
+
+/// Adds a list of `(from, to, weight)` edges between [`GraphLabel`] values to the vertices that are already in the world
+/// 
+/// Each label is resolved to the entity holding it, and the edge is added to the [StandardGraphVertex] of its source vertex, which is inserted
+/// if the entity doesn't have one yet. As with [`StandardGraphVertex::add_edge`], an edge that already exists keeps its weight. This is the
+/// in-memory analogue of [`load_graph`] for entities that have already been spawned with their labels.
+/// 
+/// Every label is resolved before any edge is added, so on an error the world is left unchanged.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::UnknownLabel`]: If no entity has one of the labels.
+/// 
+/// [`GraphError::DuplicateLabel`]: If more than one entity has one of the labels.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A startup system that connects the rooms of a level, which were spawned from the level file with their labels
+/// fn connect_rooms(world: &mut World) {
+///     let doors = world.resource::<LevelFile>().doors.clone();
+///     wire_edges_by_label(world, &doors).expect("Every door should lead between two rooms");
+/// }
+/// ```
+pub fn wire_edges_by_label(world: &mut World, edges: &[(usize, usize, f32)]) -> Result<(), GraphError> {
+    let mut label_entities: HashMap<usize, Entity> = HashMap::new();
+    for (ent, label) in world.query::<(Entity, &GraphLabel)>().iter(world) {
+        if label_entities.insert(label.value, ent).is_some() {return Err(GraphError::DuplicateLabel(label.value));}
+    }
+    let entity_of = |label: usize| label_entities.get(&label).copied().ok_or(GraphError::UnknownLabel(label));
+    let resolved = edges.iter()
+    .map(|&(from, to, weight)| Ok((entity_of(from)?, entity_of(to)?, weight)))
+    .collect::<Result<Vec<_>, GraphError>>()?;
+
+    for (from_ent, to_ent, weight) in resolved {
+        match world.get_mut::<StandardGraphVertex>(from_ent) {
+            Some(mut vert) => {vert.add_edge(to_ent, weight);},
+            None => {world.entity_mut(from_ent).insert(StandardGraphVertex::new_with_edges(vec![(to_ent, weight)]));}
+        }
+    }
+    Ok(())
+}
+
+/// Finds the vertex with the given [`GraphLabel`] value
+/// 
+/// Nothing requires labels to be unique, so this checks every vertex in the query to make sure only one has the label. For labels that are
+/// intentionally shared between vertices, use [`find_all_by_label`].
+/// 
+/// # Errors
+/// 
+/// [`GraphError::UnknownLabel`]: If no vertex in the query has the label.
+/// 
+/// [`GraphError::DuplicateLabel`]: If more than one vertex in the query has the label.
+pub fn find_by_label(query: &Query<(Entity, &GraphLabel)>, value: usize) -> Result<Entity, GraphError> {
+    let mut matching = query.iter().filter(|(_, label)| label.value == value).map(|(ent, _)| ent);
+    let found = matching.next().ok_or(GraphError::UnknownLabel(value))?;
+    if matching.next().is_some() {return Err(GraphError::DuplicateLabel(value));}
+    Ok(found)
+}
+
+/// Finds every vertex with the given [`GraphLabel`] value, in the order of the query
+/// 
+/// Returns an empty vector if no vertex has the label.
+pub fn find_all_by_label(query: &Query<(Entity, &GraphLabel)>, value: usize) -> Vec<Entity> {
+    query.iter().filter(|(_, label)| label.value == value).map(|(ent, _)| ent).collect()
+}
+
+/// Iterates over every edge of the graph, as (source, destination, weight) triples
+/// 
+/// Goes through the vertices in the order of the query, and each vertex's edges in the order given by 
+/// [`GraphVertex::get_neighbours_with_weight`]. Every edge is included, even those to entities outside the query, so the number of edges is the 
+/// sum of the out-degrees of the vertices.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that logs the most expensive road on the map
+/// fn log_longest_road(junctions: Query<(Entity, &JunctionVertex)>) {
+///     if let Some((from, to, length)) = all_edges(&junctions).max_by(|a, b| a.2.total_cmp(&b.2)) {
+///         info!("The longest road is from {:?} to {:?}, with length {}", from, to, length);
+///     }
+/// }
+/// ```
+pub fn all_edges<'a, 's, 'v, V: GraphVertex>(query: &'a Query<'_, 's, (Entity, &'v V)>) -> AllEdges<'a, 's, 'v, V> {
+    AllEdges{vertices: query.iter(), current: None}
+}
+
+/// Iterator over every edge of the graph, as returned by [`all_edges`]
+pub struct AllEdges<'a, 's, 'v, V: GraphVertex> {
+    vertices: QueryIter<'a, 's, (Entity, &'v V), ()>,
+    /// The vertex whose edges are being iterated, and its remaining edges
+    current: Option<(Entity, std::vec::IntoIter<(Entity, f32)>)>
+}
+
+impl<V: GraphVertex> Iterator for AllEdges<'_, '_, '_, V> {
+    type Item = (Entity, Entity, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((ent, edges)) = &mut self.current {
+                if let Some((neighbour_ent, weight)) = edges.next() {return Some((*ent, neighbour_ent, weight));}
+            }
+            let (ent, vert) = self.vertices.next()?;
+            self.current = Some((ent, vert.get_neighbours_with_weight().into_iter()));
+        }
+    }
+}
+
+/// Determines the incoming edges of every vertex in the query
+/// 
+/// Returns a map from each vertex to the (source, weight) pairs of the edges ending at it. Vertices with no incoming edges are not included.
+/// Building the map walks every edge of the graph, so functions that take it as a parameter let it be built once and reused.
+pub fn reverse_adjacency<V: GraphVertex>(query: &Query<(Entity, &V)>) -> HashMap<Entity, Vec<(Entity, f32)>> {
+    let mut incoming: HashMap<Entity, Vec<(Entity, f32)>> = HashMap::new();
+    for (ent, neighbour_ent, weight) in all_edges(query) {
+        incoming.entry(neighbour_ent).or_default().push((ent, weight));
+    }
+    incoming
+}
+
+/// Helper function returning the neighbours of a vertex when following edges in the given [`Direction`]
+/// 
+/// Returns [`None`] if the entity is not in the query, matching how the searches skip entities that aren't vertices.
+pub(crate) fn directed_neighbours<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    incoming: &HashMap<Entity, Vec<(Entity, f32)>>,
+    ent: Entity,
+    direction: Direction
+) -> Option<Vec<(Entity, f32)>> {
+    let (_, vert) = query.get(ent).ok()?;
+    let incoming_edges = || incoming.get(&ent).cloned().unwrap_or_default();
+    Some(match direction {
+        Direction::Outgoing => vert.get_neighbours_with_weight(),
+        Direction::Incoming => incoming_edges(),
+        Direction::Both => {
+            let mut edges = vert.get_neighbours_with_weight();
+            edges.extend(incoming_edges());
+            edges
+        }
+    })
+}
+
+
+/// Helper function checking that the start and end vertex are not known to be in different components
+/// 
+/// Vertices missing from the component map are assumed to possibly be connected, so the search is still run for them.
+pub(crate) fn check_same_component(components: &HashMap<Entity, usize>, start_ent: Entity, end_ent: Entity) -> Result<(), GraphError> {
+    match (components.get(&start_ent), components.get(&end_ent)) {
+        (Some(start_comp), Some(end_comp)) if start_comp != end_comp => Err(GraphError::NoPath),
+        _ => Ok(())
+    }
+}
+
+/// Disjoint set forest over the indices `0..n`, using path halving and union by size
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>
+}
+
+impl UnionFind {
+    pub(crate) fn new(n: usize) -> Self {
+        Self{parent: (0..n).collect(), size: vec![1; n]}
+    }
+
+    pub(crate) fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Joins the sets containing `a` and `b`, returning false if they were already in the same set
+    pub(crate) fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {return false;}
+        if self.size[a] < self.size[b] {std::mem::swap(&mut a, &mut b);}
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+
+/// The number of vertices a search may still search before giving up, with [`None`] for no limit
+pub(crate) struct ExpansionBudget {
+    remaining: Option<usize>
+}
+
+impl ExpansionBudget {
+    pub(crate) fn new(max_expansions: Option<usize>) -> Self {
+        Self{remaining: max_expansions}
+    }
+
+    /// Uses up one vertex of the budget, returning [`GraphError::BudgetExceeded`] if there are none left
+    pub(crate) fn spend(&mut self) -> Result<(), GraphError> {
+        match &mut self.remaining {
+            Some(0) => Err(GraphError::BudgetExceeded),
+            Some(remaining) => {*remaining -= 1; Ok(())},
+            None => Ok(())
+        }
+    }
+}
+
+
+/// Helper function fetching the start vertex of a search, returning [`GraphError::StartBlocked`] if it is not in the query
+pub(crate) fn get_start<'a, D: QueryData, F: QueryFilter>(query: &'a Query<D, F>, start_ent: Entity) -> Result<ROQueryItem<'a, D>, GraphError> {
+    query.get(start_ent).map_err(|_| GraphError::StartBlocked)
+}
+
+/// Helper function containing the shared checks run before a search between two known vertices
+/// 
+/// Returns [`GraphError::StartBlocked`] if the start vertex is not in the query and [`GraphError::InvalidEntity`] if the end vertex isn't.
+/// If the start and end vertex are the same, the single vertex path is returned, otherwise [`None`] is returned and the search should be run.
+pub(crate) fn search_preamble<D: QueryData, F: QueryFilter, P>(
+    query: &Query<D, F>,
+    start_ent: Entity,
+    end_ent: Entity,
+    start_val: P
+) -> Result<Option<GraphPath<P>>, GraphError> {
+    get_start(query, start_ent)?;
+    query.get(end_ent)?;
+    Ok(if start_ent == end_ent {Some(GraphPath::single(start_ent, start_val))} else {None})
+}
+
+/// Helper function returning [`GraphError::IsolatedStart`] if the start vertex of a search has no outgoing edges
+/// 
+/// Should only be called once the start and end vertex are known to differ, so that the search can't succeed.
+pub(crate) fn check_start_edges<V: GraphVertex>(start_vert: &V) -> Result<(), GraphError> {
+    if start_vert.neighbour_count() == 0 {Err(GraphError::IsolatedStart)} else {Ok(())}
+}
+
+/// Helper function returning [`GraphError::InvalidTolerance`] if the tolerance is negative or NaN
+/// 
+/// A negative tolerance would let a path replace one that is no shorter, so a zero weight loop could make a vertex its own previous vertex.
+pub(crate) fn check_tolerance(tolerance: f32) -> Result<(), GraphError> {
+    if tolerance.is_nan() || tolerance < 0.0 {Err(GraphError::InvalidTolerance)} else {Ok(())}
+}
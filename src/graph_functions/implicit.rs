@@ -0,0 +1,374 @@
+use std::{cmp::Reverse, collections::VecDeque, hash::Hash};
+
+use bevy::utils::HashMap;
+use priority_queue::PriorityQueue;
+
+use super::{helper::ExpansionBudget, GraphError, Heuristic, PathWeight};
+
+
+/// A graph whose vertices are values of type `N` rather than entities, with the edges computed on demand
+///
+/// Useful for graphs that are too large, or infinite, to spawn an entity for every vertex, such as a procedurally generated grid where
+/// each vertex is a coordinate. The searches in this module only ever ask for the neighbours of vertices they reach. Any function or closure
+/// taking a vertex and returning its (neighbour, edge weight) pairs is an implicit graph.
+///
+/// # Example
+///
+/// ```ignore
+/// //An infinite grid, where each coordinate is connected to those next to it unless it is a wall
+/// struct Cave {
+///     walls: HashSet<(i32, i32)>
+/// }
+///
+/// impl ImplicitGraph<(i32, i32)> for Cave {
+///     fn neighbours(&self, (x, y): (i32, i32)) -> Vec<((i32, i32), f32)> {
+///         [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)].into_iter()
+///         .filter(|pos| !self.walls.contains(pos))
+///         .map(|pos| (pos, 1.0))
+///         .collect()
+///     }
+/// }
+/// ```
+pub trait ImplicitGraph<N: Hash + Eq + Clone> {
+    /// Returns each neighbour of the vertex, paired with the weight of the edge to it
+    fn neighbours(&self, node: N) -> Vec<(N, f32)>;
+}
+
+impl<N: Hash + Eq + Clone, F: Fn(N) -> Vec<(N, f32)>> ImplicitGraph<N> for F {
+    fn neighbours(&self, node: N) -> Vec<(N, f32)> {
+        self(node)
+    }
+}
+
+
+/// Runs a breadth-first search on an implicit graph, returning the path in **reverse order**
+///
+/// The path has the fewest possible steps, ignoring edge weights. As an implicit graph can be infinite, a search with no path may never end,
+/// so a `max_expansions` budget should be given for such graphs. Once that many vertices have been searched without finding the end vertex,
+/// the search gives up.
+///
+/// # Errors
+///
+/// [`GraphError::NoPath`]: If a path could not be found.
+///
+/// [`GraphError::BudgetExceeded`]: If the search was stopped after searching `max_expansions` vertices.
+///
+/// # See also
+///
+/// [`bfs`](super::bfs::bfs): For a breadth-first search over vertex entities
+pub fn implicit_bfs<N, G>(
+    graph: &G,
+    start: N,
+    end: N,
+    max_expansions: Option<usize>
+) -> Result<Vec<N>, GraphError>
+where
+    N: Hash + Eq + Clone,
+    G: ImplicitGraph<N> + ?Sized
+{
+    implicit_bfs_by(graph, start, end, max_expansions, |_| Ok(()))
+}
+
+/// Breadth-first search on an implicit graph, where `on_found` is called with each vertex the first time it is found, ending the search
+/// if it returns an error
+///
+/// This is the search behind [`implicit_bfs`], as well as [`bfs`](super::bfs::bfs) and its strict, budgeted and neighbour function variants,
+/// which adapt their query into an [`ImplicitGraph`].
+pub(crate) fn implicit_bfs_by<N, G, F>(
+    graph: &G,
+    start: N,
+    end: N,
+    max_expansions: Option<usize>,
+    mut on_found: F
+) -> Result<Vec<N>, GraphError>
+where
+    N: Hash + Eq + Clone,
+    G: ImplicitGraph<N> + ?Sized,
+    F: FnMut(&N) -> Result<(), GraphError>
+{
+    if start == end {return Ok(vec![start]);}
+
+    //the vertex each found vertex was first reached from
+    let mut previous: HashMap<N, Option<N>> = HashMap::new();
+    previous.insert(start.clone(), None);
+    let mut search_queue: VecDeque<N> = VecDeque::from([start]);
+    let mut budget = ExpansionBudget::new(max_expansions);
+
+    //loop while still vertices to check
+    while let Some(sv_node) = search_queue.pop_front() {
+        budget.spend()?;
+        for (neighbour_node, _) in graph.neighbours(sv_node.clone()) {
+
+            if previous.contains_key(&neighbour_node) {continue;}
+            on_found(&neighbour_node)?;
+            previous.insert(neighbour_node.clone(), Some(sv_node.clone()));
+
+            if neighbour_node == end {return Ok(follow_previous(&previous, neighbour_node, |_| ()).into_iter().map(|(node, _)| node).collect());}
+
+            search_queue.push_back(neighbour_node);
+        }
+    }
+    //if we get to this point, then we must have found no path
+    Err(GraphError::NoPath)
+}
+
+/// Runs Dijkstra's algorithm on an implicit graph, returning the path in **reverse order** with each vertex paired with its distance from the start
+///
+/// The path minimises total edge weight. When several vertices are at the same distance they are searched in the order they were found,
+/// so the result is deterministic given a deterministic [`ImplicitGraph`]. As for [`implicit_bfs`], a `max_expansions` budget should be given
+/// for infinite graphs.
+///
+/// # Errors
+///
+/// [`GraphError::NoPath`]: If a path could not be found.
+///
+/// [`GraphError::NegativeWeight`]: If the graph provides an edge with a negative weight
+///
+/// [`GraphError::BudgetExceeded`]: If the search was stopped after searching `max_expansions` vertices.
+///
+/// # See also
+///
+/// [`dijkstra_search`](super::dijkstra::dijkstra_search): For Dijkstra's algorithm over vertex entities
+pub fn implicit_dijkstra<N, G>(
+    graph: &G,
+    start: N,
+    end: N,
+    max_expansions: Option<usize>
+) -> Result<Vec<(N, f32)>, GraphError>
+where
+    N: Hash + Eq + Clone,
+    G: ImplicitGraph<N> + ?Sized
+{
+    implicit_a_star(graph, start, end, |_| Heuristic{value: 0.0}, max_expansions)
+}
+
+/// Runs A* on an implicit graph, returning the path in **reverse order** with each vertex paired with its distance from the start
+///
+/// The heuristic estimates the distance from a vertex to the end vertex. Given an admissible heuristic (one that never overestimates the
+/// remaining distance) the path minimises total edge weight. As for [`implicit_bfs`], a `max_expansions` budget should be given for infinite graphs.
+///
+/// # Errors
+///
+/// [`GraphError::NoPath`]: If a path could not be found.
+///
+/// [`GraphError::NegativeWeight`]: If the graph provides an edge with a negative weight
+///
+/// [`GraphError::BudgetExceeded`]: If the search was stopped after searching `max_expansions` vertices.
+///
+/// # Example
+///
+/// ```ignore
+/// //A system that paths a digger through an infinite cave, without spawning an entity for each coordinate
+/// fn dig_route(
+///     cave: Res<Cave>,
+///     mut digger: Query<(&GridPos, &DigTarget, &mut DigRoute)>
+/// ) {
+///     let (pos, target, mut route) = digger.single_mut();
+///     let heuristic = |node: &(i32, i32)| Heuristic{value: ((node.0 - target.0.0).abs() + (node.1 - target.0.1).abs()) as f32};
+///     route.0 = implicit_a_star(cave.as_ref(), pos.0, target.0, heuristic, Some(100_000)).ok();
+/// }
+/// ```
+pub fn implicit_a_star<N, G, H>(
+    graph: &G,
+    start: N,
+    end: N,
+    heuristic_determiner: H,
+    max_expansions: Option<usize>
+) -> Result<Vec<(N, f32)>, GraphError>
+where
+    N: Hash + Eq + Clone,
+    G: ImplicitGraph<N> + ?Sized,
+    H: Fn(&N) -> Heuristic
+{
+    //ties in priority are broken by the order vertices were found in, as vertices have no index
+    let priority = |_: &N, dist: PathWeight, heuristic: Heuristic, found_count: u64| (dist + heuristic, found_count);
+    implicit_a_star_by(graph, start, end, heuristic_determiner, priority, PathWeight::new(f32::INFINITY), 0.0, max_expansions, |_, _, _| Ok(()))
+}
+
+/// A* on an implicit graph, searching the vertex with the smallest `priority` first, which is given each vertex with its distance, its heuristic,
+/// and the number of vertices found before it. Paths longer than `max_cost` are never queued, a found path is only replaced by one shorter by more
+/// than `tolerance`, and `on_relax` is called whenever a vertex's distance improves, ending the search if it returns an error.
+///
+/// This is the search behind [`implicit_dijkstra`] and [`implicit_a_star`], as well as the entity based Dijkstra and A* searches, which adapt
+/// their query into an [`ImplicitGraph`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn implicit_a_star_by<N, G, H, P, Q, R>(
+    graph: &G,
+    start: N,
+    end: N,
+    heuristic_determiner: H,
+    priority: P,
+    max_cost: PathWeight,
+    tolerance: f32,
+    max_expansions: Option<usize>,
+    mut on_relax: R
+) -> Result<Vec<(N, f32)>, GraphError>
+where
+    N: Hash + Eq + Clone,
+    G: ImplicitGraph<N> + ?Sized,
+    H: Fn(&N) -> Heuristic,
+    P: Fn(&N, PathWeight, Heuristic, u64) -> Q,
+    Q: Ord,
+    R: FnMut(&N, &N, f32) -> Result<(), GraphError>
+{
+    let mut search = SteppedAStar::new(start, &priority, max_expansions);
+    search.max_cost = max_cost;
+    search.tolerance = tolerance;
+    loop {
+        match search.step(graph, &heuristic_determiner, &priority, |node| *node == end, &mut on_relax)? {
+            SearchStep::Expanded(_) => {},
+            SearchStep::Reached(node) => return Ok(search.path_to(node)),
+            //if we get to this point, then we must have found no path
+            SearchStep::Exhausted => return Err(GraphError::NoPath)
+        }
+    }
+}
+
+
+/// The outcome of searching a single vertex of a [`SteppedAStar`]
+pub(crate) enum SearchStep<N> {
+    /// The vertex was searched and its neighbours added to the queue
+    Expanded(N),
+    /// The vertex was a goal, so the search is finished
+    Reached(N),
+    /// There were no vertices left to search
+    Exhausted
+}
+
+/// The state of an A* search on an implicit graph, run one vertex at a time
+///
+/// Run to the end by [`implicit_a_star_by`], and stepped by hand by the A* searches that can be paused, have several goals, or are greedy,
+/// so that these all search in the same way. The priority function, heuristic and graph are given to each [`step`](SteppedAStar::step)
+/// rather than stored, so that the state can be kept in a named type.
+pub(crate) struct SteppedAStar<N: Hash + Eq + Clone, Q: Ord> {
+    start: N,
+    /// The vertex each found vertex was reached from, its minimal distance, and its heuristic
+    found: HashMap<N, (Option<N>, PathWeight, Heuristic)>,
+    found_count: u64,
+    search_queue: PriorityQueue<N, Reverse<Q>>,
+    budget: ExpansionBudget,
+    /// Paths longer than this are never queued
+    pub(crate) max_cost: PathWeight,
+    /// A found path is only replaced by one shorter by more than this
+    pub(crate) tolerance: f32
+}
+
+impl<N: Hash + Eq + Clone, Q: Ord> SteppedAStar<N, Q> {
+    pub(crate) fn new<P>(start: N, priority: &P, max_expansions: Option<usize>) -> Self
+    where
+        P: Fn(&N, PathWeight, Heuristic, u64) -> Q
+    {
+        let mut found = HashMap::new();
+        found.insert(start.clone(), (None, PathWeight{weight: 0.0}, Heuristic{value: 0.0}));
+        let mut search_queue = PriorityQueue::new();
+        search_queue.push(start.clone(), Reverse(priority(&start, PathWeight{weight: 0.0}, Heuristic{value: 0.0}, 0)));
+        Self{
+            start, 
+            found, 
+            found_count: 0, 
+            search_queue, 
+            budget: ExpansionBudget::new(max_expansions), 
+            max_cost: PathWeight::new(f32::INFINITY), 
+            tolerance: 0.0
+        }
+    }
+
+    /// Searches the next vertex in the queue
+    ///
+    /// Returns [`GraphError::NegativeWeight`] if it has an edge with a negative weight, [`GraphError::BudgetExceeded`] if the budget given
+    /// to [`SteppedAStar::new`] has run out, and any error returned by `on_relax`.
+    pub(crate) fn step<G, H, P, I, R>(
+        &mut self,
+        graph: &G,
+        heuristic_determiner: &H,
+        priority: &P,
+        is_goal: I,
+        on_relax: &mut R
+    ) -> Result<SearchStep<N>, GraphError>
+    where
+        G: ImplicitGraph<N> + ?Sized,
+        H: Fn(&N) -> Heuristic,
+        P: Fn(&N, PathWeight, Heuristic, u64) -> Q,
+        I: Fn(&N) -> bool,
+        R: FnMut(&N, &N, f32) -> Result<(), GraphError>
+    {
+        let Some((sv_node, _)) = self.search_queue.pop() else {return Ok(SearchStep::Exhausted)};
+        //check if we are currently searching a goal vertex, as this implies we have already found the minimum path
+        if is_goal(&sv_node) {return Ok(SearchStep::Reached(sv_node));}
+        self.budget.spend()?;
+
+        let sv_dist = self.found.get(&sv_node).unwrap().1; //true minimum distance to this vertex
+
+        for (neighbour_node, edge_weight) in graph.neighbours(sv_node.clone()) {
+            if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+
+            //Determine the distance to this neighbour via the path to the search vertex
+            let total_dist = sv_dist + edge_weight;
+            if total_dist > self.max_cost {continue;}
+
+            let heuristic = if let Some((neighbour_previous, neighbour_dist, neighbour_heuristic)) = self.found.get_mut(&neighbour_node) {
+                //check if the vertex was found already at a closer or equal distance, up to the tolerance
+                if total_dist + self.tolerance >= *neighbour_dist {continue;}
+                //otherwise update it, pushing to update the priority if still queued, or reopen it if already searched
+                *neighbour_previous = Some(sv_node.clone());
+                *neighbour_dist = total_dist;
+                *neighbour_heuristic
+            } else {
+                let heuristic = heuristic_determiner(&neighbour_node);
+                self.found.insert(neighbour_node.clone(), (Some(sv_node.clone()), total_dist, heuristic));
+                heuristic
+            };
+            self.found_count += 1;
+            let neighbour_priority = priority(&neighbour_node, total_dist, heuristic, self.found_count);
+            on_relax(&sv_node, &neighbour_node, total_dist.value())?;
+            self.search_queue.push(neighbour_node, Reverse(neighbour_priority));
+        }
+        Ok(SearchStep::Expanded(sv_node))
+    }
+
+    /// The vertex that will be searched next, or the start vertex if the queue is empty
+    pub(crate) fn next_node(&self) -> &N {
+        self.search_queue.peek().map_or(&self.start, |(node, _)| node)
+    }
+
+    /// The path found to the vertex, in **reverse order** with each vertex paired with its distance from the start
+    pub(crate) fn path_to(&self, node: N) -> Vec<(N, f32)> {
+        follow_previous(&self.found, node, |(_, dist, _)| dist.value())
+    }
+}
+
+
+/// Helper trait giving the previous vertex stored in the map values used by the implicit searches
+trait PreviousNode<N> {
+    fn previous(&self) -> Option<&N>;
+}
+
+impl<N> PreviousNode<N> for Option<N> {
+    fn previous(&self) -> Option<&N> {
+        self.as_ref()
+    }
+}
+
+impl<N> PreviousNode<N> for (Option<N>, PathWeight, Heuristic) {
+    fn previous(&self) -> Option<&N> {
+        self.0.as_ref()
+    }
+}
+
+/// Helper function following the previous vertices back from the final vertex to the start, returning the path in **reverse order**
+/// with each vertex paired with the data extracted from its map entry
+fn follow_previous<N, E, D, F>(found: &HashMap<N, E>, final_node: N, data: F) -> Vec<(N, D)>
+where
+    N: Hash + Eq + Clone,
+    E: PreviousNode<N>,
+    F: Fn(&E) -> D
+{
+    let mut path = Vec::new();
+    let mut to_follow = Some(final_node);
+    while let Some(current) = to_follow {
+        let entry = found.get(&current).expect("Every found vertex should have an entry");
+        to_follow = entry.previous().cloned();
+        path.push((current, data(entry)));
+    }
+    path
+}
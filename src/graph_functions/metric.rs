@@ -0,0 +1,166 @@
+use bevy::{prelude::{Entity, Query}, utils::HashMap};
+
+use crate::graph_vertex::GraphVertex;
+
+use super::{bfs::bfs, dfs::{are_connected, dfs, reachable_count}, dijkstra::{dijkstra_search, dijkstra_tree, k_shortest_paths}, neighbourhood::{at_step, within_distance, within_steps}, Distance, GraphError, GraphPath, Steps};
+
+
+/// A graph query whose edge weights matter, exposing only the algorithms that measure paths by their total weight
+///
+/// Calling [`bfs`] on a weighted graph is an easy mistake, as it silently ignores the weights and returns the path with the fewest steps.
+/// Wrapping the query in [`Weighted`] or [`Unweighted`] once, where the graph's metric is decided, means only the matching algorithms can be
+/// called on it, so the mistake is caught at compile time. Each method is identical to the function of the same name.
+///
+/// # Example
+///
+/// ```ignore
+/// //A system that paths units along roads, where the length of each road matters
+/// fn path_units(
+///     roads: Query<&RoadVertex>,
+///     mut units: Query<(&CurrentJunction, &Target, &mut UnitPath)>
+/// ) {
+///     let roads = Weighted::new(&roads);
+///     for (junction, target, mut unit_path) in units.iter_mut() {
+///         unit_path.0 = roads.dijkstra_search(junction.0, target.0).ok();
+///     }
+/// }
+///
+/// //A system that moves pieces on a board, where every move is a single step
+/// fn move_pieces(
+///     squares: Query<&SquareVertex>,
+///     mut pieces: Query<(&CurrentSquare, &mut Reachable)>
+/// ) {
+///     let squares = Unweighted::new(&squares);
+///     for (square, mut reachable) in pieces.iter_mut() {
+///         reachable.0 = squares.within_steps(square.0, 3).unwrap();
+///     }
+/// }
+/// ```
+///
+/// On a graph where a direct road is shorter in steps but longer in total weight, each wrapper finds the route measured its own way:
+///
+/// ```
+/// use bevy::{ecs::system::SystemState, prelude::*};
+/// use bevy_graph::{graph_functions::{metric::{Unweighted, Weighted}, spawn_graph}, graph_vertex::StandardGraphVertex};
+///
+/// //a direct road from 0 to 2 of weight 3, and a route through 1 of weight 1 + 1
+/// let mut world = World::new();
+/// let vertices = spawn_graph(&mut world.commands(), &[(0, 2, 3.0), (0, 1, 1.0), (1, 2, 1.0)], 3);
+/// world.flush();
+/// let mut system_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+/// let roads = system_state.get(&world);
+///
+/// let by_weight = Weighted::new(&roads).dijkstra_search(vertices[0], vertices[2]).unwrap();
+/// assert_eq!(by_weight.total_weight(), 2.0);
+/// let by_steps = Unweighted::new(&roads).bfs(vertices[0], vertices[2]).unwrap();
+/// assert_eq!(by_steps.len(), 2);
+/// ```
+///
+/// Calling an algorithm that ignores the weights on a [`Weighted`] query doesn't compile:
+///
+/// ```compile_fail
+/// # use bevy::{ecs::system::SystemState, prelude::*};
+/// # use bevy_graph::{graph_functions::{metric::Weighted, spawn_graph}, graph_vertex::StandardGraphVertex};
+/// # let mut world = World::new();
+/// # let vertices = spawn_graph(&mut world.commands(), &[(0, 2, 3.0), (0, 1, 1.0), (1, 2, 1.0)], 3);
+/// # world.flush();
+/// # let mut system_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+/// # let roads = system_state.get(&world);
+/// //there is no Weighted::bfs, as it would ignore the road weights
+/// let route = Weighted::new(&roads).bfs(vertices[0], vertices[2]);
+/// ```
+pub struct Weighted<'a, 'w, 's, V: GraphVertex> {
+    query: &'a Query<'w, 's, &'static V>
+}
+
+impl<'a, 'w, 's, V: GraphVertex> Weighted<'a, 'w, 's, V> {
+    /// Wraps a query whose edge weights should be used when measuring paths
+    pub fn new(query: &'a Query<'w, 's, &'static V>) -> Self {
+        Self{query}
+    }
+
+    /// The wrapped query, for calling functions without a method here
+    pub fn query(&self) -> &'a Query<'w, 's, &'static V> {
+        self.query
+    }
+
+    /// Finds the path with the least total weight, see [`dijkstra_search`]
+    pub fn dijkstra_search(&self, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<f32>, GraphError> {
+        dijkstra_search(self.query, start_ent, end_ent)
+    }
+
+    /// Finds the least total weight to every reachable vertex, see [`dijkstra_tree`]
+    pub fn dijkstra_tree(&self, start_ent: Entity) -> Result<HashMap<Entity, (f32, Option<Entity>)>, GraphError> {
+        dijkstra_tree(self.query, start_ent)
+    }
+
+    /// Finds up to `k` paths in increasing order of total weight, see [`k_shortest_paths`]
+    pub fn k_shortest_paths(&self, start_ent: Entity, end_ent: Entity, k: usize) -> Result<Vec<GraphPath<f32>>, GraphError> {
+        k_shortest_paths(self.query, start_ent, end_ent, k)
+    }
+
+    /// Finds every vertex within a total weight of the start vertex, see [`within_distance`]
+    pub fn within_distance(&self, start_ent: Entity, max_distance: f32) -> Result<Vec<(Entity, Distance)>, GraphError> {
+        within_distance(self.query, start_ent, max_distance)
+    }
+
+    /// Whether the end vertex can be reached from the start vertex, see [`are_connected`]
+    pub fn are_connected(&self, start_ent: Entity, end_ent: Entity) -> Result<bool, GraphError> {
+        are_connected(self.query, start_ent, end_ent)
+    }
+
+    /// The number of vertices that can be reached from the start vertex, see [`reachable_count`]
+    pub fn reachable_count(&self, start_ent: Entity) -> Result<usize, GraphError> {
+        reachable_count(self.query, start_ent)
+    }
+}
+
+
+/// A graph query whose edge weights are ignored, exposing only the algorithms that measure paths by their number of steps
+///
+/// See [`Weighted`] for why and how to use it.
+pub struct Unweighted<'a, 'w, 's, V: GraphVertex> {
+    query: &'a Query<'w, 's, &'static V>
+}
+
+impl<'a, 'w, 's, V: GraphVertex> Unweighted<'a, 'w, 's, V> {
+    /// Wraps a query whose edge weights should be ignored, measuring paths by their number of steps
+    pub fn new(query: &'a Query<'w, 's, &'static V>) -> Self {
+        Self{query}
+    }
+
+    /// The wrapped query, for calling functions without a method here
+    pub fn query(&self) -> &'a Query<'w, 's, &'static V> {
+        self.query
+    }
+
+    /// Finds the path with the fewest steps, see [`bfs`]
+    pub fn bfs(&self, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<()>, GraphError> {
+        bfs(self.query, start_ent, end_ent)
+    }
+
+    /// Finds a path, not necessarily the shortest, see [`dfs`]
+    pub fn dfs(&self, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<()>, GraphError> {
+        dfs(self.query, start_ent, end_ent)
+    }
+
+    /// Finds every vertex within a number of steps of the start vertex, see [`within_steps`]
+    pub fn within_steps(&self, start_ent: Entity, max_steps: u64) -> Result<Vec<(Entity, Steps)>, GraphError> {
+        within_steps(self.query, start_ent, max_steps)
+    }
+
+    /// Finds every vertex exactly a number of steps from the start vertex, see [`at_step`]
+    pub fn at_step(&self, start_ent: Entity, step: u64) -> Result<Vec<Entity>, GraphError> {
+        at_step(self.query, start_ent, step)
+    }
+
+    /// Whether the end vertex can be reached from the start vertex, see [`are_connected`]
+    pub fn are_connected(&self, start_ent: Entity, end_ent: Entity) -> Result<bool, GraphError> {
+        are_connected(self.query, start_ent, end_ent)
+    }
+
+    /// The number of vertices that can be reached from the start vertex, see [`reachable_count`]
+    pub fn reachable_count(&self, start_ent: Entity) -> Result<usize, GraphError> {
+        reachable_count(self.query, start_ent)
+    }
+}
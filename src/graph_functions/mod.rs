@@ -9,6 +9,19 @@ pub mod dfs;
 pub mod astar;
 pub mod dijkstra;
 pub mod neighbourhood;
+pub mod analysis;
+pub mod all_pairs;
+pub mod dag;
+pub mod world;
+pub mod flow;
+#[cfg(feature = "gizmos")]
+pub mod debug_draw;
+pub mod path_cache;
+pub mod searcher;
+pub mod implicit;
+pub mod view;
+pub mod metric;
+pub mod spanning;
 
 use bfs::*;
 use dfs::*;
@@ -16,6 +29,8 @@ use dijkstra::*;
 use astar::*;
 use neighbourhood::*;
 
+pub use helper::{all_edges, find_all_by_label, AllEdges, find_by_label, load_graph, load_graph_with_policy, reverse_adjacency, spawn_graph, wire_edges_by_label, DuplicateEdgePolicy};
+
 
 
 //TODO:
@@ -54,13 +69,15 @@ pub trait GraphFunctionExt{
     fn dfs_multiple_end<V, CE, FE>(&mut self, start_ent: Entity, end_determiner: FE, max_ends: Option<usize>) -> Result<Vec<GraphPath<()>>, GraphError> //compute paths to every end point satisfying the end_determiner, up to a maximum amount provided (perhaps a limiter -> None, max_steps, max_number)
     where V: GraphVertex, CE: Component, FE: Fn(&CE) -> bool;
 
+    fn are_connected<V: GraphVertex>(&mut self, start_ent: Entity, end_ent: Entity) -> Result<bool, GraphError>;
+
 
     //====================================
     // Dijsktra Search Based Algorithms
     //====================================
     fn dijkstra_search<V: GraphVertex>(&mut self, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<f32>, GraphError>;
 
-    fn dijkstra_computed_end<V, CE, FE>(&mut self, start_ent: Entity, end_determiner: FE) -> Result<GraphPath<f32>, GraphError>
+    fn dijkstra_computed_end<V, CE, FE>(&mut self, start_ent: Entity, end_determiner: FE, max_distance: Option<f32>) -> Result<GraphPath<f32>, GraphError>
     where V: GraphVertex, CE: Component, FE: Fn(&CE) -> bool;
 
     fn dijkstra_multiple_end<V, CE, FE>(&mut self, start_ent: Entity, end_determiner: FE, max_ends: Option<usize>, max_dist: Option<f32>) -> Result<Vec<GraphPath<f32>>, GraphError> //compute paths to every end point satisfying the end_determiner, up to a maximum amount provided (perhaps a limiter -> None, max_steps, max_number)
@@ -83,11 +100,11 @@ pub trait GraphFunctionExt{
     //====================================
     // Neighbourhood Algorithms
     //====================================
-    fn within_steps<V:GraphVertex>(&mut self, start_ent: Entity, max_steps: usize) -> Result<Vec<(Entity, usize)>, GraphError>;
+    fn within_steps<V:GraphVertex>(&mut self, start_ent: Entity, max_steps: u64) -> Result<Vec<(Entity, Steps)>, GraphError>;
 
-    fn within_distance<V:GraphVertex>(&mut self, start_ent: Entity, max_distance: f32) -> Result<Vec<(Entity, f32)>, GraphError>;
+    fn within_distance<V:GraphVertex>(&mut self, start_ent: Entity, max_distance: f32) -> Result<Vec<(Entity, Distance)>, GraphError>;
 
-    fn at_step<V:GraphVertex>(&mut self, start_ent: Entity, at_step: usize) -> Result<Vec<Entity>, GraphError>;
+    fn at_step<V:GraphVertex>(&mut self, start_ent: Entity, at_step: u64) -> Result<Vec<Entity>, GraphError>;
 
 }
 
@@ -114,16 +131,21 @@ impl<'world, 'state, S: QueryData, T: QueryFilter> GraphFunctionExt for Query<'w
         let mut lensed = self.transmute_lens::<(&V, &C)>();
         dfs_computed_end(&lensed.query(), start_ent, end_determiner)
     }
+
+    fn are_connected<V: GraphVertex>(&mut self, start_ent: Entity, end_ent: Entity) -> Result<bool, GraphError> {
+        let mut lensed = self.transmute_lens::<&V>();
+        are_connected(&lensed.query(), start_ent, end_ent)
+    }
     
     fn dijkstra_search<V: GraphVertex>(&mut self, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<f32>, GraphError> {
         let mut lensed = self.transmute_lens::<&V>();
         dijkstra_search(&lensed.query(), start_ent, end_ent)
     }
     
-    fn dijkstra_computed_end<V, C, F>(&mut self, start_ent: Entity, end_determiner: F) -> Result<GraphPath<f32>, GraphError>
+    fn dijkstra_computed_end<V, C, F>(&mut self, start_ent: Entity, end_determiner: F, max_distance: Option<f32>) -> Result<GraphPath<f32>, GraphError>
     where V: GraphVertex, C: Component, F: Fn(&C) -> bool {
         let mut lensed = self.transmute_lens::<(&V, &C)>();
-        dijkstra_computed_end(&lensed.query(), start_ent, end_determiner)
+        dijkstra_computed_end(&lensed.query(), start_ent, end_determiner, max_distance)
     }
     
     fn a_star_search<V, C, F>(&mut self, start_ent: Entity, end_ent: Entity, heuristic_determiner: F) -> Result<GraphPath<f32>, GraphError> 
@@ -132,17 +154,17 @@ impl<'world, 'state, S: QueryData, T: QueryFilter> GraphFunctionExt for Query<'w
         a_star_search(&lensed.query(), start_ent, end_ent, heuristic_determiner)
     }
     
-    fn within_steps<V:GraphVertex>(&mut self, start_ent: Entity, max_steps: usize) -> Result<Vec<(Entity, usize)>, GraphError> {
+    fn within_steps<V:GraphVertex>(&mut self, start_ent: Entity, max_steps: u64) -> Result<Vec<(Entity, Steps)>, GraphError> {
         let mut lensed = self.transmute_lens::<&V>();
         within_steps(&lensed.query(), start_ent, max_steps)
     }
     
-    fn within_distance<V:GraphVertex>(&mut self, start_ent: Entity, max_distance: f32) -> Result<Vec<(Entity, f32)>, GraphError> {
+    fn within_distance<V:GraphVertex>(&mut self, start_ent: Entity, max_distance: f32) -> Result<Vec<(Entity, Distance)>, GraphError> {
         let mut lensed = self.transmute_lens::<&V>();
         within_distance(&lensed.query(), start_ent, max_distance)
     }
     
-    fn at_step<V:GraphVertex>(&mut self, start_ent: Entity, step: usize) -> Result<Vec<Entity>, GraphError> {
+    fn at_step<V:GraphVertex>(&mut self, start_ent: Entity, step: u64) -> Result<Vec<Entity>, GraphError> {
         let mut lensed = self.transmute_lens::<&V>();
         at_step(&lensed.query(), start_ent, step)
         
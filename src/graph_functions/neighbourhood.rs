@@ -1,11 +1,11 @@
-use std::{cmp::Reverse, collections::VecDeque};
+use std::{cell::Cell, cmp::Reverse, collections::VecDeque};
 
-use bevy::{prelude::{Entity, Query}, utils::{HashMap, HashSet}};
+use bevy::{prelude::{Component, Entity, Query}, utils::{HashMap, HashSet}};
 use priority_queue::PriorityQueue;
 
 use crate::graph_vertex::GraphVertex;
 
-use super::{GraphError, PathWeight};
+use super::{helper::{directed_neighbours, get_start, reverse_adjacency}, Direction, Distance, GraphError, GraphPath, PathWeight, Steps};
 
 
 
@@ -17,7 +17,7 @@ use super::{GraphError, PathWeight};
 /// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
 /// 
 /// # Example
 /// 
@@ -31,53 +31,145 @@ use super::{GraphError, PathWeight};
 pub fn within_steps<V: GraphVertex>(
     query: &Query<&V>,
     start_ent: Entity,
-    max_steps: usize
-) -> Result<Vec<(Entity, usize)>, GraphError> {
+    max_steps: u64
+) -> Result<Vec<(Entity, Steps)>, GraphError> {
+    get_start(query, start_ent)?;
+    Ok(within_steps_by(start_ent, max_steps, None, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours())))
+}
 
-    //vector of vertices that we want to check, alongside their distance from the start vertex
-    let mut to_view: VecDeque<&V> = VecDeque::from([query.get(start_ent)?]);
+/// Returns all vertices within the given number of steps, other than the start vertex itself
+/// 
+/// Identical to [`within_steps`], except the start vertex, always found at step 0, is left out. This suits "what else is near me" queries,
+/// where the start vertex would otherwise have to be filtered out of the result by hand. The result is empty if nothing else is in range.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that alerts the other guards in earshot when a guard spots the player
+/// fn call_for_help(
+///     spotter: Query<&CurrentRoom, With<SpottedPlayer>>,
+///     rooms: Query<&RoomVertex>
+/// ) {
+///     for room in spotter.iter() {
+///         let others = within_steps_excluding_start(&rooms, room.0, 2).unwrap();
+///     }
+/// }
+/// ```
+pub fn within_steps_excluding_start<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    max_steps: u64
+) -> Result<Vec<(Entity, Steps)>, GraphError> {
+    let mut found = within_steps(query, start_ent, max_steps)?;
+    found.retain(|(ent, _)| *ent != start_ent);
+    Ok(found)
+}
+
+/// Returns all vertices within the given number of steps, following edges in the given [`Direction`]
+/// 
+/// Identical to [`within_steps`] when using [`Direction::Outgoing`]. With [`Direction::Incoming`] edges are followed backwards, giving the vertices
+/// that can reach the start vertex, while [`Direction::Both`] treats every edge as undirected. Following incoming edges requires a pass over
+/// the entire query to determine each vertex's incoming edges, which is why the query must also provide the entities.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds all rooms adjacent to the player's room, regardless of which way the doors open
+/// fn nearby_rooms(
+///     player: Query<&CurrentRoom, With<Player>>,
+///     rooms: Query<(Entity, &RoomVertex)>
+/// ) {
+///     let start = player.single().0;
+///     let nearby = within_steps_directed(&rooms, start, 1, Direction::Both).unwrap();
+///     println!("{} rooms are a single door away", nearby.len() - 1);
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`within_distance_directed`]: For the same search by edge weight, rather than steps.
+pub fn within_steps_directed<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    start_ent: Entity,
+    max_steps: u64,
+    direction: Direction
+) -> Result<Vec<(Entity, Steps)>, GraphError> {
+    get_start(query, start_ent)?;
+    let incoming = reverse_adjacency(query);
+    Ok(within_steps_by(start_ent, max_steps, None, |ent| {
+        directed_neighbours(query, &incoming, ent, direction)
+        .map(|neighbours| neighbours.into_iter().map(|(ent, _)| ent).collect())
+    }))
+}
+
+/// Breadth-first search over the neighbours returned by the provided function, which should return [`None`] for any entity that isn't a vertex
+fn within_steps_by<N>(
+    start_ent: Entity,
+    max_steps: u64,
+    max_vertices: Option<usize>,
+    neighbours_of: N
+) -> Vec<(Entity, Steps)> 
+where
+    N: Fn(Entity) -> Option<Vec<Entity>>
+{
+    let max_vertices = max_vertices.unwrap_or(usize::MAX);
+
+    //the neighbours of the vertices that we want to check
+    let mut to_view: VecDeque<Vec<Entity>> = VecDeque::from([neighbours_of(start_ent).unwrap_or_default()]);
 
     //hashset storing entities weve checked already
     let mut seen: HashSet<Entity> = HashSet::new();
     seen.insert(start_ent);
 
     //final output list
-    let mut valid: Vec<(Entity, usize)> = vec![(start_ent, 0)];
+    let mut valid: Vec<(Entity, Steps)> = vec![(start_ent, Steps(0))];
+    if valid.len() >= max_vertices {valid.truncate(max_vertices); return valid;}
+    if max_steps == 0 {return valid;}
 
-    //the current step we are on
-    let mut current_step = 0;
+    //the current step we are on, which saturates rather than wrapping on enormous graphs
+    let mut current_step: u64 = 0;
     //the number of vertices left to check at this distance
     let mut at_current_step = 1;
     
-    while let Some(current_vert) = to_view.pop_front(){
+    while let Some(current_neighbours) = to_view.pop_front(){
         
         //decrement number left to check at this distance
         at_current_step -= 1;
 
-        for neighbour in current_vert.get_neighbours(){
+        for neighbour in current_neighbours{
             //check if we have checked this entity before, skipping this iteration if so
             if !seen.insert(neighbour){continue;}
             //otherwise add it to the valid list and to_view queue
-            if let Ok(vert) = query.get(neighbour){
-                to_view.push_back(vert);
-                valid.push((neighbour, current_step+1));
+            if let Some(next_neighbours) = neighbours_of(neighbour){
+                to_view.push_back(next_neighbours);
+                valid.push((neighbour, Steps(current_step.saturating_add(1))));
+                //stop once we've found as many vertices as we were asked for
+                if valid.len() == max_vertices {return valid;}
             }
         }
 
         //if we've viewed all at the current step, increment current_step and calculate how many at this step
         if at_current_step == 0 {
-            current_step += 1;
+            current_step = current_step.saturating_add(1);
             //check if we've checked far enough
-            if current_step == max_steps {break;}
+            if current_step >= max_steps {break;}
             at_current_step = to_view.len(); //if this is 0, we shouldnt run another loop iteration so should be ok
         }
     }
-    Ok(valid)
+    valid
 }
 
 /// TODO
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
 /// 
 /// [`GraphError::NegativeWeight`]: If a vertex returns a negative edge weight.
 /// 
@@ -94,39 +186,221 @@ pub fn within_distance<V: GraphVertex>(
     query: &Query<&V>,
     start_ent: Entity,
     max_distance: f32,
-) -> Result<Vec<(Entity, f32)>, GraphError> {
-
+) -> Result<Vec<(Entity, Distance)>, GraphError> {
     //test for a valid start
-    query.get(start_ent)?;
+    get_start(query, start_ent)?;
+    within_distance_by(start_ent, max_distance, None, None, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours_with_weight()))
+}
+
+/// Returns all vertices within the given distance, other than the start vertex itself
+/// 
+/// Identical to [`within_distance`], except the start vertex, always found at distance 0, is left out. Other vertices reached at distance 0,
+/// via edges of zero weight, are still included.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex returns a negative edge weight.
+/// 
+/// # See also
+/// 
+/// [`within_steps_excluding_start`]: For the same search by number of steps.
+pub fn within_distance_excluding_start<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    max_distance: f32,
+) -> Result<Vec<(Entity, Distance)>, GraphError> {
+    let mut found = within_distance(query, start_ent, max_distance)?;
+    found.retain(|(ent, _)| *ent != start_ent);
+    Ok(found)
+}
+
+/// Returns all vertices within the given distance, by edge weight, following edges in the given [`Direction`]
+/// 
+/// Identical to [`within_distance`] when using [`Direction::Outgoing`]. With [`Direction::Incoming`] edges are followed backwards, giving the vertices
+/// that can reach the start vertex within the distance, while [`Direction::Both`] treats every edge as undirected with its original weight. 
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex returns a negative edge weight.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that alerts every guard within earshot of a noise, regardless of edge direction
+/// fn alert_guards(
+///     noise: Query<&NoiseTile>,
+///     tiles: Query<(Entity, &VertexType)>
+/// ) {
+///     for noise_tile in noise.iter() {
+///         let heard_by = within_distance_directed(&tiles, noise_tile.0, 10.0, Direction::Both).unwrap();
+///     }
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`within_steps_directed`]: For the same search by number of steps, rather than distance.
+pub fn within_distance_directed<V: GraphVertex>(
+    query: &Query<(Entity, &V)>,
+    start_ent: Entity,
+    max_distance: f32,
+    direction: Direction
+) -> Result<Vec<(Entity, Distance)>, GraphError> {
+    get_start(query, start_ent)?;
+    let incoming = reverse_adjacency(query);
+    within_distance_by(start_ent, max_distance, None, None, |ent| directed_neighbours(query, &incoming, ent, direction))
+}
+
+/// Returns all vertices within the given distance that can be reached using valid edges, alongside an error for each invalid edge found
+/// 
+/// Identical to [`within_distance`], except that rather than ending the search on the first negative edge weight, each negative or non-finite
+/// edge is skipped and recorded. The search continues over the rest of the graph, so this gives as much of the result as possible when
+/// analysing imperfect data, eg an imported map. An invalid edge is recorded each time it is found, which is at most once per edge.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that reports problems with an imported map while still showing the reachable area
+/// fn check_imported_map(
+///     player: Query<&CurrentTile, With<Player>>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     let (reachable, problems) = within_distance_lenient(&tiles, player.single().0, 50.0).unwrap();
+///     for problem in problems {
+///         warn!("Imported map has a bad edge: {}", problem);
+///     }
+/// }
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn within_distance_lenient<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    max_distance: f32,
+) -> Result<(Vec<(Entity, Distance)>, Vec<GraphError>), GraphError> {
+    get_start(query, start_ent)?;
+    let mut edge_errors = Vec::new();
+    let reachable = within_distance_by(start_ent, max_distance, None, Some(&mut edge_errors), |ent| query.get(ent).ok().map(|vert| vert.get_neighbours_with_weight()))?;
+    Ok((reachable, edge_errors))
+}
+
+/// Returns all vertices within the given distance, where each edge weight is first passed through the provided transform
+/// 
+/// Identical to [`within_distance`], except the distance of each edge is `transform(weight)` rather than its weight. This allows the search to be
+/// reused for "maximising" style queries, eg with a reciprocal transform an edge with a large reward becomes a short edge, so the vertices
+/// reached via the most rewarding edges come first. The search is still Dijkstra's algorithm, so the transformed weights must be non-negative.
+/// A transformed weight of [`f32::INFINITY`] is allowed, and makes the edge unusable.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If the transform returns a negative weight for an edge.
+/// 
+/// [`GraphError::NonFiniteWeight`]: If the transform returns NaN for an edge.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds the tiles a scout can reach, preferring routes along edges with a high scouting reward
+/// fn plan_scouting(
+///     scout: Query<&CurrentTile, With<Scout>>,
+///     tiles: Query<&RewardVertex>
+/// ) {
+///     let by_reward = within_distance_transformed(&tiles, scout.single().0, 5.0, |reward| 1.0 / reward).unwrap();
+/// }
+/// ```
+pub fn within_distance_transformed<V, T>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    max_distance: f32,
+    transform: T
+) -> Result<Vec<(Entity, Distance)>, GraphError> 
+where
+    V: GraphVertex,
+    T: Fn(f32) -> f32
+{
+    get_start(query, start_ent)?;
+    //negative weights are caught by the search itself, but NaN would compare as neither negative nor too far, so is caught here
+    let found_nan = Cell::new(false);
+    let reachable = within_distance_by(start_ent, max_distance, None, None, |ent| {
+        let neighbours = query.get(ent).ok()?.get_neighbours_with_weight().into_iter()
+        .map(|(neighbour_ent, edge_weight)| (neighbour_ent, transform(edge_weight)))
+        .collect::<Vec<_>>();
+        if neighbours.iter().any(|(_, edge_weight)| edge_weight.is_nan()) {found_nan.set(true);}
+        Some(neighbours)
+    })?;
+    if found_nan.get() {return Err(GraphError::NonFiniteWeight);}
+    Ok(reachable)
+}
+
+/// Dijkstra's algorithm over the neighbours returned by the provided function, which should return [`None`] for any entity that isn't a vertex
+/// 
+/// If `edge_errors` is provided, invalid edges are recorded in it and skipped, rather than ending the search with an error.
+fn within_distance_by<N>(
+    start_ent: Entity,
+    max_distance: f32,
+    max_vertices: Option<usize>,
+    mut edge_errors: Option<&mut Vec<GraphError>>,
+    neighbours_of: N
+) -> Result<Vec<(Entity, Distance)>, GraphError> 
+where
+    N: Fn(Entity) -> Option<Vec<(Entity, f32)>>
+{
+    let max_vertices = max_vertices.unwrap_or(usize::MAX);
+
+    //the vertices in the order they were searched, which is in order of increasing distance
+    let mut searched: Vec<(Entity, Distance)> = Vec::new();
 
     //The list of visited entities. stores the cardinality (current minimum found distance to the vertex)
     let mut minimal_dist : HashMap<Entity, PathWeight> = HashMap::new();
     minimal_dist.insert(start_ent, PathWeight{weight: 0.0});
 
+    //the vertices that have been searched, whose distance is therefore final
+    let mut finalized: HashSet<Entity> = HashSet::new();
+
     //create the search queue
     let mut search_queue: PriorityQueue<Entity , Reverse<PathWeight>> = PriorityQueue::new();
     search_queue.push(start_ent, Reverse(PathWeight{weight: 0.0}));
 
     while let Some((sv_ent, Reverse(sv_dist))) = search_queue.pop() {
+        finalized.insert(sv_ent);
+
+        //get the neighbours of the search vertex
+        let Some(sv_neighbours) = neighbours_of(sv_ent) else {continue;};
 
-        //get the GraphVertex info of the search vertex
-        let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+        //stop once we've found as many vertices as we were asked for
+        if searched.len() == max_vertices {break;}
+        searched.push((sv_ent, Distance(sv_dist.value())));
 
         //loop over this vertex's neighbours
-        for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight(){
+        for (neighbour_ent, edge_weight) in sv_neighbours{
+            if let Some(edge_errors) = edge_errors.as_deref_mut() {
+                //when collecting errors, skip any edge that can't be given a meaningful distance
+                if !edge_weight.is_finite() {edge_errors.push(GraphError::NonFiniteWeight); continue;}
+                if edge_weight < 0.0 {edge_errors.push(GraphError::NegativeWeight); continue;}
+            } else if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
 
-            if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+            //a searched vertex can't be improved on, even via zero weight edges, so don't update it again
+            if finalized.contains(&neighbour_ent) {continue;}
 
             //Determine the distance to this neighbour via the path to the search vertex
             let total_dist = sv_dist + edge_weight;
-            if total_dist > (PathWeight{weight: max_distance}) {continue;}
+            if total_dist > (PathWeight::new(max_distance)) {continue;}
 
             //check if we have visited this vertex before
             //if so, compare the cardinalities to see if we should update
             if let Some(dist) = minimal_dist.get_mut(&neighbour_ent) {
-                //check if the vertex was visited already at a closer distance
+                //check if the vertex was visited already at a closer or equal distance
                 //if so we ignore this vertex
-                if total_dist > *dist {continue;}
+                if total_dist >= *dist {continue;}
                 //otherwise update the vertex's distance, previous vertex and priority in the queue
                 *dist = total_dist;
                 search_queue.change_priority(&neighbour_ent, Reverse(total_dist));
@@ -138,7 +412,155 @@ pub fn within_distance<V: GraphVertex>(
         }
     }
 
-    Ok(minimal_dist.into_iter().map(|(ent, pathweight)| (ent, pathweight.weight)).collect())
+    Ok(searched)
+}
+
+
+/// Returns the vertices within the given number of steps, stopping once `max_vertices` have been found
+/// 
+/// Identical to [`within_steps`], except the search ends as soon as `max_vertices` vertices (including the start vertex) have been found,
+/// bounding the time and memory used on dense graphs. The vertices are returned in the order they were discovered, so those at fewer steps
+/// always come first. A `max_vertices` of [`None`] places no limit on the number of vertices.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that picks up to 10 nearby tiles to spawn enemies on
+/// fn spawn_nearby_enemies(
+///     player: Query<&CurrentTile, With<Player>>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     let nearby = within_steps_capped(&tiles, player.single().0, 5, Some(10)).unwrap();
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`within_distance_capped`]: For the same limit when searching by distance.
+pub fn within_steps_capped<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    max_steps: u64,
+    max_vertices: Option<usize>
+) -> Result<Vec<(Entity, Steps)>, GraphError> {
+    get_start(query, start_ent)?;
+    Ok(within_steps_by(start_ent, max_steps, max_vertices, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours())))
+}
+
+/// Returns the vertices within the given distance, stopping once `max_vertices` have been found
+/// 
+/// Identical to [`within_distance`], except the search ends as soon as `max_vertices` vertices (including the start vertex) have been found.
+/// As the vertices are found in order of increasing distance, the result is the `max_vertices` closest vertices within the distance.
+/// A `max_vertices` of [`None`] places no limit on the number of vertices.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex returns a negative edge weight.
+/// 
+/// # See also
+/// 
+/// [`within_steps_capped`]: For the same limit when searching by steps.
+pub fn within_distance_capped<V: GraphVertex>(
+    query: &Query<&V>,
+    start_ent: Entity,
+    max_distance: f32,
+    max_vertices: Option<usize>
+) -> Result<Vec<(Entity, Distance)>, GraphError> {
+    get_start(query, start_ent)?;
+    within_distance_by(start_ent, max_distance, max_vertices, None, |ent| query.get(ent).ok().map(|vert| vert.get_neighbours_with_weight()))
+}
+
+/// Returns the vertices matching the predicate that can be reached within both the given number of steps and the given distance
+/// 
+/// A vertex is returned if some path to it has at most `max_steps` edges and a total weight of at most `max_distance`, such as shops within
+/// 5 blocks and 2km. Each is given with the shortest distance of such a path, and the fewest steps of a path with that distance. The shortest 
+/// path to a vertex may take too many steps while a longer one does not, so this is not the same as filtering [`within_distance`] by the steps of
+/// its paths. The start vertex is included, at 0 steps and distance 0, if it matches the predicate. The vertices are ordered by distance, then 
+/// by steps.
+/// 
+/// Edges to entities that don't appear in the query are ignored. The search relaxes edges one step at a time, so takes up to `max_steps` passes
+/// over the vertices in range.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+/// 
+/// [`GraphError::NegativeWeight`]: If a vertex returns a negative edge weight.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that lists the shops a shopper can walk to in at most 5 blocks and 2km
+/// fn nearby_shops(
+///     shopper: Query<&CurrentJunction, With<Shopper>>,
+///     junctions: Query<(&JunctionVertex, &Building)>
+/// ) {
+///     let shops = reachable_within(&junctions, shopper.single().0, 5, 2000.0, |building| building.is_shop()).unwrap();
+/// }
+/// ```
+/// 
+/// # See also
+/// 
+/// [`within_steps`]: For vertices within a number of steps alone.
+/// 
+/// [`within_distance`]: For vertices within a distance alone.
+pub fn reachable_within<V, C, F>(
+    query: &Query<(&V, &C)>,
+    start_ent: Entity,
+    max_steps: u64,
+    max_distance: f32,
+    predicate: F
+) -> Result<Vec<(Entity, Steps, Distance)>, GraphError> 
+where
+    V: GraphVertex,
+    C: Component,
+    F: Fn(&C) -> bool
+{
+    get_start(query, start_ent)?;
+
+    //the shortest distance found to each vertex using at most the current number of steps, and the fewest steps giving that distance
+    let mut best: HashMap<Entity, (Steps, PathWeight)> = HashMap::new();
+    best.insert(start_ent, (Steps(0), PathWeight{weight: 0.0}));
+
+    //the vertices whose distance improved on the last step, as only their edges can improve other vertices on the next step
+    let mut improved: Vec<(Entity, PathWeight)> = vec![(start_ent, PathWeight{weight: 0.0})];
+    let mut step = 0;
+
+    while step < max_steps && !improved.is_empty() {
+        step += 1;
+        let mut next: HashMap<Entity, PathWeight> = HashMap::new();
+        for (ent, dist) in improved {
+            let Ok((vert, _)) = query.get(ent) else {continue;};
+            for (neighbour_ent, edge_weight) in vert.get_neighbours_with_weight() {
+                if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+                if !query.contains(neighbour_ent) {continue;}
+
+                let total_dist = dist + edge_weight;
+                if total_dist > PathWeight::new(max_distance) {continue;}
+                //only keep paths strictly shorter than any using fewer steps, so each vertex keeps the fewest steps for its distance
+                if best.get(&neighbour_ent).is_some_and(|(_, best_dist)| total_dist >= *best_dist) {continue;}
+                if next.get(&neighbour_ent).is_some_and(|next_dist| total_dist >= *next_dist) {continue;}
+                next.insert(neighbour_ent, total_dist);
+            }
+        }
+        for (&ent, &dist) in next.iter() {
+            best.insert(ent, (Steps(step), dist));
+        }
+        improved = next.into_iter().collect();
+    }
+
+    let mut reachable: Vec<(Entity, Steps, Distance)> = best.into_iter()
+    .filter(|(ent, _)| query.get(*ent).is_ok_and(|(_, data)| predicate(data)))
+    .map(|(ent, (steps, dist))| (ent, steps, Distance(dist.value())))
+    .collect();
+    reachable.sort_by(|(ent1, steps1, dist1), (ent2, steps2, dist2)| dist1.total_cmp(dist2).then(steps1.cmp(steps2)).then(ent1.cmp(ent2)));
+    Ok(reachable)
 }
 
 
@@ -149,7 +571,7 @@ pub fn within_distance<V: GraphVertex>(
 /// 
 /// # Errors
 /// 
-/// [`GraphError::InvalidEntity`]: If the provided start vertex entity does not appear in the provided query.
+/// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
 /// 
 /// # Example
 /// 
@@ -161,9 +583,215 @@ pub fn within_distance<V: GraphVertex>(
 pub fn at_step<V:GraphVertex>(
     query: &Query<&V>,
     start_ent: Entity,
-    at_step: usize,
+    at_step: u64,
 ) -> Result<Vec<Entity>, GraphError> {
     Ok(within_steps(query, start_ent, at_step)?.into_iter()
-    .filter_map(|(ent, step)| if step == at_step {Some(ent)} else {None})
+    .filter_map(|(ent, step)| if *step == at_step {Some(ent)} else {None})
+    .collect())
+}
+
+
+/// Returns each neighbour of the given vertex paired with its component from the query
+/// 
+/// Saves fetching each neighbour's component separately when writing a custom traversal. Neighbours that are not in the query are skipped,
+/// and the neighbours are returned in the order given by [`GraphVertex::get_neighbours`].
+/// 
+/// # Errors
+/// 
+/// [`GraphError::StartBlocked`]: If the provided vertex entity does not appear in the provided query.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that finds the richest room next to the player
+/// fn richest_neighbour(
+///     player: Query<&CurrentRoom, With<Player>>,
+///     rooms: Query<(&RoomVertex, &Treasure)>
+/// ) {
+///     let neighbours = neighbours_with(&rooms, player.single().0).unwrap();
+///     let richest = neighbours.into_iter().max_by_key(|(_, treasure)| treasure.gold);
+/// }
+/// ```
+pub fn neighbours_with<'a, V: GraphVertex, C: Component>(
+    query: &'a Query<(&V, &C)>,
+    ent: Entity
+) -> Result<Vec<(Entity, &'a C)>, GraphError> {
+    let (vert, _) = get_start(query, ent)?;
+    Ok(vert.get_neighbours().into_iter()
+    .filter_map(|neighbour_ent| query.get(neighbour_ent).ok().map(|(_, data)| (neighbour_ent, data)))
     .collect())
 }
+
+/// Picks one neighbour of the vertex at random, with probability proportional to `weight_fn` applied to the weight of the edge to it
+/// 
+/// `rng` should return a uniformly random value in `[0, 1)`, so any random number source can be used, eg `|| rng.gen::<f32>()`. Passing 
+/// `|weight| 1.0 / weight` prefers cheaper edges, giving wandering that avoids costly moves without always taking the cheapest. Edges whose
+/// sampling weight is not positive and finite are never picked. The neighbours are sampled in a single pass using weighted reservoir sampling.
+/// 
+/// Returns [`None`] if the vertex has no neighbours that can be picked.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that moves each wandering NPC to a random neighbouring tile, preferring those that are easy to reach
+/// fn wander(
+///     mut npcs: Query<&mut CurrentTile, With<Wandering>>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     let mut rng = rand::thread_rng();
+///     for mut tile in npcs.iter_mut() {
+///         let Ok(vert) = tiles.get(tile.0) else {continue;};
+///         if let Some(next) = weighted_random_neighbour(vert, || rng.gen::<f32>(), |weight| 1.0 / weight) {
+///             tile.0 = next;
+///         }
+///     }
+/// }
+/// ```
+pub fn weighted_random_neighbour<V, R, W>(
+    vertex: &V,
+    mut rng: R,
+    weight_fn: W
+) -> Option<Entity> 
+where
+    V: GraphVertex,
+    R: FnMut() -> f32,
+    W: Fn(f32) -> f32
+{
+    let mut total = 0.0;
+    let mut chosen = None;
+    for (neighbour_ent, edge_weight) in vertex.get_neighbours_with_weight() {
+        let sample_weight = weight_fn(edge_weight);
+        if !sample_weight.is_finite() || sample_weight <= 0.0 {continue;}
+        total += sample_weight;
+        //replace the current choice with probability sample_weight / total, which leaves each neighbour chosen in proportion to its weight
+        if rng() * total < sample_weight {chosen = Some(neighbour_ent);}
+    }
+    chosen
+}
+
+/// A paused run of Dijkstra's algorithm from a start vertex, which can be resumed to search further out
+/// 
+/// [`within_distance`] has to search from scratch every time the distance is increased. Instead, this keeps the search queue, the minimal distances 
+/// and the previous vertices between calls, so [`DijkstraState::expand_to`] only needs to search the vertices between the old and new distance.
+/// This makes progressively widening searches, eg a growing area of effect, cheap.
+/// 
+/// The graph should not change between expansions, otherwise the results may be incorrect.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that grows a spreading fire by one unit of distance every frame
+/// fn spread_fire(
+///     mut fire: ResMut<FireSpread>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     let new_distance = fire.state.max_distance() + 1.0;
+///     fire.state.expand_to(&tiles, new_distance).unwrap();
+///     for (tile, _) in fire.state.reached() {
+///         //set the tile on fire
+///     }
+/// }
+/// ```
+pub struct DijkstraState {
+    start_ent: Entity,
+    max_distance: f32,
+    /// the vertices in the order they were searched, which is in order of increasing distance
+    searched: Vec<(Entity, Distance)>,
+    /// the current minimum found distance to each vertex
+    minimal_dist: HashMap<Entity, PathWeight>,
+    /// the previous vertex of the shortest path found to each vertex
+    path_previous: HashMap<Entity, Entity>,
+    /// the vertices yet to be searched, with ties in distance broken by the entity index
+    search_queue: PriorityQueue<Entity, Reverse<(PathWeight, u32)>>
+}
+
+impl DijkstraState {
+    /// Creates a search starting at the given vertex, which has not yet searched any vertices
+    /// 
+    /// # Errors
+    /// 
+    /// [`GraphError::StartBlocked`]: If the provided start vertex entity does not appear in the provided query.
+    pub fn new<V: GraphVertex>(query: &Query<&V>, start_ent: Entity) -> Result<Self, GraphError> {
+        get_start(query, start_ent)?;
+        let mut search_queue = PriorityQueue::new();
+        search_queue.push(start_ent, Reverse((PathWeight{weight: 0.0}, start_ent.index())));
+        Ok(Self {
+            start_ent,
+            max_distance: 0.0,
+            searched: Vec::new(),
+            minimal_dist: HashMap::from([(start_ent, PathWeight{weight: 0.0})]),
+            path_previous: HashMap::new(),
+            search_queue
+        })
+    }
+
+    /// Continues the search until every vertex within the new distance has been found
+    /// 
+    /// If the new distance is less than the distance already searched to, nothing is done.
+    /// 
+    /// # Errors
+    /// 
+    /// [`GraphError::NegativeWeight`]: If a vertex returns a negative edge weight. The state should not be used after this error.
+    pub fn expand_to<V: GraphVertex>(&mut self, query: &Query<&V>, new_max_distance: f32) -> Result<(), GraphError> {
+        self.max_distance = self.max_distance.max(new_max_distance);
+        let max_dist = PathWeight::new(self.max_distance);
+
+        //only search vertices within the distance, leaving the rest in the queue for the next expansion
+        while let Some((_, Reverse((next_dist, _)))) = self.search_queue.peek() {
+            if *next_dist > max_dist {break;}
+            let (sv_ent, Reverse((sv_dist, _))) = self.search_queue.pop().unwrap();
+
+            //get the GraphVertex info of the search vertex
+            let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+            self.searched.push((sv_ent, Distance(sv_dist.value())));
+
+            //loop over this vertex's neighbours
+            for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight() {
+                if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+
+                //Determine the distance to this neighbour via the path to the search vertex
+                let total_dist = sv_dist + edge_weight;
+
+                //check if we have visited this vertex before
+                //if so, compare the cardinalities to see if we should update
+                if let Some(dist) = self.minimal_dist.get_mut(&neighbour_ent) {
+                    if total_dist >= *dist {continue;}
+                    *dist = total_dist;
+                    self.path_previous.insert(neighbour_ent, sv_ent);
+                    self.search_queue.change_priority(&neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
+                } else {
+                    //vertices beyond the distance are still queued, so they are found by a later expansion
+                    self.minimal_dist.insert(neighbour_ent, total_dist);
+                    self.path_previous.insert(neighbour_ent, sv_ent);
+                    self.search_queue.push(neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The distance that has been searched to so far
+    pub fn max_distance(&self) -> f32 {
+        self.max_distance
+    }
+
+    /// All vertices found within [`DijkstraState::max_distance`] and their distance from the start vertex, in order of increasing distance
+    /// 
+    /// This is the same set of vertices as returned by [`within_distance`] for that distance.
+    pub fn reached(&self) -> &[(Entity, Distance)] {
+        &self.searched
+    }
+
+    /// Returns the shortest path from the start vertex to the given vertex, in **reverse order**, if it has been reached
+    pub fn path_to(&self, end_ent: Entity) -> Option<GraphPath<f32>> {
+        //only vertices that have been searched have a final distance
+        if !self.searched.iter().any(|(ent, _)| *ent == end_ent) {return None;}
+        let mut path = vec![(end_ent, self.minimal_dist[&end_ent].value())];
+        let mut current = end_ent;
+        while current != self.start_ent {
+            current = self.path_previous[&current];
+            path.push((current, self.minimal_dist[&current].value()));
+        }
+        Some(GraphPath::new(path))
+    }
+}
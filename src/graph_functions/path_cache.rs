@@ -0,0 +1,112 @@
+use bevy::{ecs::{removal_detection::RemovedComponents, system::{ResMut, Resource}}, prelude::{Changed, Entity, Query}, utils::HashMap};
+
+use crate::graph_vertex::GraphVertex;
+
+use super::{dijkstra_search, GraphError, GraphPath};
+
+
+/// Resource storing the last path computed between pairs of vertices, alongside the graph generation it was computed for
+///
+/// Any cached path from an older generation is considered stale and will be recomputed on the next lookup. The generation should be bumped
+/// whenever the graph's edges change, either manually with [`PathCache::bump_generation`] or automatically by adding the
+/// [`invalidate_path_cache`] system for the relevant [`GraphVertex`] type.
+#[derive(Resource, Default)]
+pub struct PathCache {
+    generation: u64,
+    paths: HashMap<(Entity, Entity), (u64, GraphPath<f32>)>
+}
+
+impl PathCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current generation of the graph, as known by the cache
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Marks every currently cached path as stale, dropping them from the cache
+    pub fn bump_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.paths.clear();
+    }
+
+    /// Returns the cached path between the two vertices, if one exists for the current generation
+    pub fn get(&self, start_ent: Entity, end_ent: Entity) -> Option<&GraphPath<f32>> {
+        self.paths.get(&(start_ent, end_ent))
+        .and_then(|(generation, path)| if *generation == self.generation {Some(path)} else {None})
+    }
+
+    /// Stores a path between the two vertices for the current generation, replacing any previous entry
+    pub fn insert(&mut self, start_ent: Entity, end_ent: Entity, path: GraphPath<f32>) {
+        self.paths.insert((start_ent, end_ent), (self.generation, path));
+    }
+
+    /// Returns the number of paths currently stored
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+
+/// Runs [`dijkstra_search`] between the two vertices, reusing the path stored in the [`PathCache`] if the graph generation hasn't changed
+///
+/// Only successful searches are cached, so a search resulting in an error will be rerun on each call. As with [`dijkstra_search`],
+/// the path is returned in **reverse order**.
+///
+/// # Errors
+///
+/// See [`dijkstra_search`]
+///
+/// # Example
+///
+/// ```ignore
+/// //A system that recomputes an agent's path every frame, only running the search when the graph has changed
+/// fn follow_path(
+///     agents: Query<(&Agent, &Target)>,
+///     tiles: Query<&VertexType>,
+///     mut cache: ResMut<PathCache>
+/// ) {
+///     for (agent, target) in agents.iter() {
+///         if let Ok(path) = cached_dijkstra(&tiles, &mut cache, agent.tile, target.tile) {
+///             //move along the path
+///         }
+///     }
+/// }
+/// ```
+///
+/// # See also
+///
+/// [`invalidate_path_cache`]: For a system that bumps the cache generation whenever the graph changes
+pub fn cached_dijkstra<V: GraphVertex>(
+    query: &Query<&V>,
+    cache: &mut PathCache,
+    start_ent: Entity,
+    end_ent: Entity
+) -> Result<GraphPath<f32>, GraphError> {
+    if let Some(path) = cache.get(start_ent, end_ent) {return Ok(path.clone());}
+
+    let path = dijkstra_search(query, start_ent, end_ent)?;
+    cache.insert(start_ent, end_ent, path.clone());
+    Ok(path)
+}
+
+
+/// System that bumps the [`PathCache`] generation whenever a vertex of type `V` is added, changed or removed
+///
+/// Add this to your app, eg `app.add_systems(PostUpdate, invalidate_path_cache::<StandardGraphVertex>)`, so cached paths never outlive the graph they were computed on.
+pub fn invalidate_path_cache<V: GraphVertex>(
+    changed: Query<(), Changed<V>>,
+    mut removed: RemovedComponents<V>,
+    cache: Option<ResMut<PathCache>>,
+) {
+    let Some(mut cache) = cache else {return;};
+    //read all removals so they aren't reported again next run
+    let any_removed = removed.read().count() > 0;
+    if any_removed || !changed.is_empty() {cache.bump_generation();}
+}
@@ -0,0 +1,135 @@
+use std::{cmp::Reverse, collections::VecDeque};
+
+use bevy::{prelude::{Entity, Query}, utils::HashMap};
+use priority_queue::PriorityQueue;
+
+use crate::graph_vertex::GraphVertex;
+
+use super::{dijkstra::determine_path, helper::{check_start_edges, get_start, search_preamble}, GraphError, GraphPath, PathWeight, VisitedNodes};
+
+
+/// Reusable buffers for running many searches, avoiding the allocations made by each call of the free functions
+/// 
+/// The buffers are cleared, rather than dropped, at the start of each search, so once they have grown to fit the graph no further allocation is needed.
+/// The results are identical to those of the corresponding free functions. Keep one per system, eg in a [`Local`](bevy::prelude::Local), when
+/// searching for many agents each frame.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //A system that paths every agent to its target, reusing the same buffers for each search
+/// fn path_agents(
+///     mut searcher: Local<Searcher>,
+///     mut agents: Query<(&CurrentTile, &Target, &mut AgentPath)>,
+///     tiles: Query<&VertexType>
+/// ) {
+///     for (tile, target, mut agent_path) in agents.iter_mut() {
+///         agent_path.0 = searcher.dijkstra_search(&tiles, tile.0, target.0).ok();
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct Searcher {
+    visited: Option<VisitedNodes>,
+    breadth_queue: VecDeque<Entity>,
+    path_previous: HashMap<Entity, Option<Entity>>,
+    minimal_dist: HashMap<Entity, PathWeight>,
+    priority_queue: PriorityQueue<Entity, Reverse<(PathWeight, u32)>>
+}
+
+impl Searcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs [`bfs`](super::bfs::bfs) using this searcher's buffers
+    /// 
+    /// # Errors
+    /// 
+    /// See [`bfs`](super::bfs::bfs)
+    pub fn bfs<V: GraphVertex>(
+        &mut self,
+        query: &Query<&V>,
+        start_ent: Entity,
+        end_ent: Entity
+    ) -> Result<GraphPath<()>, GraphError> {
+        if let Some(path) = search_preamble(query, start_ent, end_ent, ())? {return Ok(path);}
+        check_start_edges(get_start(query, start_ent)?)?;
+
+        self.breadth_queue.clear();
+        self.breadth_queue.push_back(start_ent);
+        let visited = match &mut self.visited {
+            Some(visited) => {visited.reset_to_start(start_ent); visited},
+            None => self.visited.insert(VisitedNodes::new_from_start(start_ent))
+        };
+
+        //loop while still vertices to check
+        while let Some(sv_ent) = self.breadth_queue.pop_front() {
+            let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+
+            for neighbour_ent in sv_vert.get_neighbours(){
+                if visited.is_visited(&neighbour_ent) {continue;}
+                visited.insert(neighbour_ent, sv_ent, 0, 0.0);
+
+                if neighbour_ent == end_ent {return Ok(visited.determine_path(neighbour_ent).expect("The created path should be valid"));}
+
+                self.breadth_queue.push_back(neighbour_ent);
+            }
+        }
+        //if we get to this point, then we must have found no path
+        Err(GraphError::NoPath)
+    }
+
+    /// Runs [`dijkstra_search`](super::dijkstra::dijkstra_search) using this searcher's buffers
+    /// 
+    /// # Errors
+    /// 
+    /// See [`dijkstra_search`](super::dijkstra::dijkstra_search)
+    pub fn dijkstra_search<V: GraphVertex>(
+        &mut self,
+        query: &Query<&V>,
+        start_ent: Entity,
+        end_ent: Entity
+    ) -> Result<GraphPath<f32>, GraphError> {
+        if let Some(path) = search_preamble(query, start_ent, end_ent, 0.0)? {return Ok(path);}
+        check_start_edges(get_start(query, start_ent)?)?;
+
+        self.path_previous.clear();
+        self.path_previous.insert(start_ent, None);
+        self.minimal_dist.clear();
+        self.minimal_dist.insert(start_ent, PathWeight{weight: 0.0});
+        self.priority_queue.clear();
+        self.priority_queue.push(start_ent, Reverse((PathWeight{weight: 0.0}, start_ent.index())));
+
+        while let Some((sv_ent, Reverse((sv_dist, _)))) = self.priority_queue.pop() {
+            //check if we are currently searching the end vertex, as this implies we have already found the minimum path
+            if sv_ent == end_ent {
+                return Ok(determine_path(&self.path_previous, &self.minimal_dist, sv_ent).expect("The created path should be valid"));
+            }
+
+            let Ok(sv_vert) = query.get(sv_ent) else {continue;};
+
+            for (neighbour_ent, edge_weight) in sv_vert.get_neighbours_with_weight(){
+                if edge_weight < 0.0 {return Err(GraphError::NegativeWeight);}
+
+                //Determine the distance to this neighbour via the path to the search vertex
+                let total_dist = sv_dist + edge_weight;
+
+                //keep the first path found when later paths are no shorter, matching dijkstra_search's tie breaking
+                if let Some(dist) = self.minimal_dist.get_mut(&neighbour_ent) {
+                    if total_dist >= *dist {continue;}
+                    *dist = total_dist;
+                    self.path_previous.insert(neighbour_ent, Some(sv_ent));
+                    self.priority_queue.change_priority(&neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
+                } else {
+                    self.path_previous.insert(neighbour_ent, Some(sv_ent));
+                    self.minimal_dist.insert(neighbour_ent, total_dist);
+                    self.priority_queue.push(neighbour_ent, Reverse((total_dist, neighbour_ent.index())));
+                }
+            }
+        }
+
+        //if we get to this point, then we must have found no path
+        Err(GraphError::NoPath)
+    }
+}
@@ -0,0 +1,102 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::{prelude::{Entity, Query}, utils::{HashMap, HashSet}};
+
+use crate::graph_vertex::GraphVertex;
+
+use super::{helper::{all_edges, UnionFind}, PathWeight};
+
+
+/// Returns every edge of the graph treated as undirected, sorted by ascending weight
+///
+/// Edges in each direction between two vertices are combined into one, keeping the smaller weight, and each edge is given with the lower
+/// entity first. Self-loops and edges to entities outside the query are dropped. Edges of equal weight are ordered by their entities, so the
+/// order is deterministic. This is the input consumed by Kruskal's algorithm, see [`kruskal_mst`].
+pub fn sorted_edges<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<(Entity, Entity, f32)> {
+    let mut edges = undirected_edges(query).into_iter().map(|((a, b), weight)| (a, b, weight)).collect::<Vec<_>>();
+    edges.sort_by(|(a1, b1, weight1), (a2, b2, weight2)| weight1.total_cmp(weight2).then(a1.cmp(a2)).then(b1.cmp(b2)));
+    edges
+}
+
+/// Finds a minimum spanning forest of the graph treated as undirected, using Kruskal's algorithm
+///
+/// Returns the edges of the forest, as given by [`sorted_edges`], in the order they were added. The forest joins every pair of vertices that
+/// are connected, ignoring edge direction, using the least total edge weight possible, and has one tree for each connected component.
+/// Kruskal's algorithm sorts every edge up front, so is best suited to sparse graphs. For dense graphs, consider [`prim_mst`].
+///
+/// # Example
+///
+/// ```ignore
+/// //A system that chooses the cheapest set of roads to pave so that every town is connected
+/// fn plan_paving(
+///     towns: Query<(Entity, &TownVertex)>,
+///     mut plan: ResMut<PavingPlan>
+/// ) {
+///     plan.roads = kruskal_mst(&towns);
+///     plan.cost = plan.roads.iter().map(|(_, _, weight)| weight).sum();
+/// }
+/// ```
+pub fn kruskal_mst<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<(Entity, Entity, f32)> {
+    let indices: HashMap<Entity, usize> = query.iter().enumerate().map(|(ind, (ent, _))| (ent, ind)).collect();
+    let mut union_find = UnionFind::new(indices.len());
+
+    //take each edge in order of weight, unless its vertices are already joined
+    sorted_edges(query).into_iter()
+    .filter(|(a, b, _)| union_find.union(indices[a], indices[b]))
+    .collect()
+}
+
+/// Finds a minimum spanning forest of the graph treated as undirected, using Prim's algorithm
+///
+/// Identical to [`kruskal_mst`] in the total weight of the forest found, though the edges may differ when several have the same weight.
+/// Each edge is given with the vertex already in the tree first, in the order the edges were added. Prim's algorithm grows each tree
+/// from a single vertex, only queueing the edges leaving it, so is best suited to dense graphs.
+pub fn prim_mst<V: GraphVertex>(
+    query: &Query<(Entity, &V)>
+) -> Vec<(Entity, Entity, f32)> {
+    let mut adjacency: HashMap<Entity, Vec<(Entity, f32)>> = HashMap::new();
+    for ((a, b), weight) in undirected_edges(query) {
+        adjacency.entry(a).or_default().push((b, weight));
+        adjacency.entry(b).or_default().push((a, weight));
+    }
+
+    let mut in_tree: HashSet<Entity> = HashSet::new();
+    let mut tree_edges: Vec<(Entity, Entity, f32)> = Vec::new();
+
+    for (root_ent, _) in query.iter() {
+        if !in_tree.insert(root_ent) {continue;}
+
+        //lazy deletion, so edges to vertices that have since joined the tree are skipped when popped
+        let mut edge_heap: BinaryHeap<Reverse<(PathWeight, Entity, Entity)>> = BinaryHeap::new();
+        let push_edges = |heap: &mut BinaryHeap<_>, from_ent: Entity| {
+            for &(to_ent, weight) in adjacency.get(&from_ent).into_iter().flatten() {
+                heap.push(Reverse((PathWeight::new(weight), from_ent, to_ent)));
+            }
+        };
+        push_edges(&mut edge_heap, root_ent);
+
+        while let Some(Reverse((weight, from_ent, to_ent))) = edge_heap.pop() {
+            if !in_tree.insert(to_ent) {continue;}
+            tree_edges.push((from_ent, to_ent, weight.value()));
+            push_edges(&mut edge_heap, to_ent);
+        }
+    }
+    tree_edges
+}
+
+
+/// Helper function combining the edges in each direction between two vertices, keeping the smaller weight, keyed with the lower entity first
+fn undirected_edges<V: GraphVertex>(query: &Query<(Entity, &V)>) -> HashMap<(Entity, Entity), f32> {
+    let mut edges: HashMap<(Entity, Entity), f32> = HashMap::new();
+    for (ent, neighbour_ent, weight) in all_edges(query) {
+        if neighbour_ent == ent || !query.contains(neighbour_ent) {continue;}
+        let key = if ent < neighbour_ent {(ent, neighbour_ent)} else {(neighbour_ent, ent)};
+        let min_weight = edges.entry(key).or_insert(weight);
+        *min_weight = min_weight.min(weight);
+    }
+    edges
+}
@@ -0,0 +1,122 @@
+use bevy::{ecs::system::SystemState, prelude::{Entity, Query, World}};
+
+use crate::graph_vertex::{GraphVertex, StandardGraphVertex};
+
+use super::{bfs, dfs, dijkstra_search, within_distance, within_steps, Distance, GraphError, GraphPath, Steps};
+
+
+//Convenience wrappers for running the searches directly on a World, for use outside of systems (eg in tests, tools or scripts).
+//Each call creates a new SystemState, so inside systems the Query based functions should be preferred.
+
+
+/// Runs [`bfs`] on every vertex of type `V` in the world, returning the path in **reverse order**
+/// 
+/// # Errors
+/// 
+/// See [`bfs`]
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// let mut world = World::new();
+/// let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+/// let path = bfs_in_world::<StandardGraphVertex>(&mut world, vertices[1], vertices[5]).unwrap();
+/// ```
+pub fn bfs_in_world<V: GraphVertex>(world: &mut World, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<()>, GraphError> {
+    let mut state: SystemState<Query<&V>> = SystemState::new(world);
+    bfs(&state.get(world), start_ent, end_ent)
+}
+
+/// Runs [`dfs`] on every vertex of type `V` in the world, returning the path in **reverse order**
+/// 
+/// # Errors
+/// 
+/// See [`dfs`]
+pub fn dfs_in_world<V: GraphVertex>(world: &mut World, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<()>, GraphError> {
+    let mut state: SystemState<Query<&V>> = SystemState::new(world);
+    dfs(&state.get(world), start_ent, end_ent)
+}
+
+/// Runs [`dijkstra_search`] on every vertex of type `V` in the world, returning the path in **reverse order**
+/// 
+/// # Errors
+/// 
+/// See [`dijkstra_search`]
+pub fn dijkstra_in_world<V: GraphVertex>(world: &mut World, start_ent: Entity, end_ent: Entity) -> Result<GraphPath<f32>, GraphError> {
+    let mut state: SystemState<Query<&V>> = SystemState::new(world);
+    dijkstra_search(&state.get(world), start_ent, end_ent)
+}
+
+/// Runs [`within_steps`] on every vertex of type `V` in the world
+/// 
+/// # Errors
+/// 
+/// See [`within_steps`]
+pub fn within_steps_in_world<V: GraphVertex>(world: &mut World, start_ent: Entity, max_steps: u64) -> Result<Vec<(Entity, Steps)>, GraphError> {
+    let mut state: SystemState<Query<&V>> = SystemState::new(world);
+    within_steps(&state.get(world), start_ent, max_steps)
+}
+
+/// Runs [`within_distance`] on every vertex of type `V` in the world
+/// 
+/// # Errors
+/// 
+/// See [`within_distance`]
+pub fn within_distance_in_world<V: GraphVertex>(world: &mut World, start_ent: Entity, max_distance: f32) -> Result<Vec<(Entity, Distance)>, GraphError> {
+    let mut state: SystemState<Query<&V>> = SystemState::new(world);
+    within_distance(&state.get(world), start_ent, max_distance)
+}
+
+
+/// Contracts the edge between two [`StandardGraphVertex`]s, merging the `remove` vertex into the `keep` vertex and despawning it
+/// 
+/// Every edge pointing at `remove` is redirected to `keep`, and every edge leaving `remove` is added to `keep`. Where this gives a vertex two 
+/// edges to the same destination, only the smaller weight is kept. Any edge that would become a self-loop on `keep`, such as the edge being 
+/// contracted, is dropped. Paths through the graph are preserved, with `keep` standing in for both vertices, which makes this the basic 
+/// operation for coarsening a graph.
+/// 
+/// # Errors
+/// 
+/// [`GraphError::InvalidEntity`]: If either entity does not have a [`StandardGraphVertex`], or they are the same entity.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// //Merge every room with the corridor leading into it
+/// for (corridor, room) in corridor_room_pairs {
+///     contract_edge(&mut world, corridor, room).unwrap();
+/// }
+/// ```
+pub fn contract_edge(world: &mut World, keep: Entity, remove: Entity) -> Result<(), GraphError> {
+    if keep == remove {return Err(GraphError::InvalidEntity(remove));}
+    let mut state: SystemState<Query<(Entity, &mut StandardGraphVertex)>> = SystemState::new(world);
+    let mut query = state.get_mut(world);
+    query.get(keep)?;
+    let removed_edges = query.get(remove)?.1.get_neighbours_with_weight();
+
+    //redirect the edges pointing at the removed vertex
+    for (ent, mut vert) in query.iter_mut() {
+        let Some((_, weight)) = vert.get_neighbours_with_weight().into_iter().find(|(neighbour_ent, _)| *neighbour_ent == remove) else {continue;};
+        vert.remove_edge(remove);
+        if ent != keep && ent != remove {add_min_edge(&mut vert, keep, weight);}
+    }
+
+    //then give the kept vertex the outgoing edges of the removed one
+    let (_, mut keep_vert) = query.get_mut(keep)?;
+    for (neighbour_ent, weight) in removed_edges {
+        if neighbour_ent == keep || neighbour_ent == remove {continue;}
+        add_min_edge(&mut keep_vert, neighbour_ent, weight);
+    }
+
+    world.despawn(remove);
+    Ok(())
+}
+
+/// Helper function adding an edge to the vertex, or lowering the weight of the existing edge if the new weight is smaller
+fn add_min_edge(vert: &mut StandardGraphVertex, neighbour_ent: Entity, weight: f32) {
+    let existing = vert.get_neighbours_with_weight().into_iter().find(|(ent, _)| *ent == neighbour_ent);
+    match existing {
+        Some((_, existing_weight)) => {vert.change_weight_of(neighbour_ent, existing_weight.min(weight));},
+        None => {vert.add_edge(neighbour_ent, weight);}
+    }
+}
@@ -1,12 +1,102 @@
 use bevy::prelude::{Component, Entity};
 
+#[cfg(feature = "derive")]
+pub use bevy_graph_derive::GraphVertex;
+
 
 pub trait GraphVertex : Component {
+    /// The weight given to every edge by the default implementation of [`GraphVertex::get_neighbours_with_weight`]
+    const DEFAULT_WEIGHT: f32 = 1.0;
+
     fn get_neighbours(&self) -> Vec<Entity>;
-    fn get_neighbours_with_weight(&self) -> Vec<(Entity, f32)>;
+
+    /// Returns each neighbour paired with the weight of the edge to it
+    /// 
+    /// By default every edge has weight [`GraphVertex::DEFAULT_WEIGHT`], so vertex types that only model connectivity need only implement 
+    /// [`GraphVertex::get_neighbours`].
+    fn get_neighbours_with_weight(&self) -> Vec<(Entity, f32)> {
+        self.get_neighbours().into_iter().map(|ent| (ent, Self::DEFAULT_WEIGHT)).collect()
+    }
+
+    /// Returns each neighbour paired with the weight given to the edge to it by the provided function, ignoring any weight stored in the vertex
+    /// 
+    /// `weight_of` is given this vertex's entity and then the neighbour's entity, so edge weights can be kept outside the vertex, eg as dynamic 
+    /// costs in a resource. This allows weighted searches on vertex types that only model connectivity.
+    fn get_neighbours_weighted_by<W: Fn(Entity, Entity) -> f32>(&self, self_ent: Entity, weight_of: W) -> Vec<(Entity, f32)> {
+        self.get_neighbours().into_iter().map(|ent| (ent, weight_of(self_ent, ent))).collect()
+    }
+
+    /// The number of outgoing edges of this vertex
+    /// 
+    /// By default this collects [`GraphVertex::get_neighbours`], so vertex types that can count their edges without allocating should override it.
+    fn neighbour_count(&self) -> usize {
+        self.get_neighbours().len()
+    }
+
+    /// The total weight of the outgoing edges of this vertex, the weighted analogue of [`GraphVertex::neighbour_count`]
+    /// 
+    /// By default this sums [`GraphVertex::get_neighbours_with_weight`], so vertex types that can sum their weights without allocating should 
+    /// override it.
+    fn weighted_out_degree(&self) -> f32 {
+        self.get_neighbours_with_weight().into_iter().map(|(_, weight)| weight).sum()
+    }
+
+    /// Whether this vertex is a sink, ie has no outgoing edges
+    /// 
+    /// Searches use this to avoid queueing vertices that can't lead anywhere.
+    fn is_sink(&self) -> bool {
+        self.neighbour_count() == 0
+    }
 }
 
-#[derive(Component)]
+/// A component that only stores the edges of a vertex, making it a [`GraphVertex`] without implementing the trait directly
+/// 
+/// Useful when connectivity is kept in a dedicated component, separate from any gameplay data. The component only needs to expose its edges' 
+/// destinations as a slice, with every edge having a weight of 1 unless [`RelationVertex::weight`] is overridden. A component implementing this
+/// must not also implement [`GraphVertex`] itself.
+/// 
+/// # Example
+/// 
+/// ```ignore
+/// #[derive(Component)]
+/// struct Exits(Vec<Entity>);
+/// 
+/// impl RelationVertex for Exits {
+///     fn targets(&self) -> &[Entity] {
+///         &self.0
+///     }
+/// }
+/// 
+/// //Exits can now be searched like any other vertex
+/// fn path_to_exit(rooms: Query<&Exits>, start: Res<StartRoom>, end: Res<EndRoom>) {
+///     let path = bfs(&rooms, start.0, end.0);
+/// }
+/// ```
+pub trait RelationVertex : Component {
+    /// The destinations of this vertex's edges
+    fn targets(&self) -> &[Entity];
+    /// The weight of the edge to the destination at the given position in [`RelationVertex::targets`]
+    fn weight(&self, _index: usize) -> f32 {
+        1.0
+    }
+}
+
+impl<R: RelationVertex> GraphVertex for R {
+    fn get_neighbours(&self) -> Vec<Entity> {
+        self.targets().to_vec()
+    }
+    fn get_neighbours_with_weight(&self) -> Vec<(Entity, f32)> {
+        self.targets().iter().enumerate().map(|(index, ent)| (*ent, self.weight(index))).collect()
+    }
+    fn neighbour_count(&self) -> usize {
+        self.targets().len()
+    }
+    fn weighted_out_degree(&self) -> f32 {
+        (0..self.targets().len()).map(|index| self.weight(index)).sum()
+    }
+}
+
+#[derive(Component, Default)]
 pub struct StandardGraphVertex {
     neighbours: Vec<(Entity, f32)>
 }
@@ -41,6 +131,20 @@ impl StandardGraphVertex{
         .map(|pos| self.neighbours[pos].1 = new_weight)
         .is_some()
     }
+    /// Collapses any repeated edges to the same vertex into one, keeping the smallest weight
+    /// 
+    /// [`StandardGraphVertex::add_edge`] never adds a repeated edge, but [`StandardGraphVertex::new_with_edges`] takes the edges as given. Each
+    /// remaining edge keeps the position of the first edge to its vertex.
+    pub fn dedup_edges(&mut self){
+        let mut deduped: Vec<(Entity, f32)> = Vec::with_capacity(self.neighbours.len());
+        for &(other_vertex, weight) in self.neighbours.iter() {
+            match deduped.iter_mut().find(|(ent, _)| *ent == other_vertex) {
+                Some((_, existing_weight)) => *existing_weight = existing_weight.min(weight),
+                None => deduped.push((other_vertex, weight))
+            }
+        }
+        self.neighbours = deduped;
+    }
 }
 
 impl GraphVertex for StandardGraphVertex {
@@ -50,5 +154,11 @@ impl GraphVertex for StandardGraphVertex {
     fn get_neighbours_with_weight(&self) -> Vec<(Entity, f32)> {
         self.neighbours.clone()
     }
+    fn neighbour_count(&self) -> usize {
+        self.neighbours.len()
+    }
+    fn weighted_out_degree(&self) -> f32 {
+        self.neighbours.iter().map(|(_, weight)| weight).sum()
+    }
 }
 
@@ -8,22 +8,14 @@
 //! 
 
 
+//allows the derive macros to refer to this crate by name, including from within it
+extern crate self as bevy_graph;
+
 pub mod graph_functions;
 mod types;
-mod graph_vertex;
+pub mod graph_vertex;
 
 #[cfg(test)]
 mod tests;
 
 pub use types::*; 
-
-fn main(){
-
-
-}
-
-
-
-
-
-
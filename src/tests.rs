@@ -1,5 +1,9 @@
 
+use std::sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex};
+
 use bevy::ecs::{
+    component::Component,
+    query::{With, Without},
     world::World, 
     entity::Entity, 
     system::{
@@ -7,9 +11,11 @@ use bevy::ecs::{
         Query
     }
 };
+use bevy::{log::tracing_subscriber, utils::{tracing, HashMap, HashSet}};
 
 use crate::{
-    graph_functions::{helper::load_graph, GraphFunctionExt}, graph_vertex::StandardGraphVertex, GraphLabel
+    GraphError,
+    graph_functions::{all_edges, dag::{dag_path, longest_path_dag, topological_sort, transitive_reduction, PathObjective}, dfs::{dfs, dfs_best_effort, DfsProgress, DfsState, dfs_budgeted, dfs_ordered, dfs_path_only, EdgeOrder, dfs_preorder, reachable_count, reachable_with_max_edge}, flow::{edge_disjoint_path_set, edge_disjoint_paths, min_cost_flow}, find_all_by_label, find_by_label, load_graph_with_policy, reverse_adjacency, spawn_graph, wire_edges_by_label, DuplicateEdgePolicy, all_pairs::{floyd_warshall_with_next, reconstruct_apsp}, analysis::{bridges, closeness_centrality, component_sizes, condensation, connected_components, dead_ends, diff_graphs, duplicate_edges, evaluate_path, graph_stats, k_core, recompute_component_after_removal, sinks, smooth_path, sources, verify_path, GraphStats}, bfs::{bfs, bfs_all_paths, bfs_budgeted, bfs_by, bfs_computed_end, bfs_computed_end_strict, bfs_prepared, dfs_multiple_end, bfs_spanning_tree, bfs_tree, bfs_strict, bfs_with_filter, bfs_within_set, bfs_without}, dijkstra::{batch_distances, dijkstra_with_relax_hook, bottleneck_path, bottleneck_tree, dijkstra_search_budgeted, k_shortest_paths, k_shortest_within, dijkstra_computed_end, dijkstra_search, dijkstra_tree, meeting_point, shortest_path_vertices, dijkstra_search_by, dijkstra_search_heap, dijkstra_search_prepared, dijkstra_search_strict, dijkstra_search_with_tolerance, dijkstra_search_with_weights}, astar::{a_star_bounded, a_star_nearest_goal, bidirectional_a_star, greedy_best_first, a_star_search_budgeted, a_star_search_with_tie_break, a_star_search_with_tolerance, AStarIter, TieBreak, a_star_search, a_star_search_weighted_heuristic, a_star_search_with_weights}, helper::load_graph, neighbourhood::{at_step, neighbours_with, reachable_within, weighted_random_neighbour, within_distance, within_distance_lenient, DijkstraState, within_distance_capped, within_distance_directed, within_distance_excluding_start, within_distance_transformed, within_steps, within_steps_capped, within_steps_directed, within_steps_excluding_start}, world::{bfs_in_world, contract_edge}, path_cache::{cached_dijkstra, PathCache}, searcher::Searcher, view::{snapshot_graph, GraphView}, metric::{Unweighted, Weighted}, spanning::{kruskal_mst, prim_mst, sorted_edges}, implicit::{implicit_a_star, implicit_bfs, implicit_dijkstra, ImplicitGraph}, GraphFunctionExt}, graph_vertex::{GraphVertex, RelationVertex, StandardGraphVertex}, Direction, Distance, GraphLabel, GraphPath, Heuristic, LogOnErr, Steps, VisitedNodes
 };
 
 
@@ -94,12 +100,2606 @@ fn dijkstra_search_test() {
 }
 
 
+#[test]
+fn cached_dijkstra_test() {
+    //load the test graph
+    let mut world = World::new();
+    load_graph(&mut world, "./assets/test_graph.graph");
 
+    let entity_start = get_entity_with_label(&mut world, 1).expect("The given label should exist");
+    let entity_end = get_entity_with_label(&mut world, 5).expect("The given label should exist");
 
-/// Helper function that returns the Entity with corresponding GraphLabel value
-fn get_entity_with_label(mut world: &mut World, label: usize) -> Option<Entity> {
-    let mut label_sys_state: SystemState<Query<(Entity, &GraphLabel)>> = SystemState::new(&mut world);
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let mut cache = PathCache::new();
+
+    //the first search should fill the cache
+    let vert_query = vertex_sys_state.get(&world);
+    cached_dijkstra(&vert_query, &mut cache, entity_start, entity_end).expect("Test graph should have a valid path between the test vertices");
+    assert_eq!(cache.len(), 1);
+
+    //remove the only edge out of the start vertex without bumping the generation
+    let entity_next = get_entity_with_label(&mut world, 2).expect("The given label should exist");
+    world.get_mut::<StandardGraphVertex>(entity_start).unwrap().remove_edge(entity_next);
+
+    //the cached path is still returned, proving the graph wasn't explored again
+    let vert_query = vertex_sys_state.get(&world);
+    assert!(cached_dijkstra(&vert_query, &mut cache, entity_start, entity_end).is_ok());
+
+    //once the generation is bumped, the search is rerun on the modified graph
+    cache.bump_generation();
+    assert!(cached_dijkstra(&vert_query, &mut cache, entity_start, entity_end).is_err());
+    assert!(cache.is_empty());
+}
+
+
+#[test]
+fn weighted_a_star_test() {
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 20, 20);
+    let (start, end) = (grid[0], grid[20 * 20 - 1]);
+
+    let mut sys_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //run regular A* and count the number of expanded vertices
+    let optimal = a_star_search(&query, start, end, euclidean_heuristic).expect("The grid should be connected");
+    let optimal_expansions = take_expansions(&query);
+
+    //with epsilon 1 the search should be identical
+    let unweighted = a_star_search_weighted_heuristic(&query, start, end, euclidean_heuristic, 1.0).expect("The grid should be connected");
+    assert_eq!(unweighted.total_weight(), optimal.total_weight());
+    assert_eq!(take_expansions(&query), optimal_expansions);
+
+    //with a larger epsilon fewer vertices are expanded, but the path is still within the epsilon bound
+    let weighted = a_star_search_weighted_heuristic(&query, start, end, euclidean_heuristic, 3.0).expect("The grid should be connected");
+    assert!(take_expansions(&query) < optimal_expansions);
+    assert!(weighted.total_weight() <= 3.0 * optimal.total_weight());
+}
+
+
+#[test]
+fn sources_and_sinks_test() {
+    //a single source 0, branching to the sinks 3 and 4
+    let mut world = World::new();
+    let ents = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (0, 2, 1.0), (1, 2, 1.0), (1, 3, 1.0), (2, 4, 1.0)]);
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    assert_eq!(sources(&query), vec![ents[0]]);
+    let mut found_sinks = sinks(&query);
+    found_sinks.sort();
+    assert_eq!(found_sinks, vec![ents[3], ents[4]]);
+}
+
+
+#[test]
+fn directed_neighbourhood_test() {
+    //0 -> 1 -> 3 and 2 -> 0, so 2 is only reachable from 0 by following edges backwards
+    let mut world = World::new();
+    let ents = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 3, 1.0), (2, 0, 1.0)]);
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let sorted_ents = |mut found: Vec<Entity>| {found.sort(); found};
+
+    let outgoing = within_steps_directed(&query, ents[0], 1, Direction::Outgoing).unwrap();
+    assert_eq!(sorted_ents(outgoing.into_iter().map(|(ent, _)| ent).collect()), sorted_ents(vec![ents[0], ents[1]]));
+
+    let incoming = within_steps_directed(&query, ents[0], 1, Direction::Incoming).unwrap();
+    assert_eq!(sorted_ents(incoming.into_iter().map(|(ent, _)| ent).collect()), sorted_ents(vec![ents[0], ents[2]]));
+
+    let both = within_steps_directed(&query, ents[0], 1, Direction::Both).unwrap();
+    assert_eq!(sorted_ents(both.into_iter().map(|(ent, _)| ent).collect()), sorted_ents(vec![ents[0], ents[1], ents[2]]));
+
+    let both_dist = within_distance_directed(&query, ents[0], 2.5, Direction::Both).unwrap();
+    assert_eq!(sorted_ents(both_dist.into_iter().map(|(ent, _)| ent).collect()), sorted_ents(ents.clone()));
+}
+
+
+#[test]
+fn prepared_component_check_test() {
+    //two components, 0 <-> 1 and 2 -> 3
+    let mut world = World::new();
+    let ents = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 0, 1.0), (2, 3, 1.0)]);
+
+    let mut labelled_sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let labelled_query = labelled_sys_state.get(&world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let components = connected_components(&labelled_query);
+    assert_eq!(components[&ents[0]], components[&ents[1]]);
+    assert_eq!(components[&ents[2]], components[&ents[3]]);
+    assert_ne!(components[&ents[0]], components[&ents[2]]);
+
+    assert!(bfs_prepared(&vert_query, ents[0], ents[1], &components).is_ok());
+    assert!(matches!(bfs_prepared(&vert_query, ents[0], ents[3], &components), Err(GraphError::NoPath)));
+    assert!(matches!(dijkstra_search_prepared(&vert_query, ents[0], ents[3], &components), Err(GraphError::NoPath)));
+
+    //the same graph with counting vertices, to check the searches give up without fetching the neighbours of any vertex
+    let mut world = World::new();
+    let ents: Vec<Entity> = (0..4).map(|_| world.spawn_empty().id()).collect();
+    let edges = [vec![(ents[1], 1.0)], vec![(ents[0], 1.0)], vec![(ents[3], 1.0)], vec![]];
+    for (&ent, neighbours) in ents.iter().zip(edges) {
+        world.entity_mut(ent).insert((CountingVertex{neighbours, expansions: AtomicUsize::new(0)}, GridPosition(0, 0)));
+    }
+    let components: HashMap<Entity, usize> = ents.iter().enumerate().map(|(i, &ent)| (ent, i / 2)).collect();
+
+    let mut counting_sys_state: SystemState<Query<&CountingVertex>> = SystemState::new(&mut world);
+    let mut expansion_sys_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let counting_query = counting_sys_state.get(&world);
+    let expansion_query = expansion_sys_state.get(&world);
+
+    assert!(matches!(bfs_prepared(&counting_query, ents[0], ents[3], &components), Err(GraphError::NoPath)));
+    assert_eq!(take_expansions(&expansion_query), 0);
+    assert!(matches!(dijkstra_search_prepared(&counting_query, ents[0], ents[3], &components), Err(GraphError::NoPath)));
+    assert_eq!(take_expansions(&expansion_query), 0);
+    //within a component the search runs as usual
+    assert!(dijkstra_search_prepared(&counting_query, ents[0], ents[1], &components).is_ok());
+    assert!(take_expansions(&expansion_query) > 0);
+}
+
+
+#[test]
+fn graph_stats_test() {
+    //load the test graph
+    let mut world = World::new();
+    load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //loading stops at the first edge on a line with an "N" weight, so vertices 4, 11 and 12 lose some edges
+    let stats = graph_stats(&query);
+    assert_eq!(stats, GraphStats{vertex_count: 21, edge_count: 26, max_out_degree: 3, has_negative_weight: false, has_self_loop: false});
+    assert_eq!(stats.to_string(), "21 vertices, 26 edges (max out-degree 3)");
+}
+
+
+#[test]
+fn floyd_warshall_reconstruction_test() {
+    //load the test graph
+    let mut world = World::new();
+    load_graph(&mut world, "./assets/test_graph.graph");
+
+    let entity_start = get_entity_with_label(&mut world, 1).expect("The given label should exist");
+    let entity_end = get_entity_with_label(&mut world, 5).expect("The given label should exist");
+
+    let mut vertex_sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let mut label_sys_state: SystemState<Query<&GraphLabel>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
     let label_query = label_sys_state.get(&world);
-    label_query.iter().find_map(|(ent, lab)| if lab.value == label {Some(ent)} else {None})
+
+    let (distances, next) = floyd_warshall_with_next(&vert_query).expect("The test graph has no negative cycles");
+    let path = reconstruct_apsp(&distances, &next, entity_start, entity_end).expect("Test graph should have a valid path between the test vertices");
+
+    //the reconstructed path should be the same as the one found by Dijkstra's algorithm, with a matching total cost
+    assert_eq!(path.total_weight(), distances[&(entity_start, entity_end)]);
+    let labels: Vec<usize> = path.into_iter()
+    .filter_map(|ent| label_query.get(ent).ok().map(|b| b.value)).collect();
+    assert_eq!(labels, vec![5, 10, 15, 14, 19, 13, 12, 7, 2, 1]);
+
+    //vertex 0 has no edges, so no path can be reconstructed from it
+    let entity_isolated = get_entity_with_label(&mut world, 0).expect("The given label should exist");
+    assert!(reconstruct_apsp(&distances, &next, entity_isolated, entity_end).is_none());
+}
+
+
+#[cfg(feature = "derive")]
+#[test]
+fn derived_graph_vertex_test() {
+    #[derive(Component, crate::graph_vertex::GraphVertex)]
+    struct WeightedRoom {
+        #[allow(dead_code)]
+        name: &'static str,
+        #[graph_edges]
+        doors: Vec<(Entity, f32)>
+    }
+
+    #[derive(Component, crate::graph_vertex::GraphVertex)]
+    struct Corridor(#[graph_edges] Vec<Entity>);
+
+    let mut world = World::new();
+    let a = world.spawn_empty().id();
+    let b = world.spawn_empty().id();
+    let c = world.spawn_empty().id();
+    world.entity_mut(a).insert(WeightedRoom{name: "a", doors: vec![(b, 2.0)]});
+    world.entity_mut(b).insert(WeightedRoom{name: "b", doors: vec![(c, 3.0)]});
+    world.entity_mut(c).insert(WeightedRoom{name: "c", doors: vec![]});
+
+    //the derived methods should expose the marked field, defaulting the weight of unweighted edges to 1
+    assert_eq!(world.get::<WeightedRoom>(a).unwrap().get_neighbours_with_weight(), vec![(b, 2.0)]);
+    let corridor = Corridor(vec![a, b]);
+    assert_eq!(corridor.get_neighbours(), vec![a, b]);
+    assert_eq!(corridor.get_neighbours_with_weight(), vec![(a, 1.0), (b, 1.0)]);
+
+    let mut sys_state: SystemState<Query<&WeightedRoom>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let path = bfs(&query, a, c).expect("The rooms should be connected");
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![c, b, a]);
+}
+
+
+#[test]
+fn bfs_in_world_test() {
+    //load the test graph
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    //no system state is needed to run the search
+    let result = bfs_in_world::<StandardGraphVertex>(&mut world, vertices[1], vertices[5]).expect("Test graph should have a valid path between the test vertices");
+    let labels: Vec<usize> = result.into_iter()
+    .filter_map(|ent| world.get::<GraphLabel>(ent).map(|b| b.value)).collect();
+
+    assert_eq!(labels, vec![5, 10, 15, 14, 19, 13, 12, 3, 2, 1]);
+}
+
+
+#[test]
+fn trivial_search_cases_test() {
+    #[derive(Component)]
+    struct Blocked;
+
+    //load the test graph and block vertex 1
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+    world.entity_mut(vertices[1]).insert(Blocked);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex, Without<Blocked>>> = SystemState::new(&mut world);
+    let mut query = sys_state.get(&world);
+
+    //a blocked start gives a dedicated error, a blocked end is an invalid entity
+    assert!(matches!(query.bfs::<StandardGraphVertex>(vertices[1], vertices[5]), Err(GraphError::StartBlocked)));
+    assert!(matches!(query.dijkstra_search::<StandardGraphVertex>(vertices[1], vertices[5]), Err(GraphError::StartBlocked)));
+    assert!(matches!(query.bfs::<StandardGraphVertex>(vertices[5], vertices[1]), Err(GraphError::InvalidEntity(_))));
+    assert!(matches!(query.dijkstra_search::<StandardGraphVertex>(vertices[5], vertices[1]), Err(GraphError::InvalidEntity(_))));
+
+    //even when start equals end, a blocked start is an error
+    assert!(matches!(query.bfs::<StandardGraphVertex>(vertices[1], vertices[1]), Err(GraphError::StartBlocked)));
+    assert!(matches!(query.dijkstra_search::<StandardGraphVertex>(vertices[1], vertices[1]), Err(GraphError::StartBlocked)));
+
+    //otherwise start equals end gives the single vertex path
+    let bfs_path = query.bfs::<StandardGraphVertex>(vertices[5], vertices[5]).expect("A vertex always has a path to itself");
+    assert_eq!(bfs_path.into_iter().collect::<Vec<_>>(), vec![vertices[5]]);
+    let dijkstra_path = query.dijkstra_search::<StandardGraphVertex>(vertices[5], vertices[5]).expect("A vertex always has a path to itself");
+    assert_eq!(dijkstra_path.total_weight(), 0.0);
+    assert_eq!(dijkstra_path.into_iter().collect::<Vec<_>>(), vec![vertices[5]]);
+}
+
+
+#[test]
+fn duplicate_edge_policy_test() {
+    //vertex 0 lists the edge to 1 with weights 2, 5 and 1
+    let expected = [
+        (DuplicateEdgePolicy::First, 2.0),
+        (DuplicateEdgePolicy::Last, 1.0),
+        (DuplicateEdgePolicy::Min, 1.0),
+        (DuplicateEdgePolicy::Max, 5.0),
+        (DuplicateEdgePolicy::Sum, 8.0),
+    ];
+    for (policy, weight) in expected {
+        let mut world = World::new();
+        let vertices = load_graph_with_policy(&mut world, "./assets/test_duplicate_edges.graph", policy);
+        let edges = world.get::<StandardGraphVertex>(vertices[0]).unwrap().get_neighbours_with_weight();
+        assert_eq!(edges, vec![(vertices[1], weight), (vertices[2], 1.0)], "unexpected edges for {:?}", policy);
+    }
+}
+
+
+#[test]
+fn spawn_graph_test() {
+    //a square with a diagonal, 0 -> 1 -> 2 -> 3 and 0 -> 2
+    let mut world = World::new();
+    let vertices = spawn_graph(&mut world.commands(), &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (0, 2, 1.0)], 4);
+    world.flush();
+
+    assert_eq!(world.get::<GraphLabel>(vertices[3]).map(|label| label.value), Some(3));
+
+    let path = bfs_in_world::<StandardGraphVertex>(&mut world, vertices[0], vertices[3]).expect("The spawned graph should be connected");
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[3], vertices[2], vertices[0]]);
+}
+
+
+#[test]
+fn are_connected_test() {
+    //load the test graph
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let mut vert_query = vertex_sys_state.get(&world);
+
+    assert!(vert_query.are_connected::<StandardGraphVertex>(vertices[1], vertices[5]).unwrap());
+    assert!(vert_query.are_connected::<StandardGraphVertex>(vertices[1], vertices[1]).unwrap());
+    //vertex 0 has no edges, and vertex 8 has none pointing to it
+    assert!(!vert_query.are_connected::<StandardGraphVertex>(vertices[0], vertices[5]).unwrap());
+    assert!(!vert_query.are_connected::<StandardGraphVertex>(vertices[1], vertices[8]).unwrap());
+}
+
+
+#[cfg(feature = "gizmos")]
+#[test]
+fn draw_path_segments_test() {
+    use bevy::{math::Vec3, prelude::Transform};
+    use crate::{graph_functions::debug_draw::path_segments, GraphPath};
+
+    //three positioned vertices, with an unpositioned one between the last two
+    let mut world = World::new();
+    let a = world.spawn(Transform::from_xyz(0.0, 0.0, 0.0)).id();
+    let b = world.spawn(Transform::from_xyz(1.0, 0.0, 0.0)).id();
+    let hidden = world.spawn_empty().id();
+    let c = world.spawn(Transform::from_xyz(1.0, 2.0, 0.0)).id();
+
+    let mut sys_state: SystemState<Query<&Transform>> = SystemState::new(&mut world);
+    let transforms = sys_state.get(&world);
+
+    let path = GraphPath::new(vec![(a, ()), (b, ()), (hidden, ()), (c, ())]);
+    assert_eq!(path_segments(&path, &transforms), vec![
+        (Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)),
+        (Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 2.0, 0.0)),
+    ]);
+}
+
+
+#[test]
+fn capped_neighbourhood_test() {
+    //load the larger test graph
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph2.graph");
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //the capped searches give exactly the first vertices found by the uncapped ones
+    let all_steps = within_steps(&vert_query, vertices[1], 100).unwrap();
+    assert!(all_steps.len() > 10);
+    let capped_steps = within_steps_capped(&vert_query, vertices[1], 100, Some(10)).unwrap();
+    assert_eq!(capped_steps, all_steps[..10]);
+
+    let all_dist = within_distance(&vert_query, vertices[1], 100.0).unwrap();
+    assert!(all_dist.len() > 10);
+    let capped_dist = within_distance_capped(&vert_query, vertices[1], 100.0, Some(10)).unwrap();
+    assert_eq!(capped_dist, all_dist[..10]);
+
+    //the start vertex counts towards the cap
+    assert_eq!(within_steps_capped(&vert_query, vertices[1], 100, Some(1)).unwrap(), vec![(vertices[1], Steps(0))]);
+    assert_eq!(within_distance_capped(&vert_query, vertices[1], 100.0, Some(1)).unwrap(), vec![(vertices[1], Distance(0.0))]);
+}
+
+
+#[test]
+fn dijkstra_tie_break_test() {
+    //two equal cost routes from 0 to 3, via 1 or via 2, listed in either order
+    for edges in [
+        [(0, 1, 1.0), (0, 2, 1.0), (1, 3, 1.0), (2, 3, 1.0)],
+        [(0, 2, 1.0), (0, 1, 1.0), (2, 3, 1.0), (1, 3, 1.0)],
+    ] {
+        let mut world = World::new();
+        let vertices = spawn_test_graph(&mut world, 4, &edges);
+
+        let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+        let vert_query = vertex_sys_state.get(&world);
+
+        //the route via the vertex with the lower entity index is always chosen
+        for _ in 0..10 {
+            let path = dijkstra_search(&vert_query, vertices[0], vertices[3]).expect("The vertices should be connected");
+            assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[3], vertices[1], vertices[0]]);
+        }
+    }
+}
+
+
+#[test]
+fn edge_disjoint_paths_test() {
+    //two disjoint routes 0 -> 1 -> 4 and 0 -> 2 -> 3 -> 4, with a cross edge 1 -> 3 and a bottleneck 4 -> 5
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 1.0), (1, 4, 1.0), (0, 2, 1.0), (2, 3, 1.0), (3, 4, 1.0), (1, 3, 1.0), (4, 5, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    assert_eq!(edge_disjoint_paths(&vert_query, vertices[0], vertices[4]).unwrap(), 2);
+    assert_eq!(edge_disjoint_paths(&vert_query, vertices[0], vertices[5]).unwrap(), 1);
+    assert_eq!(edge_disjoint_paths(&vert_query, vertices[5], vertices[0]).unwrap(), 0);
+
+    //the returned paths should go from the source to the sink without sharing any edges
+    let paths = edge_disjoint_path_set(&vert_query, vertices[0], vertices[4]).unwrap();
+    assert_eq!(paths.len(), 2);
+    let mut used_edges = Vec::new();
+    for path in paths {
+        let ents: Vec<Entity> = path.into_iter().collect();
+        assert_eq!((ents[0], ents[ents.len() - 1]), (vertices[4], vertices[0]));
+        used_edges.extend(ents.windows(2).map(|pair| (pair[1], pair[0])));
+    }
+    let edge_count = used_edges.len();
+    used_edges.sort();
+    used_edges.dedup();
+    assert_eq!(used_edges.len(), edge_count);
+}
+
+
+#[test]
+fn dijkstra_state_expansion_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let mut state = DijkstraState::new(&vert_query, vertices[1]).unwrap();
+    state.expand_to(&vert_query, 5.0).unwrap();
+    let mut at_five = state.reached().to_vec();
+    let mut fresh_five = within_distance(&vert_query, vertices[1], 5.0).unwrap();
+    at_five.sort_by_key(|(ent, _)| *ent);
+    fresh_five.sort_by_key(|(ent, _)| *ent);
+    assert_eq!(at_five, fresh_five);
+
+    //resuming should give the same vertices and distances as a fresh search
+    state.expand_to(&vert_query, 10.0).unwrap();
+    let mut at_ten = state.reached().to_vec();
+    let mut fresh_ten = within_distance(&vert_query, vertices[1], 10.0).unwrap();
+    assert!(at_ten.len() > at_five.len());
+    at_ten.sort_by_key(|(ent, _)| *ent);
+    fresh_ten.sort_by_key(|(ent, _)| *ent);
+    assert_eq!(at_ten, fresh_ten);
+
+    //paths are only available to reached vertices
+    let path = state.path_to(vertices[13]).unwrap();
+    assert_eq!(path.entities().last(), Some(vertices[1]));
+    assert_eq!(path.total_weight(), fresh_ten.iter().find(|(ent, _)| *ent == vertices[13]).unwrap().1.into());
+    assert!(state.path_to(vertices[8]).is_none());
+}
+
+
+#[test]
+fn strict_search_test() {
+    //vertex 1 has an edge to vertex 3, which is despawned, but there is still a path 0 -> 1 -> 2
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 3, 1.0), (1, 2, 1.0)]);
+    world.despawn(vertices[3]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    assert!(bfs(&vert_query, vertices[0], vertices[2]).is_ok());
+    assert!(dijkstra_search(&vert_query, vertices[0], vertices[2]).is_ok());
+    assert_eq!(bfs_strict(&vert_query, vertices[0], vertices[2]).err(), Some(GraphError::InvalidEntity(vertices[3])));
+    assert_eq!(dijkstra_search_strict(&vert_query, vertices[0], vertices[2]).err(), Some(GraphError::InvalidEntity(vertices[3])));
+}
+
+
+#[test]
+fn split_path_test() {
+    let mut world = World::new();
+    let vertices: Vec<Entity> = (0..5).map(|_| world.spawn_empty().id()).collect();
+    //a path from vertex 0 to vertex 4 with unit edge weights, in reverse order
+    let path = GraphPath::new((0..5).rev().map(|pos| (vertices[pos], pos as f32)).collect());
+
+    let (walked, remaining) = path.split_at_vertex(vertices[2]).unwrap();
+    assert_eq!(walked.entities().collect::<Vec<_>>(), vec![vertices[2], vertices[1], vertices[0]]);
+    assert_eq!(remaining.entities().collect::<Vec<_>>(), vec![vertices[4], vertices[3], vertices[2]]);
+    assert_eq!(walked.total_weight(), 2.0);
+    assert_eq!(remaining.total_weight(), 4.0);
+
+    //splitting at either end leaves a single vertex path on one side
+    let (walked, remaining) = path.split_at_vertex(vertices[0]).unwrap();
+    assert_eq!((walked.len(), remaining.len()), (1, 5));
+
+    let unrelated = world.spawn_empty().id();
+    assert!(path.split_at_vertex(unrelated).is_none());
+}
+
+
+#[test]
+fn condensation_test() {
+    //a cycle 0 -> 1 -> 2 -> 0 and a cycle 3 <-> 4, joined by a single edge 2 -> 3
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (2, 3, 1.0), (3, 4, 1.0), (4, 3, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = vertex_sys_state.get(&world);
+
+    let (mut components, edges) = condensation(&query);
+    assert_eq!(components.len(), 2);
+    components.iter_mut().for_each(|component| component.sort());
+    assert_eq!(components[0], vec![vertices[0], vertices[1], vertices[2]]);
+    assert_eq!(components[1], vec![vertices[3], vertices[4]]);
+    assert_eq!(edges.into_iter().collect::<Vec<_>>(), vec![(0, 1)]);
+}
+
+
+#[test]
+fn step_count_limits_test() {
+    //a long chain 0 -> 1 -> ... -> 4999
+    let chain_length = 5000;
+    let edges: Vec<(usize, usize, f32)> = (1..chain_length).map(|pos| (pos - 1, pos, 1.0)).collect();
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, chain_length, &edges);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //an unbounded step count must not wrap, and reaches the end of the chain
+    let all = within_steps(&vert_query, vertices[0], u64::MAX).unwrap();
+    assert_eq!(all.len(), chain_length);
+    assert_eq!(all.last(), Some(&(vertices[chain_length - 1], Steps(chain_length as u64 - 1))));
+
+    assert_eq!(within_steps(&vert_query, vertices[0], 0).unwrap(), vec![(vertices[0], Steps(0))]);
+    assert_eq!(at_step(&vert_query, vertices[0], 4000).unwrap(), vec![vertices[4000]]);
+}
+
+
+#[test]
+fn relation_vertex_test() {
+    #[derive(Component)]
+    struct Edges(Vec<Entity>);
+
+    impl RelationVertex for Edges {
+        fn targets(&self) -> &[Entity] {
+            &self.0
+        }
+    }
+
+    //a square 0 -> 1 -> 3 and 0 -> 2 -> 3, with a long way round 0 -> 4 -> 5 -> 3
+    let mut world = World::new();
+    let vertices: Vec<Entity> = (0..6).map(|_| world.spawn_empty().id()).collect();
+    for (pos, targets) in [vec![4, 1, 2], vec![3], vec![3], vec![], vec![5], vec![3]].into_iter().enumerate() {
+        world.entity_mut(vertices[pos]).insert(Edges(targets.into_iter().map(|target| vertices[target]).collect()));
+    }
+
+    let mut edges_sys_state: SystemState<Query<&Edges>> = SystemState::new(&mut world);
+    let query = edges_sys_state.get(&world);
+
+    let path: Vec<Entity> = bfs(&query, vertices[0], vertices[3]).unwrap().into_iter().collect();
+    assert_eq!(path, vec![vertices[3], vertices[1], vertices[0]]);
+    assert_eq!(query.get(vertices[0]).unwrap().get_neighbours_with_weight()[1], (vertices[1], 1.0));
+}
+
+
+#[test]
+fn is_sink_test() {
+    let mut world = World::new();
+    let neighbour = world.spawn_empty().id();
+    let vertex = StandardGraphVertex::new_with_edges(vec![(neighbour, 1.0)]);
+    assert!(!vertex.is_sink());
+    assert_eq!(vertex.neighbour_count(), 1);
+
+    let vertex = StandardGraphVertex::new();
+    assert!(vertex.is_sink());
+    assert_eq!(vertex.neighbour_count(), 0);
+}
+
+
+#[test]
+fn weighted_out_degree_test() {
+    #[derive(Component)]
+    struct Unweighted(Vec<Entity>);
+
+    impl GraphVertex for Unweighted {
+        fn get_neighbours(&self) -> Vec<Entity> {
+            self.0.clone()
+        }
+    }
+
+    #[derive(Component)]
+    struct Roads(Vec<Entity>);
+
+    impl RelationVertex for Roads {
+        fn targets(&self) -> &[Entity] {
+            &self.0
+        }
+        fn weight(&self, index: usize) -> f32 {
+            index as f32 + 0.5
+        }
+    }
+
+    let mut world = World::new();
+    let neighbours: Vec<Entity> = (0..3).map(|_| world.spawn_empty().id()).collect();
+
+    let vertex = StandardGraphVertex::new_with_edges(vec![(neighbours[0], 1.5), (neighbours[1], 2.0), (neighbours[2], 0.25)]);
+    assert_eq!(vertex.weighted_out_degree(), 3.75);
+    assert_eq!(StandardGraphVertex::new().weighted_out_degree(), 0.0);
+
+    //the default implementation gives every edge the default weight
+    assert_eq!(Unweighted(neighbours.clone()).weighted_out_degree(), 3.0);
+    assert_eq!(Roads(neighbours).weighted_out_degree(), 0.5 + 1.5 + 2.5);
+}
+
+
+#[test]
+fn dijkstra_heap_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //both implementations should find the same path, or fail in the same way, between every pair of vertices
+    for &start in vertices.iter() {
+        for &end in vertices.iter() {
+            let queue_path = dijkstra_search(&vert_query, start, end);
+            let heap_path = dijkstra_search_heap(&vert_query, start, end);
+            match (queue_path, heap_path) {
+                (Ok(queue_path), Ok(heap_path)) => assert_eq!(queue_path.entities().collect::<Vec<_>>(), heap_path.entities().collect::<Vec<_>>()),
+                (Err(queue_err), Err(heap_err)) => assert_eq!(queue_err, heap_err),
+                _ => panic!("only one implementation found a path")
+            }
+        }
+    }
 }
 
+
+#[test]
+fn heterogeneous_search_test() {
+    #[derive(Component)]
+    struct RoadVertex(Vec<Entity>);
+    impl RelationVertex for RoadVertex {
+        fn targets(&self) -> &[Entity] {
+            &self.0
+        }
+    }
+
+    #[derive(Component)]
+    struct RailVertex(Vec<Entity>);
+    impl RelationVertex for RailVertex {
+        fn targets(&self) -> &[Entity] {
+            &self.0
+        }
+        fn weight(&self, _index: usize) -> f32 {
+            0.5
+        }
+    }
+
+    //roads 0 -> 1 -> 2, with a station at 2 taking the railway 2 -> 3 -> 4, then a road 4 -> 5
+    let mut world = World::new();
+    let vertices: Vec<Entity> = (0..6).map(|_| world.spawn_empty().id()).collect();
+    world.entity_mut(vertices[0]).insert(RoadVertex(vec![vertices[1]]));
+    world.entity_mut(vertices[1]).insert(RoadVertex(vec![vertices[2]]));
+    world.entity_mut(vertices[2]).insert(RailVertex(vec![vertices[3]]));
+    world.entity_mut(vertices[3]).insert(RailVertex(vec![vertices[4]]));
+    world.entity_mut(vertices[4]).insert(RoadVertex(vec![vertices[5]]));
+    world.entity_mut(vertices[5]).insert(RoadVertex(vec![]));
+    let unrelated = world.spawn_empty().id();
+
+    let mut sys_state: SystemState<(Query<&RoadVertex>, Query<&RailVertex>)> = SystemState::new(&mut world);
+    let (roads, rails) = sys_state.get(&world);
+    let weighted_neighbours = |ent| {
+        roads.get(ent).map(|road| road.get_neighbours_with_weight())
+        .or_else(|_| rails.get(ent).map(|rail| rail.get_neighbours_with_weight()))
+        .ok()
+    };
+
+    let expected: Vec<Entity> = vertices.iter().rev().copied().collect();
+    let bfs_path = bfs_by(vertices[0], vertices[5], |ent| weighted_neighbours(ent).map(|edges| edges.into_iter().map(|(ent, _)| ent).collect())).unwrap();
+    assert_eq!(bfs_path.into_iter().collect::<Vec<_>>(), expected);
+    let dijkstra_path = dijkstra_search_by(vertices[0], vertices[5], weighted_neighbours).unwrap();
+    assert_eq!(dijkstra_path.entities().collect::<Vec<_>>(), expected);
+
+    //neither search can start from an entity that is neither kind of vertex
+    assert!(matches!(dijkstra_search_by(unrelated, vertices[5], weighted_neighbours), Err(GraphError::StartBlocked)));
+}
+
+
+#[test]
+fn weighted_path_distances_test() {
+    //a chain 0 -> 1 -> 2 -> 3 with a tempting but longer shortcut 0 -> 3
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.5), (1, 2, 0.5), (2, 3, 2.0), (0, 3, 5.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //each vertex should carry the summed edge weights from the start to itself
+    let expected = vec![(vertices[0], 0.0), (vertices[1], 1.5), (vertices[2], 2.0), (vertices[3], 4.0)];
+    let path = dijkstra_search(&vert_query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(path.total_weight(), 4.0);
+    assert_eq!(path.into_forward(), expected);
+    let heap_path = dijkstra_search_heap(&vert_query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(heap_path.into_forward(), expected);
+
+    //the same pairings should come from the visited nodes used by A*
+    let mut visited = VisitedNodes::new_from_start(vertices[0]);
+    visited.insert(vertices[1], vertices[0], 1, 1.5);
+    visited.insert(vertices[2], vertices[1], 2, 2.0);
+    visited.insert(vertices[3], vertices[2], 3, 4.0);
+    assert_eq!(visited.determine_path_weighted(vertices[3]).unwrap().into_forward(), expected);
+}
+
+
+#[test]
+fn diff_graphs_test() {
+    let mut old_world = World::new();
+    spawn_test_graph(&mut old_world, 4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)]);
+    //the new version drops 1 -> 2, adds 3 -> 0 and reweights 2 -> 3
+    let mut new_world = World::new();
+    spawn_test_graph(&mut new_world, 4, &[(0, 1, 1.0), (2, 3, 4.0), (3, 0, 2.0)]);
+
+    let mut old_sys_state: SystemState<Query<(&GraphLabel, &StandardGraphVertex)>> = SystemState::new(&mut old_world);
+    let mut new_sys_state: SystemState<Query<(&GraphLabel, &StandardGraphVertex)>> = SystemState::new(&mut new_world);
+    let old_query = old_sys_state.get(&old_world);
+    let new_query = new_sys_state.get(&new_world);
+
+    let diff = diff_graphs(&old_query, &new_query);
+    assert_eq!(diff.added, vec![(3, 0, 2.0)]);
+    assert_eq!(diff.removed, vec![(1, 2)]);
+    assert_eq!(diff.reweighted, vec![(2, 3, 4.0)]);
+    assert!(diff_graphs(&old_query, &old_query).is_empty());
+}
+
+
+#[test]
+fn reachable_with_max_edge_test() {
+    //a light route 0 -> 1 -> 2 -> 3 with one heavy edge, and a direct route 0 -> 3 that is heavier still
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 2, 3.0), (2, 3, 1.0), (0, 3, 5.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    assert!(!reachable_with_max_edge(&vert_query, vertices[0], vertices[3], 2.0).unwrap());
+    assert!(reachable_with_max_edge(&vert_query, vertices[0], vertices[3], 3.0).unwrap());
+    assert!(reachable_with_max_edge(&vert_query, vertices[0], vertices[1], 1.0).unwrap());
+}
+
+
+#[test]
+fn reachable_count_test() {
+    //a cycle of 3 with a tail of 2 leading into it, and a separate pair
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 7, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (3, 4, 1.0), (4, 0, 1.0), (5, 6, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    assert_eq!(reachable_count(&vert_query, vertices[3]).unwrap(), 5);
+    assert_eq!(reachable_count(&vert_query, vertices[1]).unwrap(), 3);
+    assert_eq!(reachable_count(&vert_query, vertices[6]).unwrap(), 1);
+}
+
+
+#[test]
+fn bottleneck_path_test() {
+    //the shortest route 0 -> 1 -> 3 has one heavy edge, while the longer route 0 -> 2 -> 4 -> 3 has only light edges
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 3, 6.0), (0, 2, 3.0), (2, 4, 3.0), (4, 3, 2.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let shortest = dijkstra_search(&vert_query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(shortest.entities().collect::<Vec<_>>(), vec![vertices[3], vertices[1], vertices[0]]);
+
+    let (path, bottleneck) = bottleneck_path(&vert_query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[3], vertices[4], vertices[2], vertices[0]]);
+    assert_eq!(bottleneck, 3.0);
+}
+
+
+#[test]
+fn searcher_reuse_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //every search through the one searcher should match the free functions, regardless of what was searched before
+    let mut searcher = Searcher::new();
+    for &start in vertices.iter() {
+        for &end in vertices.iter() {
+            match (bfs(&vert_query, start, end), searcher.bfs(&vert_query, start, end)) {
+                (Ok(free_path), Ok(searcher_path)) => assert_eq!(free_path.into_iter().collect::<Vec<_>>(), searcher_path.into_iter().collect::<Vec<_>>()),
+                (Err(free_err), Err(searcher_err)) => assert_eq!(free_err, searcher_err),
+                _ => panic!("only one breadth-first search found a path")
+            }
+            match (dijkstra_search(&vert_query, start, end), searcher.dijkstra_search(&vert_query, start, end)) {
+                (Ok(free_path), Ok(searcher_path)) => assert_eq!(free_path.into_forward(), searcher_path.into_forward()),
+                (Err(free_err), Err(searcher_err)) => assert_eq!(free_err, searcher_err),
+                _ => panic!("only one Dijkstra search found a path")
+            }
+        }
+    }
+}
+
+
+#[test]
+fn zero_weight_neighbourhood_test() {
+    //a grid where every edge into an even column is free
+    let mut world = World::new();
+    let vertices = spawn_counting_grid(&mut world, 6, 6);
+    let mut grid_sys_state: SystemState<Query<(Entity, &mut CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let mut grid_query = grid_sys_state.get_mut(&mut world);
+    let free_columns: Vec<Entity> = grid_query.iter().filter_map(|(ent, _, pos)| if pos.0 % 2 == 0 {Some(ent)} else {None}).collect();
+    for (_, mut vert, _) in grid_query.iter_mut() {
+        vert.neighbours.iter_mut().filter(|(ent, _)| free_columns.contains(ent)).for_each(|(_, weight)| *weight = 0.0);
+    }
+
+    let mut vertex_sys_state: SystemState<Query<&CountingVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+    vert_query.iter().for_each(|vert| {vert.expansions.swap(0, Ordering::Relaxed);});
+
+    let found = within_distance(&vert_query, vertices[0], 2.0).unwrap();
+    //every vertex should be expanded at most once, and appear once
+    let expansions: usize = vert_query.iter().map(|vert| vert.expansions.swap(0, Ordering::Relaxed)).sum();
+    assert_eq!(expansions, found.len());
+    let mut found_ents: Vec<Entity> = found.iter().map(|(ent, _)| *ent).collect();
+    found_ents.sort();
+    found_ents.dedup();
+    assert_eq!(found_ents.len(), found.len());
+
+    //and the distances should be those found by Dijkstra's algorithm
+    for &(ent, dist) in found.iter() {
+        assert_eq!(dijkstra_search(&vert_query, vertices[0], ent).unwrap().total_weight(), *dist);
+    }
+    let unreached: Vec<Entity> = vertices.iter().copied().filter(|ent| !found_ents.contains(ent)).collect();
+    assert!(!unreached.is_empty());
+    for ent in unreached {
+        assert!(dijkstra_search(&vert_query, vertices[0], ent).unwrap().total_weight() > 2.0);
+    }
+}
+
+
+#[test]
+fn neighbours_with_test() {
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 2, 1.0), (0, 3, 1.0), (0, 1, 1.0), (1, 0, 1.0)]);
+    world.entity_mut(vertices[3]).remove::<GraphLabel>();
+
+    let mut sys_state: SystemState<Query<(&StandardGraphVertex, &GraphLabel)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //vertex 3 has no label, so is not in the query
+    let labels: Vec<(Entity, usize)> = neighbours_with(&query, vertices[0]).unwrap().into_iter().map(|(ent, label)| (ent, label.value)).collect();
+    assert_eq!(labels, vec![(vertices[2], 2), (vertices[1], 1)]);
+    assert!(neighbours_with(&query, vertices[2]).unwrap().is_empty());
+    assert!(matches!(neighbours_with(&query, vertices[3]), Err(GraphError::StartBlocked)));
+}
+
+
+#[test]
+fn graph_error_comparison_test() {
+    let mut world = World::new();
+    let missing = world.spawn_empty().id();
+
+    let errors = vec![GraphError::NoPath, GraphError::InvalidEntity(missing), GraphError::StartBlocked];
+    let copied = errors.clone();
+    assert_eq!(errors, copied);
+    assert_ne!(GraphError::NoPath, GraphError::NegativeWeight);
+    assert_eq!(copied[1].to_string(), format!("the entity {:?} is not a valid GraphVertex", missing));
+
+    //still usable as a boxed standard error
+    let boxed: Box<dyn std::error::Error> = Box::new(GraphError::NegativeCycle);
+    assert_eq!(boxed.to_string(), "the graph contains a cycle with negative total weight");
+}
+
+
+#[test]
+fn capped_dijkstra_computed_end_test() {
+    //the only vertex labelled 3 is at distance 6 from vertex 0
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 2.0), (1, 2, 2.0), (2, 3, 2.0)]);
+
+    let mut sys_state: SystemState<Query<(&StandardGraphVertex, &GraphLabel)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let is_target = |label: &GraphLabel| label.value == 3;
+
+    assert_eq!(dijkstra_computed_end(&query, vertices[0], is_target, Some(5.0)).err(), Some(GraphError::NoPath));
+    assert_eq!(dijkstra_computed_end(&query, vertices[0], is_target, Some(6.0)).unwrap().total_weight(), 6.0);
+    assert_eq!(dijkstra_computed_end(&query, vertices[0], is_target, None).unwrap().total_weight(), 6.0);
+}
+
+
+#[test]
+fn meeting_point_test() {
+    //0 and 1 both lead into a hub at 2, which is much closer than the far vertex 3 both can also reach
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 2, 1.0), (1, 2, 2.0), (0, 3, 0.5), (1, 3, 9.0), (2, 3, 4.0), (1, 4, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let tree = dijkstra_tree(&vert_query, vertices[1]).unwrap();
+    assert_eq!(tree.len(), 4);
+    assert_eq!(tree[&vertices[1]], (0.0, None));
+    assert_eq!(tree[&vertices[3]], (6.0, Some(vertices[2])));
+
+    assert_eq!(meeting_point(&vert_query, vertices[0], vertices[1]).unwrap(), Some((vertices[2], 3.0)));
+    //nothing can be reached from both vertex 3, a sink, and vertex 4
+    assert_eq!(meeting_point(&vert_query, vertices[3], vertices[4]).unwrap(), None);
+}
+
+
+#[test]
+fn default_weight_test() {
+    #[derive(Component)]
+    struct Corridor(Vec<Entity>);
+    impl GraphVertex for Corridor {
+        fn get_neighbours(&self) -> Vec<Entity> {
+            self.0.clone()
+        }
+    }
+
+    #[derive(Component)]
+    struct LongCorridor(Vec<Entity>);
+    impl GraphVertex for LongCorridor {
+        const DEFAULT_WEIGHT: f32 = 2.5;
+        fn get_neighbours(&self) -> Vec<Entity> {
+            self.0.clone()
+        }
+    }
+
+    //0 -> 1 -> 2 -> 3, with a shortcut 0 -> 2
+    let mut world = World::new();
+    let vertices: Vec<Entity> = (0..4).map(|_| world.spawn_empty().id()).collect();
+    for (pos, targets) in [vec![1, 2], vec![2], vec![3], vec![]].into_iter().enumerate() {
+        let targets: Vec<Entity> = targets.into_iter().map(|target| vertices[target]).collect();
+        world.entity_mut(vertices[pos]).insert((Corridor(targets.clone()), LongCorridor(targets)));
+    }
+
+    let mut sys_state: SystemState<(Query<&Corridor>, Query<&LongCorridor>)> = SystemState::new(&mut world);
+    let (corridors, long_corridors) = sys_state.get(&world);
+
+    let path = dijkstra_search(&corridors, vertices[0], vertices[3]).unwrap();
+    assert_eq!(path.into_forward(), vec![(vertices[0], 0.0), (vertices[2], 1.0), (vertices[3], 2.0)]);
+    assert_eq!(dijkstra_search(&long_corridors, vertices[0], vertices[3]).unwrap().total_weight(), 5.0);
+}
+
+
+#[test]
+fn shortest_path_vertices_test() {
+    let mut world = World::new();
+    let vertices = spawn_counting_grid(&mut world, 4, 4);
+
+    let mut sys_state: SystemState<Query<(Entity, &CountingVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //every vertex in the rectangle between the corners lies on some shortest path of a unit weight grid
+    let corridor = shortest_path_vertices(&query, vertices[1], vertices[11]).unwrap();
+    let mut expected: Vec<Entity> = [1, 2, 3, 5, 6, 7, 9, 10, 11].into_iter().map(|pos| vertices[pos]).collect();
+    let mut found: Vec<Entity> = corridor.into_iter().collect();
+    expected.sort();
+    found.sort();
+    assert_eq!(found, expected);
+
+    //a straight line has only one shortest path
+    assert_eq!(shortest_path_vertices(&query, vertices[0], vertices[3]).unwrap().len(), 4);
+}
+
+
+#[test]
+fn longest_path_dag_test() {
+    //a project where 0 is the kickoff and 5 the release, with the critical path 0 -> 2 -> 3 -> 5 taking 9 days
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 3.0), (0, 2, 2.0), (1, 3, 1.0), (2, 3, 5.0), (1, 4, 4.0), (3, 5, 2.0), (4, 5, 1.0)]);
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let order = topological_sort(&query).unwrap();
+    let position = |ent: Entity| order.iter().position(|ordered| *ordered == ent).unwrap();
+    assert!(position(vertices[0]) < position(vertices[3]) && position(vertices[2]) < position(vertices[3]) && position(vertices[4]) < position(vertices[5]));
+
+    let critical_path = longest_path_dag(&query, vertices[0], vertices[5]).unwrap();
+    assert_eq!(critical_path.into_forward(), vec![(vertices[0], 0.0), (vertices[2], 2.0), (vertices[3], 7.0), (vertices[5], 9.0)]);
+    assert_eq!(longest_path_dag(&query, vertices[4], vertices[2]).err(), Some(GraphError::NoPath));
+
+    //adding a cycle makes the longest path ill-defined
+    world.entity_mut(vertices[5]).get_mut::<StandardGraphVertex>().unwrap().add_edge(vertices[2], 1.0);
+    let query = sys_state.get(&world);
+    assert_eq!(topological_sort(&query).err(), Some(GraphError::NotAcyclic));
+    assert_eq!(longest_path_dag(&query, vertices[0], vertices[5]).err(), Some(GraphError::NotAcyclic));
+}
+
+
+#[test]
+fn label_sequence_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut sys_state: SystemState<(Query<&StandardGraphVertex>, Query<&GraphLabel>)> = SystemState::new(&mut world);
+    let mut labelled_sys_state: SystemState<Query<(Entity, &GraphLabel)>> = SystemState::new(&mut world);
+    let (vert_query, label_query) = sys_state.get(&world);
+    let labelled_query = labelled_sys_state.get(&world);
+
+    let path = bfs(&vert_query, vertices[1], vertices[13]).unwrap();
+    let labels = path.to_label_seq(&label_query);
+    assert_eq!(labels.first(), Some(&13));
+    assert_eq!(labels.last(), Some(&1));
+
+    //decoding the labels should give back the same entities
+    let decoded = GraphPath::from_label_seq(&labels, &labelled_query).unwrap();
+    assert_eq!(decoded.into_iter().collect::<Vec<_>>(), path.into_iter().collect::<Vec<_>>());
+    assert_eq!(GraphPath::from_label_seq(&[1, 100], &labelled_query).err(), Some(GraphError::UnknownLabel(100)));
+}
+
+
+#[test]
+fn lenient_neighbourhood_test() {
+    //the edge 1 -> 2 is negative and 1 -> 4 is NaN, but 3 can still be reached through 0 -> 3
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 2, -1.0), (1, 4, f32::NAN), (0, 3, 2.0), (2, 3, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    assert_eq!(within_distance(&vert_query, vertices[0], 10.0).err(), Some(GraphError::NegativeWeight));
+
+    let (mut reachable, errors) = within_distance_lenient(&vert_query, vertices[0], 10.0).unwrap();
+    reachable.sort_by_key(|(ent, _)| *ent);
+    assert_eq!(reachable, vec![(vertices[0], Distance(0.0)), (vertices[1], Distance(1.0)), (vertices[3], Distance(2.0))]);
+    assert_eq!(errors, vec![GraphError::NegativeWeight, GraphError::NonFiniteWeight]);
+}
+
+
+#[test]
+fn a_star_nearest_goal_test() {
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 10, 10);
+    //goals at (9, 0) and (2, 7), with the second nearer to the start at (0, 0)
+    let (far_goal, near_goal) = (grid[9], grid[7 * 10 + 2]);
+
+    let mut sys_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let nearest_goal_heuristic = |pos: &GridPosition| {
+        let to_far = euclidean_heuristic(pos, &GridPosition(9, 0)).value;
+        let to_near = euclidean_heuristic(pos, &GridPosition(2, 7)).value;
+        Heuristic{value: to_far.min(to_near)}
+    };
+    let path = a_star_nearest_goal(&query, grid[0], &[far_goal, near_goal], nearest_goal_heuristic).unwrap();
+    assert_eq!(path.entities().next(), Some(near_goal));
+    assert_eq!(path.total_weight(), 9.0);
+
+    assert_eq!(a_star_nearest_goal(&query, grid[0], &[], nearest_goal_heuristic).err(), Some(GraphError::NoPath));
+}
+
+
+#[test]
+fn bfs_marker_filter_test() {
+    #[derive(Component)]
+    struct Walkable;
+    #[derive(Component)]
+    struct Blocked;
+
+    //a square 0 -> 1 -> 3 and 0 -> 2 -> 3, with the route through 1 blocked
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 3, 1.0), (0, 2, 1.0), (2, 3, 1.0)]);
+    world.entity_mut(vertices[1]).insert(Blocked);
+    for &ent in [vertices[0], vertices[2], vertices[3]].iter() {
+        world.entity_mut(ent).insert(Walkable);
+    }
+
+    let mut with_state: SystemState<Query<&StandardGraphVertex, With<Walkable>>> = SystemState::new(&mut world);
+    let with_query = with_state.get(&world);
+    let path: Vec<Entity> = bfs_with_filter(&with_query, vertices[0], vertices[3]).unwrap().into_iter().collect();
+    assert_eq!(path, vec![vertices[3], vertices[2], vertices[0]]);
+    assert_eq!(bfs_with_filter(&with_query, vertices[1], vertices[3]).err(), Some(GraphError::StartBlocked));
+
+    let mut without_state: SystemState<Query<&StandardGraphVertex, Without<Blocked>>> = SystemState::new(&mut world);
+    let without_query = without_state.get(&world);
+    let path: Vec<Entity> = bfs_without(&without_query, vertices[0], vertices[3]).unwrap().into_iter().collect();
+    assert_eq!(path, vec![vertices[3], vertices[2], vertices[0]]);
+    assert_eq!(bfs_without(&without_query, vertices[0], vertices[1]).err(), Some(GraphError::InvalidEntity(vertices[1])));
+}
+
+
+#[test]
+fn a_star_iter_test() {
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 8, 8);
+    let (start, end) = (grid[0], grid[8 * 8 - 1]);
+
+    let mut sys_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let mut search = AStarIter::new(&query, start, end, euclidean_heuristic).unwrap();
+    //a few steps in, the best path is a partial one from the start
+    search.by_ref().take(3).for_each(drop);
+    assert!(search.result().is_none());
+    assert_eq!(search.current_best_path().into_forward().first().map(|&(ent, _)| ent), Some(start));
+
+    //stepping to completion gives the same path as the full search, having searched the same vertices
+    search.by_ref().for_each(drop);
+    let stepped_expansions = take_expansions(&query);
+    let stepped: Vec<Entity> = search.result().unwrap().unwrap().into_iter().collect();
+    let full: Vec<Entity> = a_star_search(&query, start, end, euclidean_heuristic).unwrap().into_iter().collect();
+    assert_eq!(stepped, full);
+    assert_eq!(stepped_expansions, take_expansions(&query));
+    assert_eq!(search.current_best_path().total_weight(), 14.0);
+    assert!(search.next().is_none());
+}
+
+
+#[test]
+fn find_by_label_test() {
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(0, 1, 1.0), (1, 2, 1.0)]);
+    //give vertex 2 the same label as vertex 1
+    world.entity_mut(vertices[2]).insert(GraphLabel{value: 1});
+
+    let mut sys_state: SystemState<Query<(Entity, &GraphLabel)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    assert_eq!(find_by_label(&query, 0), Ok(vertices[0]));
+    assert_eq!(find_by_label(&query, 5), Err(GraphError::UnknownLabel(5)));
+    assert_eq!(find_by_label(&query, 1), Err(GraphError::DuplicateLabel(1)));
+
+    let mut shared = find_all_by_label(&query, 1);
+    shared.sort();
+    let mut expected = vec![vertices[1], vertices[2]];
+    expected.sort();
+    assert_eq!(shared, expected);
+    assert!(find_all_by_label(&query, 5).is_empty());
+
+    //every label in a loaded graph is unique, so each is found
+    let mut world = World::new();
+    load_graph(&mut world, "./assets/test_graph.graph");
+    let expected = get_entity_with_label(&mut world, 7).expect("The given label should exist");
+    let mut sys_state: SystemState<Query<(Entity, &GraphLabel)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    assert_eq!(find_by_label(&query, 7), Ok(expected));
+}
+
+
+#[test]
+fn k_shortest_within_test() {
+    //three routes from 0 to 3, of weights 2, 2.5 and 5
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 3, 1.0), (0, 2, 1.0), (2, 3, 1.5), (0, 3, 5.0)]);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let all_paths = k_shortest_paths(&query, vertices[0], vertices[3], 5).unwrap();
+    assert_eq!(all_paths.iter().map(|path| path.total_weight()).collect::<Vec<_>>(), vec![2.0, 2.5, 5.0]);
+    assert_eq!(all_paths[1].clone().into_iter().collect::<Vec<_>>(), vec![vertices[3], vertices[2], vertices[0]]);
+
+    //the direct route is more than 1.5 times the shortest, so is excluded
+    let close_paths = k_shortest_within(&query, vertices[0], vertices[3], 3, 1.5).unwrap();
+    assert_eq!(close_paths.iter().map(|path| path.total_weight()).collect::<Vec<_>>(), vec![2.0, 2.5]);
+
+    //the count still limits the paths within the ratio
+    assert_eq!(k_shortest_within(&query, vertices[0], vertices[3], 1, 3.0).unwrap().len(), 1);
+}
+
+
+#[test]
+fn bfs_tree_test() {
+    //0 -> 1, 0 -> 2, 1 -> 3, 2 -> 3, 3 -> 4, with 5 unreachable
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 1.0), (0, 2, 1.0), (1, 3, 1.0), (2, 3, 1.0), (3, 4, 1.0), (5, 0, 1.0)]);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //3 is first reached from 1, as 1 is searched before 2
+    let tree = bfs_tree(&query, vertices[0]).unwrap();
+    let expected = HashMap::from([
+        (vertices[0], None),
+        (vertices[1], Some(vertices[0])),
+        (vertices[2], Some(vertices[0])),
+        (vertices[3], Some(vertices[1])),
+        (vertices[4], Some(vertices[3]))
+    ]);
+    assert_eq!(tree, expected);
+}
+
+
+#[test]
+fn weighted_random_neighbour_test() {
+    //0 has a cheap edge to 1 and an edge 4 times as expensive to 2, while 3 has no neighbours
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (0, 2, 4.0)]);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //a fixed seed xorshift generator, so the test is repeatable
+    let mut state: u32 = 0x9E37_79B9;
+    let mut rng = || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state >> 8) as f32 / (1u32 << 24) as f32
+    };
+
+    //with inverse weights, 1 should be picked around 80% of the time
+    let vert = query.get(vertices[0]).unwrap();
+    let samples = 10000;
+    let cheap_count = (0..samples)
+    .filter(|_| weighted_random_neighbour(vert, &mut rng, |weight| 1.0 / weight) == Some(vertices[1]))
+    .count();
+    let cheap_fraction = cheap_count as f32 / samples as f32;
+    assert!((cheap_fraction - 0.8).abs() < 0.02, "cheap edge picked {} of the time", cheap_fraction);
+
+    //a weight function excluding the cheap edge always picks the other
+    assert_eq!(weighted_random_neighbour(vert, &mut rng, |weight| if weight < 2.0 {0.0} else {1.0}), Some(vertices[2]));
+    assert_eq!(weighted_random_neighbour(query.get(vertices[3]).unwrap(), &mut rng, |weight| weight), None);
+}
+
+
+#[test]
+fn expansion_budget_test() {
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 30, 30);
+    let (start, end) = (grid[0], grid[30 * 30 - 1]);
+
+    let mut vert_state: SystemState<Query<&CountingVertex>> = SystemState::new(&mut world);
+    let mut astar_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let vert_query = vert_state.get(&world);
+    let astar_query = astar_state.get(&world);
+
+    //a tiny budget can't reach the far corner of the grid, and only the budgeted number of vertices are searched
+    assert_eq!(bfs_budgeted(&vert_query, start, end, Some(10)).err(), Some(GraphError::BudgetExceeded));
+    assert_eq!(take_expansions(&astar_query), 10);
+    assert_eq!(dfs_budgeted(&vert_query, start, end, Some(10)).err(), Some(GraphError::BudgetExceeded));
+    assert_eq!(take_expansions(&astar_query), 10);
+    assert_eq!(dijkstra_search_budgeted(&vert_query, start, end, Some(10)).err(), Some(GraphError::BudgetExceeded));
+    assert_eq!(take_expansions(&astar_query), 10);
+    assert_eq!(a_star_search_budgeted(&astar_query, start, end, euclidean_heuristic, Some(10)).err(), Some(GraphError::BudgetExceeded));
+    assert_eq!(take_expansions(&astar_query), 10);
+
+    //while a large enough budget, or none at all, finds the path as normal
+    assert_eq!(bfs_budgeted(&vert_query, start, end, Some(900)).unwrap().len(), 59);
+    assert_eq!(dijkstra_search_budgeted(&vert_query, start, end, None).unwrap().total_weight(), 58.0);
+    assert_eq!(a_star_search_budgeted(&astar_query, start, end, euclidean_heuristic, Some(900)).unwrap().total_weight(), 58.0);
+}
+
+
+#[test]
+fn dead_ends_test() {
+    //a corridor 0 -> 1 -> 2 to the goal, with a branch 1 -> 3 -> 4 that loops back on itself 4 -> 3 but never returns,
+    //and a vertex 5 that can reach the goal but isn't reachable from the start
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 1.0), (1, 2, 1.0), (1, 3, 1.0), (3, 4, 1.0), (4, 3, 1.0), (5, 2, 1.0)]);
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    assert_eq!(dead_ends(&query, vertices[0], vertices[2]).unwrap(), vec![vertices[3], vertices[4]]);
+    //from inside the branch, everything reachable is a dead end
+    assert_eq!(dead_ends(&query, vertices[4], vertices[2]).unwrap(), vec![vertices[4], vertices[3]]);
+    //with no way to reach the goal at all, the start is a dead end too
+    assert_eq!(dead_ends(&query, vertices[2], vertices[0]).unwrap(), vec![vertices[2]]);
+}
+
+
+#[test]
+fn implicit_graph_test() {
+    //an unbounded grid of coordinates with a wall along x = 2 from y = -2 to 5, where moving up costs double
+    struct WalledGrid;
+    impl ImplicitGraph<(i32, i32)> for WalledGrid {
+        fn neighbours(&self, (x, y): (i32, i32)) -> Vec<((i32, i32), f32)> {
+            [((x + 1, y), 1.0), ((x - 1, y), 1.0), ((x, y + 1), 2.0), ((x, y - 1), 1.0)].into_iter()
+            .filter(|((nx, ny), _)| !(*nx == 2 && (-2..=5).contains(ny)))
+            .collect()
+        }
+    }
+
+    //no entities are needed, the search runs directly on the coordinates
+    //the shortest way is around the bottom of the wall, 6 steps further than the direct route
+    let bfs_path = implicit_bfs(&WalledGrid, (0, 0), (4, 0), Some(10_000)).unwrap();
+    assert_eq!(bfs_path.len(), 4 + 2 * 3 + 1);
+    assert_eq!(bfs_path.first(), Some(&(4, 0)));
+    assert_eq!(bfs_path.last(), Some(&(0, 0)));
+
+    let dijkstra_path = implicit_dijkstra(&WalledGrid, (0, 0), (4, 0), Some(10_000)).unwrap();
+    assert_eq!(dijkstra_path.first().map(|(_, dist)| *dist), Some(4.0 + 3.0 + 6.0));
+    assert!(dijkstra_path.iter().all(|((_, y), _)| *y <= 0));
+
+    let manhattan = |&(x, y): &(i32, i32)| Heuristic{value: ((x - 4).abs() + y.abs()) as f32};
+    let a_star_path = implicit_a_star(&WalledGrid, (0, 0), (4, 0), manhattan, Some(10_000)).unwrap();
+    assert_eq!(a_star_path.first().map(|(_, dist)| *dist), Some(13.0));
+
+    //on an infinite graph, an unreachable end is only given up on by the budget
+    let closed_in = |_: (i32, i32)| Vec::new();
+    assert_eq!(implicit_bfs(&closed_in, (0, 0), (1, 0), None).err(), Some(GraphError::NoPath));
+    assert_eq!(implicit_dijkstra(&WalledGrid, (0, 0), (2, 0), Some(1000)).err(), Some(GraphError::BudgetExceeded));
+}
+
+
+#[test]
+fn graph_view_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut vert_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let mut view_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let vert_query = vert_state.get(&world);
+    let view = GraphView::new(&view_state.get(&world));
+    assert_eq!(view.vertex_count(), vertices.len());
+
+    //every pair of vertices gives the same result from the view as from the query
+    for &start in vertices.iter() {
+        for &end in vertices.iter() {
+            let view_bfs = view.bfs(start, end).map(|path| path.into_iter().collect::<Vec<_>>());
+            let query_bfs = bfs(&vert_query, start, end).map(|path| path.into_iter().collect::<Vec<_>>());
+            assert_eq!(view_bfs, query_bfs);
+
+            let view_dijkstra = view.dijkstra(start, end).map(|path| path.into_forward());
+            let query_dijkstra = dijkstra_search(&vert_query, start, end).map(|path| path.into_forward());
+            assert_eq!(view_dijkstra, query_dijkstra);
+        }
+    }
+
+    //entities outside the view are rejected as for the query
+    let outside = world.spawn_empty().id();
+    assert_eq!(view.bfs(outside, vertices[0]).err(), Some(GraphError::StartBlocked));
+    assert_eq!(view.dijkstra(vertices[0], outside).err(), Some(GraphError::InvalidEntity(outside)));
+}
+
+
+#[test]
+fn contract_edge_test() {
+    //0 -> 1 -> 2 -> 3, with 1 <-> 2 in both directions, a shortcut 0 -> 2 and an edge 4 -> 2
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 2, 1.0), (2, 1, 1.0), (2, 3, 2.0), (0, 2, 5.0), (4, 2, 1.0)]);
+
+    contract_edge(&mut world, vertices[1], vertices[2]).unwrap();
+    assert!(world.get_entity(vertices[2]).is_none());
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let edges_of = |index: usize| {
+        let mut edges = query.get(vertices[index]).unwrap().get_neighbours_with_weight();
+        edges.sort_by_key(|(ent, _)| *ent);
+        edges
+    };
+
+    //the edges into 2 now lead to 1, with the smaller weight kept, and no self-loop is left on 1
+    assert_eq!(edges_of(0), vec![(vertices[1], 1.0)]);
+    assert_eq!(edges_of(1), vec![(vertices[3], 2.0)]);
+    assert_eq!(edges_of(4), vec![(vertices[1], 1.0)]);
+
+    //everything that could reach 3 still can
+    for start in [0, 1, 4] {
+        assert!(bfs(&query, vertices[start], vertices[3]).is_ok());
+    }
+
+    assert_eq!(contract_edge(&mut world, vertices[1], vertices[2]).err(), Some(GraphError::InvalidEntity(vertices[2])));
+}
+
+
+#[test]
+fn evaluate_path_test() {
+    //a direct but expensive edge 0 -> 3, and a cheaper route 0 -> 1 -> 2 -> 3
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 3, 10.0), (0, 1, 1.0), (1, 2, 2.0), (2, 3, 3.0)]);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //the path with the fewest steps costs more than the shortest weighted path
+    let bfs_path = bfs(&query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(evaluate_path(&query, &bfs_path), Ok(10.0));
+    let dijkstra_path = dijkstra_search(&query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(evaluate_path(&query, &dijkstra_path), Ok(dijkstra_path.total_weight()));
+    assert_eq!(evaluate_path(&query, &GraphPath::single(vertices[0], ())), Ok(0.0));
+
+    //a path using an edge that doesn't exist is an error
+    let broken = GraphPath::new(vec![(vertices[0], ()), (vertices[3], ())]);
+    assert_eq!(evaluate_path(&query, &broken), Err(GraphError::NoPath));
+}
+
+
+#[test]
+fn a_star_tie_break_test() {
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 20, 20);
+    let (start, end) = (grid[0], grid[20 * 20 - 1]);
+
+    let mut sys_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //with the Manhattan distance on an open grid, every vertex between the start and end has the same priority
+    let manhattan = |a: &GridPosition, b: &GridPosition| Heuristic{value: ((a.0 - b.0).abs() + (a.1 - b.1).abs()) as f32};
+    let mut expansions = Vec::new();
+    for tie_break in [TieBreak::HigherG, TieBreak::LowerG, TieBreak::ByEntity] {
+        let path = a_star_search_with_tie_break(&query, start, end, manhattan, tie_break).unwrap();
+        assert_eq!(path.total_weight(), 38.0);
+        expansions.push(take_expansions(&query));
+    }
+    //preferring higher g heads straight for the end, while the others search the whole grid
+    assert_eq!(expansions, vec![38, 399, 399]);
+
+    //the default matches HigherG
+    a_star_search(&query, start, end, manhattan).unwrap();
+    assert_eq!(take_expansions(&query), expansions[0]);
+}
+
+
+#[test]
+fn self_loop_test() {
+    //every vertex of the path 0 -> 1 -> 2 also lists itself as a neighbour, with the self-loops listed first
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(0, 0, 0.0), (0, 1, 1.0), (1, 1, 0.0), (1, 2, 1.0), (2, 2, 0.0)]);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let expected = vec![vertices[2], vertices[1], vertices[0]];
+    assert_eq!(bfs(&query, vertices[0], vertices[2]).unwrap().into_iter().collect::<Vec<_>>(), expected);
+    assert_eq!(dfs(&query, vertices[0], vertices[2]).unwrap().into_iter().collect::<Vec<_>>(), expected);
+    let dijkstra_path = dijkstra_search(&query, vertices[0], vertices[2]).unwrap();
+    assert_eq!(dijkstra_path.total_weight(), 2.0);
+    assert_eq!(dijkstra_path.into_iter().collect::<Vec<_>>(), expected);
+
+    //a self-predecessor is refused, so the path is still found from the real previous vertex
+    let mut visited = VisitedNodes::new_from_start(vertices[0]);
+    visited.insert(vertices[1], vertices[0], 1, 1.0);
+    visited.insert(vertices[1], vertices[1], 2, 1.0);
+    visited.set_previous(vertices[1], vertices[1], 1.0);
+    assert_eq!(visited.determine_path(vertices[1]).unwrap().into_iter().collect::<Vec<_>>(), vec![vertices[1], vertices[0]]);
+    //and a vertex can't be marked visited through its own self-loop
+    visited.insert(vertices[2], vertices[2], 1, 0.0);
+    assert!(!visited.is_visited(&vertices[2]));
+}
+
+
+#[test]
+fn closeness_centrality_test() {
+    //a star with hub 0 and leaves 1 to 4, with edges both ways, and an isolated vertex 5
+    let mut world = World::new();
+    let edges: Vec<(usize, usize, f32)> = (1..5).flat_map(|leaf| [(0, leaf, 1.0), (leaf, 0, 1.0)]).collect();
+    let vertices = spawn_test_graph(&mut world, 6, &edges);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //the hub is 1 from every leaf, while each leaf is 1 from the hub and 2 from the other leaves
+    let hub = closeness_centrality(&query, vertices[0]).unwrap();
+    assert_eq!(hub, 1.0);
+    for &leaf in vertices[1..5].iter() {
+        let leaf_closeness = closeness_centrality(&query, leaf).unwrap();
+        assert_eq!(leaf_closeness, 4.0 / 7.0);
+        assert!(leaf_closeness < hub);
+    }
+    assert_eq!(closeness_centrality(&query, vertices[5]), Ok(0.0));
+}
+
+
+#[test]
+fn batch_distances_test() {
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 5, 5);
+    //a vertex outside the grid, which can't be reached
+    let lone = world.spawn(CountingVertex{neighbours: Vec::new(), expansions: AtomicUsize::new(0)}).id();
+    let outside = world.spawn_empty().id();
+
+    let mut vert_state: SystemState<Query<&CountingVertex>> = SystemState::new(&mut world);
+    let mut count_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let vert_query = vert_state.get(&world);
+    let count_query = count_state.get(&world);
+
+    //three pairs share the source at (0, 0), and two the source at (4, 4)
+    let pairs = [(grid[0], grid[24]), (grid[24], grid[0]), (grid[0], grid[6]), (grid[24], grid[20]), (grid[0], lone)];
+    let distances = batch_distances(&vert_query, &pairs).unwrap();
+    assert_eq!(distances, vec![Some(8.0), Some(8.0), Some(2.0), Some(4.0), None]);
+
+    //each of the two sources searched the 25 grid vertices once
+    assert_eq!(take_expansions(&count_query), 2 * 25);
+
+    assert_eq!(batch_distances(&vert_query, &[(lone, grid[0])]), Ok(vec![None]));
+    assert_eq!(batch_distances(&vert_query, &[(grid[0], outside)]), Err(GraphError::InvalidEntity(outside)));
+}
+
+
+#[test]
+fn dfs_best_effort_test() {
+    //a short branch 0 -> 1, a deep branch 0 -> 2 -> 3 -> 4 -> 5, and the goal 6 which nothing leads to
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 7, &[(0, 1, 1.0), (0, 2, 1.0), (2, 3, 1.0), (3, 4, 1.0), (4, 5, 1.0), (6, 0, 1.0)]);
+
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let (path, reached) = dfs_best_effort(&query, vertices[0], vertices[6]).unwrap();
+    assert!(!reached);
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[5], vertices[4], vertices[3], vertices[2], vertices[0]]);
+
+    //a reachable goal gives the same path as dfs
+    let (path, reached) = dfs_best_effort(&query, vertices[0], vertices[3]).unwrap();
+    assert!(reached);
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), dfs(&query, vertices[0], vertices[3]).unwrap().into_iter().collect::<Vec<_>>());
+}
+
+
+#[test]
+fn dag_path_test() {
+    #[derive(Component)]
+    struct TaskTime(f32);
+
+    //0 -> 1 -> 3 -> 4, 0 -> 2 -> 4 and 1 -> 2, with the edge weights all 1 but very different task times
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 3, 1.0), (3, 4, 1.0), (0, 2, 1.0), (2, 4, 1.0), (1, 2, 1.0)]);
+    for (&ent, time) in vertices.iter().zip([1.0, 2.0, 10.0, 3.0, 1.0]) {
+        world.entity_mut(ent).insert(TaskTime(time));
+    }
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex, &TaskTime)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //the quickest sequence avoids task 2, while the slowest goes through both 1 and 2
+    let quickest = dag_path(&query, vertices[0], vertices[4], PathObjective::Min, |time| time.0).unwrap();
+    assert_eq!(quickest.total_weight(), 7.0);
+    assert_eq!(quickest.into_iter().collect::<Vec<_>>(), vec![vertices[4], vertices[3], vertices[1], vertices[0]]);
+    let slowest = dag_path(&query, vertices[0], vertices[4], PathObjective::Max, |time| time.0).unwrap();
+    assert_eq!(slowest.total_weight(), 14.0);
+    assert_eq!(slowest.into_forward(), vec![(vertices[0], 1.0), (vertices[1], 3.0), (vertices[2], 13.0), (vertices[4], 14.0)]);
+
+    assert_eq!(dag_path(&query, vertices[4], vertices[0], PathObjective::Min, |time| time.0).err(), Some(GraphError::NoPath));
+}
+
+#[test]
+fn dag_path_cycle_test() {
+    #[derive(Component)]
+    struct TaskTime(f32);
+
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(0, 1, 1.0), (1, 2, 1.0), (2, 1, 1.0)]);
+    for &ent in vertices.iter() {
+        world.entity_mut(ent).insert(TaskTime(1.0));
+    }
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex, &TaskTime)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    assert_eq!(dag_path(&query, vertices[0], vertices[2], PathObjective::Max, |time| time.0).err(), Some(GraphError::NotAcyclic));
+}
+
+
+#[test]
+fn snapshot_graph_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut ent_sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let ent_query = ent_sys_state.get(&world);
+    let vert_query = vertex_sys_state.get(&world);
+    let snapshot = snapshot_graph(&ent_query);
+
+    //search the snapshot on another thread, and compare with the same searches on the live query
+    let pairs: Vec<(Entity, Entity)> = vertices.iter().flat_map(|&start| vertices.iter().map(move |&end| (start, end))).collect();
+    let thread_pairs = pairs.clone();
+    let snapshot_results = std::thread::spawn(move || {
+        thread_pairs.iter().map(|&(start, end)| (
+            snapshot.dijkstra(start, end).ok().map(|path| path.into_forward()),
+            snapshot.bfs(start, end).ok().map(|path| path.into_iter().collect::<Vec<_>>())
+        )).collect::<Vec<_>>()
+    }).join().unwrap();
+
+    for (&(start, end), (snapshot_dijkstra, snapshot_bfs)) in pairs.iter().zip(snapshot_results) {
+        assert_eq!(snapshot_dijkstra, dijkstra_search(&vert_query, start, end).ok().map(|path| path.into_forward()));
+        assert_eq!(snapshot_bfs, bfs(&vert_query, start, end).ok().map(|path| path.into_iter().collect::<Vec<_>>()));
+    }
+}
+
+
+#[test]
+fn smooth_path_test() {
+    //the cheapest path 0 -> 1 -> 2 -> 3 -> 4 has a corner at 1 -> 2 -> 3 that the edge 1 -> 3 cuts for the same cost,
+    //while the edge 0 -> 4 is too costly to use and 2 -> 4 is cheaper but isn't on the path
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (3, 4, 1.0), (1, 3, 2.0), (0, 4, 5.0), (2, 4, 0.5)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let path = GraphPath::new(vec![(vertices[4], 4.0), (vertices[3], 3.0), (vertices[2], 2.0), (vertices[1], 1.0), (vertices[0], 0.0)]);
+    let smoothed = smooth_path(&vert_query, path);
+    assert_eq!(smoothed.into_forward(), vec![(vertices[0], 0.0), (vertices[1], 1.0), (vertices[3], 3.0), (vertices[4], 4.0)]);
+
+    //a path with nothing to cut is unchanged
+    let path = dijkstra_search(&vert_query, vertices[0], vertices[4]).unwrap();
+    let expected = path.clone().into_forward();
+    assert_eq!(smooth_path(&vert_query, path).into_forward(), expected);
+}
+
+
+#[test]
+fn metric_wrapper_test() {
+    //the direct edge 0 -> 2 is the fewest steps, but going through 1 is lighter
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(0, 1, 1.0), (1, 2, 1.0), (0, 2, 5.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let weighted = Weighted::new(&vert_query);
+    let path = weighted.dijkstra_search(vertices[0], vertices[2]).unwrap();
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[2], vertices[1], vertices[0]]);
+    assert_eq!(weighted.within_distance(vertices[0], 1.5).unwrap().len(), 2);
+
+    let unweighted = Unweighted::new(&vert_query);
+    let path = unweighted.bfs(vertices[0], vertices[2]).unwrap();
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[2], vertices[0]]);
+    assert_eq!(unweighted.at_step(vertices[0], 1).unwrap().len(), 2);
+}
+
+
+#[test]
+fn accumulation_precision_test() {
+    //a chain of 1000 edges of weight 0.01 from 0 to 1, just shorter than the direct edge of weight 10 between them,
+    //but summing the chain in an f32 drifts to around 10.0001
+    let chain_length = 1000;
+    let mut edges: Vec<(usize, usize, f32)> = (0..chain_length).map(|pos| (pos + 1, pos + 2, 0.01)).collect();
+    edges[0].0 = 0;
+    edges[chain_length - 1].1 = 1;
+    edges.push((0, 1, 10.0));
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, chain_length + 1, &edges);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let path = dijkstra_search(&vert_query, vertices[0], vertices[1]).unwrap();
+    if cfg!(feature = "f64-accumulation") {
+        assert_eq!(path.len(), chain_length + 1);
+        assert!((path.total_weight() - 10.0).abs() < 1e-5);
+    } else {
+        //the f32 sum makes the direct edge look shorter
+        assert_eq!(path.len(), 2);
+    }
+}
+
+
+#[test]
+fn bridges_test() {
+    //two triangles 0, 1, 2 and 3, 4, 5 joined by the edge 2 -> 3, with a tail 5 <-> 6 given an edge in each direction
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 7, &[
+        (0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0),
+        (3, 4, 1.0), (4, 5, 1.0), (5, 3, 1.0),
+        (2, 3, 1.0), (5, 6, 1.0), (6, 5, 1.0), (6, 6, 1.0)
+    ]);
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let mut found: Vec<(Entity, Entity)> = bridges(&query).into_iter()
+    .map(|(a, b)| if a < b {(a, b)} else {(b, a)})
+    .collect();
+    found.sort();
+    assert_eq!(found, vec![(vertices[2], vertices[3]), (vertices[5], vertices[6])]);
+}
+
+
+#[test]
+fn dfs_path_only_test() {
+    //load the test graph
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //any path found must be made of edges of the graph, and start and end at the right vertices
+    for &start in vertices.iter() {
+        for &end in vertices.iter() {
+            let Ok(path) = dfs_path_only(&vert_query, start, end, vertices.len()) else {
+                assert!(dfs(&vert_query, start, end).is_err());
+                continue;
+            };
+            let path: Vec<Entity> = path.into_iter().collect();
+            assert_eq!((path.first(), path.last()), (Some(&end), Some(&start)));
+            for pair in path.windows(2) {
+                assert!(vert_query.get(pair[1]).unwrap().get_neighbours().contains(&pair[0]));
+            }
+        }
+    }
+
+    //a chain is only followed as deep as allowed
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)]);
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+    assert_eq!(dfs_path_only(&vert_query, vertices[0], vertices[3], 2).err(), Some(GraphError::NoPath));
+    assert_eq!(dfs_path_only(&vert_query, vertices[0], vertices[3], 3).unwrap().len(), 4);
+}
+
+
+#[test]
+fn minimum_spanning_tree_test() {
+    //a square 0, 1, 2, 3 with a diagonal, where 1 -> 0 is lighter than 0 -> 1, plus a separate pair 4, 5
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[
+        (0, 1, 4.0), (1, 0, 1.0), (1, 2, 2.0), (2, 3, 5.0), (3, 0, 3.0), (0, 2, 2.5), (4, 5, 7.0), (5, 5, 0.0)
+    ]);
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let edges = sorted_edges(&query);
+    assert_eq!(edges.len(), 6);
+    assert_eq!(edges[0], (vertices[0], vertices[1], 1.0));
+    assert!(edges.windows(2).all(|pair| pair[0].2 <= pair[1].2));
+
+    let total = |tree: &[(Entity, Entity, f32)]| tree.iter().map(|(_, _, weight)| weight).sum::<f32>();
+    let kruskal = kruskal_mst(&query);
+    let prim = prim_mst(&query);
+    //3 edges span the square, and 1 the pair
+    assert_eq!((kruskal.len(), prim.len()), (4, 4));
+    assert_eq!(total(&kruskal), 13.0);
+    assert_eq!(total(&prim), total(&kruskal));
+}
+
+
+#[test]
+fn path_from_entities_test() {
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)]);
+    let outsider = world.spawn_empty().id();
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let path = GraphPath::from_entities(vec![vertices[0], vertices[1], vertices[2]], &vert_query).unwrap();
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[2], vertices[1], vertices[0]]);
+
+    //1 and 3 aren't adjacent, and the edges only go one way
+    assert_eq!(GraphPath::from_entities(vec![vertices[0], vertices[1], vertices[3]], &vert_query).err(), Some(GraphError::NoPath));
+    assert_eq!(GraphPath::from_entities(vec![vertices[1], vertices[0]], &vert_query).err(), Some(GraphError::NoPath));
+    assert_eq!(GraphPath::from_entities(vec![outsider, vertices[0]], &vert_query).err(), Some(GraphError::InvalidEntity(outsider)));
+    assert_eq!(GraphPath::from_entities(Vec::new(), &vert_query).err(), Some(GraphError::NoPath));
+}
+
+
+#[test]
+fn a_star_bounded_test() {
+    #[derive(Component)]
+    struct Position(f32);
+
+    //the goal 3 is reachable directly by a heavy edge, or by a longer route through 1 and 2, with 4 a dead end off the start
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 2.0), (1, 2, 2.0), (2, 3, 2.0), (0, 3, 10.0), (0, 4, 1.0)]);
+    for (&ent, pos) in vertices.iter().zip([0.0, 2.0, 4.0, 6.0, -1.0]) {
+        world.entity_mut(ent).insert(Position(pos));
+    }
+
+    let mut sys_state: SystemState<Query<(&StandardGraphVertex, &Position)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let heuristic = |a: &Position, b: &Position| Heuristic{value: (a.0 - b.0).abs()};
+
+    let path = a_star_bounded(&query, vertices[0], vertices[3], heuristic, 6.0).unwrap();
+    assert_eq!(path.total_weight(), 6.0);
+    //every route to the goal is longer than the budget
+    assert_eq!(a_star_bounded(&query, vertices[0], vertices[3], heuristic, 5.5).err(), Some(GraphError::NoPath));
+    assert_eq!(a_star_bounded(&query, vertices[0], vertices[1], heuristic, 5.5).unwrap().total_weight(), 2.0);
+}
+
+
+#[test]
+fn all_edges_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let edges: Vec<(Entity, Entity, f32)> = all_edges(&query).collect();
+    assert_eq!(edges.len(), graph_stats(&query).edge_count);
+    //edges with an N weight, and any after them on the same line, aren't loaded
+    assert_eq!(edges.len(), 26);
+    assert!(edges.iter().all(|(from, to, _)| vertices.contains(from) && vertices.contains(to)));
+}
+
+
+#[test]
+fn component_sizes_test() {
+    //a triangle 0, 1, 2 and a chain 3 -> 4 -> 5 <- 6 <- 7, which is connected when ignoring direction
+    let mut world = World::new();
+    spawn_test_graph(&mut world, 8, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (3, 4, 1.0), (4, 5, 1.0), (6, 5, 1.0), (7, 6, 1.0)]);
+
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    assert_eq!(component_sizes(&query), vec![5, 3]);
+}
+
+
+#[test]
+fn dfs_preorder_test() {
+    //a tree with root 0, children 1 and 2, where 1 has children 3 and 4 and 2 has child 5, plus an edge back up from 5 to 1
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 1.0), (0, 2, 1.0), (1, 3, 1.0), (1, 4, 1.0), (2, 5, 1.0), (5, 1, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let order = dfs_preorder(&vert_query, vertices[0]).unwrap();
+    assert_eq!(order, [0, 1, 3, 4, 2, 5].map(|pos| vertices[pos]).to_vec());
+    assert_eq!(dfs_preorder(&vert_query, vertices[2]).unwrap(), [2, 5, 1, 3, 4].map(|pos| vertices[pos]).to_vec());
+}
+
+
+#[test]
+fn dijkstra_relax_hook_test() {
+    //2 is first found directly at distance 5, then improved to 2 through 1, and 3 is only ever found through 2
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 2, 5.0), (0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (3, 0, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let mut relaxations = Vec::new();
+    let path = dijkstra_with_relax_hook(&vert_query, vertices[0], vertices[3], |from, to, dist| relaxations.push((from, to, dist))).unwrap();
+    assert_eq!(path.total_weight(), 3.0);
+    assert_eq!(relaxations, vec![
+        (vertices[0], vertices[2], 5.0),
+        (vertices[0], vertices[1], 1.0),
+        (vertices[1], vertices[2], 2.0),
+        (vertices[2], vertices[3], 3.0)
+    ]);
+}
+
+
+#[test]
+fn bidirectional_a_star_test() {
+    //an 8x8 grid with an edge each way between neighbours, with varied weights of at least 1 so the straight line heuristic is consistent
+    let (width, height) = (8, 8);
+    let mut world = World::new();
+    let entities: Vec<Entity> = (0..width * height).map(|_| world.spawn_empty().id()).collect();
+    let weight = |a: i32, b: i32| 1.0 + ((a.min(b) * 7 + a.max(b) * 3) % 5) as f32 * 0.5;
+    for y in 0..height {
+        for x in 0..width {
+            let pos = y * width + x;
+            let neighbours = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].into_iter()
+            .filter(|&(nx, ny)| nx >= 0 && ny >= 0 && nx < width && ny < height)
+            .map(|(nx, ny)| (entities[(ny * width + nx) as usize], weight(pos, ny * width + nx)))
+            .collect();
+            world.entity_mut(entities[pos as usize]).insert((StandardGraphVertex::new_with_edges(neighbours), GridPosition(x, y)));
+        }
+    }
+
+    let mut pos_sys_state: SystemState<Query<(Entity, &StandardGraphVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let pos_query = pos_sys_state.get(&world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    for &(start, end) in &[(0, 63), (63, 0), (7, 56), (10, 45), (27, 28), (5, 5)] {
+        let path = bidirectional_a_star(&pos_query, entities[start], entities[end], euclidean_heuristic).unwrap();
+        let expected = dijkstra_search(&vert_query, entities[start], entities[end]).unwrap();
+        assert!((path.total_weight() - expected.total_weight()).abs() < 1e-4);
+        //the path must also be made of real edges, with the distances matching
+        assert!((evaluate_path(&vert_query, &path).unwrap() - expected.total_weight()).abs() < 1e-4);
+        let forward = path.into_forward();
+        assert_eq!((forward[0].0, forward[forward.len() - 1].0), (entities[start], entities[end]));
+    }
+}
+
+
+#[test]
+fn display_with_labels_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+    let unlabelled = world.spawn_empty().id();
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let mut label_sys_state: SystemState<Query<&GraphLabel>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+    let label_query = label_sys_state.get(&world);
+
+    let path = bfs(&vert_query, vertices[1], vertices[13]).unwrap();
+    assert_eq!(path.display_with_labels(&label_query).to_string(), "1 -> 2 -> 3 -> 12 -> 13");
+
+    //vertices without a label fall back to their entity index
+    let path = GraphPath::new(vec![(unlabelled, ()), (vertices[1], ())]);
+    assert_eq!(path.display_with_labels(&label_query).to_string(), format!("1 -> {}", unlabelled.index()));
+}
+
+
+#[test]
+fn greedy_best_first_test() {
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 20, 20);
+    let (start, end) = (grid[0], grid[20 * 20 - 1]);
+
+    let mut sys_state: SystemState<Query<(&CountingVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    a_star_search(&query, start, end, euclidean_heuristic).unwrap();
+    let a_star_expansions = take_expansions(&query);
+
+    //on an open grid the greedy path happens to be a shortest path, found with far fewer expansions
+    let end_pos = GridPosition(19, 19);
+    let path = greedy_best_first(&query, start, end, |pos| euclidean_heuristic(pos, &end_pos)).unwrap();
+    let greedy_expansions = take_expansions(&query);
+    assert_eq!(path.total_weight(), 38.0);
+    assert!(greedy_expansions < a_star_expansions);
+    assert_eq!(greedy_expansions, 38);
+}
+
+
+#[test]
+fn bfs_computed_end_test() {
+    //a chain 0 -> 1 -> 2 -> 3, where the only goal is three steps from the start, so is only found by searching past the start's neighbours
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)]);
+    let mut sys_state: SystemState<Query<(&StandardGraphVertex, &GraphLabel)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let path = bfs_computed_end(&query, vertices[0], |label| label.value == 3).unwrap();
+    assert_eq!(path.into_iter().collect::<Vec<_>>(), vec![vertices[3], vertices[2], vertices[1], vertices[0]]);
+    assert_eq!(bfs_computed_end(&query, vertices[0], |label| label.value == 5).err(), Some(GraphError::NoPath));
+}
+
+
+#[test]
+fn bfs_computed_end_strict_test() {
+    #[derive(Component)]
+    struct Treasure(bool);
+
+    //0 -> 1 -> 2, with the treasure at 2
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(0, 1, 1.0), (1, 2, 1.0)]);
+    for (&ent, treasure) in vertices.iter().zip([false, false, true]) {
+        world.entity_mut(ent).insert(Treasure(treasure));
+    }
+
+    let mut sys_state: SystemState<Query<(&StandardGraphVertex, &Treasure)>> = SystemState::new(&mut world);
+    let mut strict_state: SystemState<Query<(Option<&StandardGraphVertex>, Option<&Treasure>)>> = SystemState::new(&mut world);
+    {
+        let query = sys_state.get(&world);
+        let strict_query = strict_state.get(&world);
+        let expected = vec![vertices[2], vertices[1], vertices[0]];
+        assert_eq!(bfs_computed_end(&query, vertices[0], |treasure| treasure.0).unwrap().into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(bfs_computed_end_strict(&strict_query, vertices[0], |treasure| treasure.0).unwrap().into_iter().collect::<Vec<_>>(), expected);
+    }
+
+    //once the goal loses its vertex component, the lenient search skips it entirely, while the strict search reports it
+    world.entity_mut(vertices[2]).remove::<StandardGraphVertex>();
+    let query = sys_state.get(&world);
+    let strict_query = strict_state.get(&world);
+    assert_eq!(bfs_computed_end(&query, vertices[0], |treasure| treasure.0).err(), Some(GraphError::NoPath));
+    assert_eq!(bfs_computed_end_strict(&strict_query, vertices[0], |treasure| treasure.0).err(), Some(GraphError::IncompleteVertex(vertices[2])));
+    assert_eq!(bfs_computed_end_strict(&strict_query, vertices[2], |treasure| treasure.0).err(), Some(GraphError::IncompleteVertex(vertices[2])));
+}
+
+
+#[test]
+fn multiple_end_test() {
+    //a chain 0 -> 1 -> 2 -> 3, with a branch 0 -> 4, where the goals 2 and 3 are two and three steps from the start
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (0, 4, 1.0)]);
+    let mut sys_state: SystemState<Query<(&StandardGraphVertex, &GraphLabel)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let is_goal = |label: &GraphLabel| label.value == 2 || label.value == 3;
+    let routes = |paths: Vec<GraphPath<()>>| paths.into_iter().map(|path| path.into_iter().collect::<Vec<_>>()).collect::<Vec<_>>();
+
+    let paths = dfs_multiple_end(&query, vertices[0], is_goal, None, None).unwrap();
+    assert_eq!(routes(paths), vec![vec![vertices[2], vertices[1], vertices[0]], vec![vertices[3], vertices[2], vertices[1], vertices[0]]]);
+    //the goal three steps away is out of reach with two steps
+    let paths = dfs_multiple_end(&query, vertices[0], is_goal, None, Some(2)).unwrap();
+    assert_eq!(routes(paths), vec![vec![vertices[2], vertices[1], vertices[0]]]);
+    let paths = dfs_multiple_end(&query, vertices[0], is_goal, Some(1), None).unwrap();
+    assert_eq!(paths.len(), 1);
+}
+
+#[test]
+fn within_distance_transformed_test() {
+    //edges from 0 with rewards 4, 1 and 2, and a further edge from 1 with reward 5
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 4.0), (0, 2, 1.0), (0, 3, 2.0), (1, 4, 5.0)]);
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //taking the reciprocal, the most rewarding edges are the shortest, so are searched first
+    let by_reward = within_distance_transformed(&query, vertices[0], 1.0, |reward| 1.0 / reward).unwrap();
+    assert_eq!(by_reward, vec![(vertices[0], Distance(0.0)), (vertices[1], Distance(0.25)), (vertices[4], Distance(0.45)), (vertices[3], Distance(0.5)), (vertices[2], Distance(1.0))]);
+    //the identity transform is the same as within_distance
+    assert_eq!(within_distance_transformed(&query, vertices[0], 2.0, |weight| weight).unwrap(), within_distance(&query, vertices[0], 2.0).unwrap());
+
+    assert_eq!(within_distance_transformed(&query, vertices[0], 1.0, |reward| -reward).err(), Some(GraphError::NegativeWeight));
+    assert_eq!(within_distance_transformed(&query, vertices[0], 1.0, |_| f32::NAN).err(), Some(GraphError::NonFiniteWeight));
+}
+
+#[test]
+fn isolated_start_test() {
+    //0 has no outgoing edges at all, while 1 has an edge, but only towards 0
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(1, 0, 1.0)]);
+    for (x, &ent) in vertices.iter().enumerate() {
+        world.entity_mut(ent).insert(GridPosition(x as i32, 0));
+    }
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let mut pos_sys_state: SystemState<Query<(Entity, &StandardGraphVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let mut heuristic_sys_state: SystemState<Query<(&StandardGraphVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let pos_query = pos_sys_state.get(&world);
+    let heuristic_query = heuristic_sys_state.get(&world);
+    let end_pos = GridPosition(1, 0);
+
+    assert_eq!(bfs(&query, vertices[0], vertices[1]).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(dfs(&query, vertices[0], vertices[1]).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(dijkstra_search(&query, vertices[0], vertices[1]).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(bfs_within_set(&query, vertices[0], vertices[1], &vertices.iter().copied().collect()).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(dijkstra_search_with_weights(&query, vertices[0], vertices[1], |_, _| 1.0).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(bottleneck_path(&query, vertices[0], vertices[1]).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(greedy_best_first(&heuristic_query, vertices[0], vertices[1], |pos| euclidean_heuristic(pos, &end_pos)).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(a_star_nearest_goal(&heuristic_query, vertices[0], &[vertices[1]], |pos| euclidean_heuristic(pos, &end_pos)).err(), Some(GraphError::IsolatedStart));
+    assert_eq!(bidirectional_a_star(&pos_query, vertices[0], vertices[1], euclidean_heuristic).err(), Some(GraphError::IsolatedStart));
+    assert!(matches!(AStarIter::new(&heuristic_query, vertices[0], vertices[1], euclidean_heuristic), Err(GraphError::IsolatedStart)));
+    //a start with edges that can't reach the end is still just unreachable
+    assert_eq!(bfs(&query, vertices[1], vertices[2]).err(), Some(GraphError::NoPath));
+    //and a search that starts at the end succeeds as usual
+    assert_eq!(bfs(&query, vertices[0], vertices[0]).unwrap().len(), 1);
+}
+
+#[test]
+fn external_weights_test() {
+    //a vertex that only models connectivity, with no weights of its own
+    #[derive(Component)]
+    struct Connections(Vec<Entity>);
+    impl GraphVertex for Connections {
+        fn get_neighbours(&self) -> Vec<Entity> {
+            self.0.clone()
+        }
+    }
+
+    //0 -> 1 -> 3 and 0 -> 2 -> 3, which tie on unit weights but not on the external weights
+    let mut world = World::new();
+    let vertices: Vec<Entity> = (0..4).map(|x| world.spawn(GridPosition(x, 0)).id()).collect();
+    for (from, to) in [(0, vec![1, 2]), (1, vec![3]), (2, vec![3]), (3, vec![])] {
+        world.entity_mut(vertices[from]).insert(Connections(to.into_iter().map(|ind| vertices[ind]).collect()));
+    }
+    let costs: HashMap<(Entity, Entity), f32> = [((0, 1), 5.0), ((1, 3), 1.0), ((0, 2), 1.0), ((2, 3), 2.0)].into_iter()
+    .map(|((from, to), cost)| ((vertices[from], vertices[to]), cost)).collect();
+    let weight_of = |from: Entity, to: Entity| costs[&(from, to)];
+
+    let mut vert_state: SystemState<Query<&Connections>> = SystemState::new(&mut world);
+    let mut data_state: SystemState<Query<(&Connections, &GridPosition)>> = SystemState::new(&mut world);
+    let vert_query = vert_state.get(&world);
+    let data_query = data_state.get(&world);
+
+    let expected = vec![(vertices[0], 0.0), (vertices[2], 1.0), (vertices[3], 3.0)];
+    assert_eq!(dijkstra_search_with_weights(&vert_query, vertices[0], vertices[3], weight_of).unwrap().into_forward(), expected);
+    let a_star_path = a_star_search_with_weights(&data_query, vertices[0], vertices[3], |_, _| Heuristic{value: 0.0}, weight_of).unwrap();
+    assert_eq!(a_star_path.into_forward(), expected);
+
+    assert_eq!(dijkstra_search_with_weights(&vert_query, vertices[0], vertices[3], |_, _| -1.0).err(), Some(GraphError::NegativeWeight));
+}
+
+#[test]
+fn k_core_test() {
+    //a triangle 0, 1, 2 with edges in mixed directions, a tail 2 -> 3 -> 4 and a single edge 5 -> 0
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 1.0), (1, 2, 1.0), (0, 2, 1.0), (2, 0, 1.0), (2, 3, 1.0), (3, 4, 1.0), (5, 0, 1.0)]);
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    //removing 4 leaves 3 with a single neighbour, so the tail is peeled away entirely
+    assert_eq!(k_core(&query, 2), HashSet::from([vertices[0], vertices[1], vertices[2]]));
+    assert_eq!(k_core(&query, 1).len(), 6);
+    assert_eq!(k_core(&query, 0).len(), 6);
+    assert!(k_core(&query, 3).is_empty());
+}
+
+#[test]
+fn recompute_component_after_removal_test() {
+    //a triangle 0, 1, 2 joined to the pair 3, 4 by the bridge 2 -> 3, and a separate pair 5, 6
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 7, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (2, 3, 1.0), (4, 3, 1.0), (5, 6, 1.0)]);
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let mut labels = connected_components(&sys_state.get(&world));
+    let count_components = |labels: &HashMap<Entity, usize>| labels.values().collect::<HashSet<_>>().len();
+    assert_eq!(count_components(&labels), 2);
+    //the incoming edges are only built once, before any removal
+    let incoming = reverse_adjacency(&sys_state.get(&world));
+
+    //removing an edge of the triangle leaves it connected
+    world.get_mut::<StandardGraphVertex>(vertices[2]).unwrap().remove_edge(vertices[0]);
+    assert!(!recompute_component_after_removal(&sys_state.get(&world), &incoming, vertices[2], vertices[0], &mut labels).unwrap());
+    assert_eq!(count_components(&labels), 2);
+
+    //removing the bridge splits off 3 and 4, even though the removed edge is still in the incoming edges,
+    //giving the same components as recomputing from scratch
+    world.get_mut::<StandardGraphVertex>(vertices[2]).unwrap().remove_edge(vertices[3]);
+    assert!(recompute_component_after_removal(&sys_state.get(&world), &incoming, vertices[3], vertices[2], &mut labels).unwrap());
+    assert_eq!(count_components(&labels), 3);
+    assert_eq!(labels[&vertices[3]], labels[&vertices[4]]);
+    assert_ne!(labels[&vertices[3]], labels[&vertices[2]]);
+    assert_eq!(count_components(&connected_components(&sys_state.get(&world))), 3);
+}
+
+#[test]
+fn bfs_all_paths_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let all_paths = bfs_all_paths(&query, vertices[1]).unwrap();
+    assert_eq!(all_paths.len(), within_steps(&query, vertices[1], u64::MAX).unwrap().len());
+    assert_eq!(all_paths[&vertices[1]].len(), 1);
+
+    //every path matches a separate search to that vertex
+    for (&ent, path) in all_paths.iter() {
+        let single = bfs(&query, vertices[1], ent).unwrap();
+        assert_eq!(path.clone().into_iter().collect::<Vec<_>>(), single.into_iter().collect::<Vec<_>>());
+    }
+    assert_eq!(all_paths[&vertices[13]].clone().into_iter().rev().collect::<Vec<_>>(), vec![vertices[1], vertices[2], vertices[3], vertices[12], vertices[13]]);
+}
+
+#[test]
+fn excluding_start_test() {
+    //0 -> 1 -> 2, with a zero weight edge 1 -> 0 back to the start
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(0, 1, 1.0), (1, 2, 1.0), (1, 0, 0.0)]);
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    assert_eq!(within_steps(&query, vertices[0], 1).unwrap(), vec![(vertices[0], Steps(0)), (vertices[1], Steps(1))]);
+    assert_eq!(within_steps_excluding_start(&query, vertices[0], 1).unwrap(), vec![(vertices[1], Steps(1))]);
+    assert!(within_steps_excluding_start(&query, vertices[0], 0).unwrap().is_empty());
+
+    assert_eq!(within_distance(&query, vertices[0], 2.0).unwrap()[0], (vertices[0], Distance(0.0)));
+    assert_eq!(within_distance_excluding_start(&query, vertices[0], 2.0).unwrap(), vec![(vertices[1], Distance(1.0)), (vertices[2], Distance(2.0))]);
+}
+
+#[test]
+fn verify_path_test() {
+    let mut world = World::new();
+    let vertices = load_graph(&mut world, "./assets/test_graph.graph");
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let path = dijkstra_search(&query, vertices[1], vertices[13]).unwrap();
+    assert_eq!(verify_path(&query, &path, vertices[1], vertices[13]), Ok(()));
+    assert_eq!(verify_path(&query, &bfs(&query, vertices[1], vertices[13]).unwrap(), vertices[1], vertices[13]), Ok(()));
+    assert_eq!(verify_path(&query, &GraphPath::single(vertices[5], ()), vertices[5], vertices[5]), Ok(()));
+
+    //the right endpoints with a vertex missing from the middle, and a real path checked against the wrong endpoints
+    let mut skipped = path.clone().into_forward();
+    skipped.remove(2);
+    let skipped = GraphPath::new(skipped.into_iter().rev().collect());
+    assert_eq!(verify_path(&query, &skipped, vertices[1], vertices[13]), Err(GraphError::BrokenPath));
+    assert_eq!(verify_path(&query, &path, vertices[2], vertices[13]), Err(GraphError::BrokenPath));
+    assert_eq!(verify_path(&query, &path, vertices[1], vertices[12]), Err(GraphError::BrokenPath));
+}
+
+#[test]
+fn transitive_reduction_test() {
+    //0 -> 1 -> 2 -> 3, with the shortcuts 0 -> 2 and 0 -> 3 implied by the chain, a duplicate edge 1 -> 2, and a separate edge 4 -> 3
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (0, 2, 1.0), (0, 3, 1.0), (1, 2, 5.0), (4, 3, 1.0)]);
+    let mut sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let mut reduced = transitive_reduction(&query).unwrap();
+    reduced.sort();
+    let mut expected = vec![(vertices[0], vertices[1]), (vertices[1], vertices[2]), (vertices[2], vertices[3]), (vertices[4], vertices[3])];
+    expected.sort();
+    assert_eq!(reduced, expected);
+
+    //adding a back edge makes a cycle
+    world.get_mut::<StandardGraphVertex>(vertices[3]).unwrap().add_edge(vertices[1], 1.0);
+    assert_eq!(transitive_reduction(&sys_state.get(&world)).err(), Some(GraphError::NotAcyclic));
+}
+
+#[test]
+fn dfs_ordered_test() {
+    //two routes from 0 to 3, the expensive branch via 1 given first and the cheap branch via 2
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 5.0), (0, 2, 1.0), (1, 3, 5.0), (2, 3, 1.0)]);
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let entities = |path: GraphPath<()>| path.into_iter().collect::<Vec<_>>();
+    assert_eq!(entities(dfs(&query, vertices[0], vertices[3]).unwrap()), vec![vertices[3], vertices[1], vertices[0]]);
+    assert_eq!(entities(dfs_ordered(&query, vertices[0], vertices[3], EdgeOrder::CheapestFirst).unwrap()), vec![vertices[3], vertices[2], vertices[0]]);
+    assert_eq!(entities(dfs_ordered(&query, vertices[0], vertices[3], EdgeOrder::ExpensiveFirst).unwrap()), vec![vertices[3], vertices[1], vertices[0]]);
+    assert_eq!(dfs_ordered(&query, vertices[3], vertices[0], EdgeOrder::CheapestFirst).err(), Some(GraphError::IsolatedStart));
+}
+
+#[test]
+fn bfs_within_set_test() {
+    //a direct route 0 -> 1 -> 4 and a longer route 0 -> 2 -> 3 -> 4
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 5, &[(0, 1, 1.0), (1, 4, 1.0), (0, 2, 1.0), (2, 3, 1.0), (3, 4, 1.0)]);
+    let mut sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+
+    let entities = |path: GraphPath<()>| path.into_iter().collect::<Vec<_>>();
+    let everything: HashSet<Entity> = vertices.iter().copied().collect();
+    assert_eq!(entities(bfs_within_set(&query, vertices[0], vertices[4], &everything).unwrap()), vec![vertices[4], vertices[1], vertices[0]]);
+
+    //without 1 the search is forced along the longer route
+    let without_one: HashSet<Entity> = [0, 2, 3, 4].into_iter().map(|ind| vertices[ind]).collect();
+    assert_eq!(entities(bfs_within_set(&query, vertices[0], vertices[4], &without_one).unwrap()), vec![vertices[4], vertices[3], vertices[2], vertices[0]]);
+
+    //the start and end must be allowed too
+    let no_end: HashSet<Entity> = [0, 1, 2, 3].into_iter().map(|ind| vertices[ind]).collect();
+    assert_eq!(bfs_within_set(&query, vertices[0], vertices[4], &no_end).err(), Some(GraphError::InvalidEntity(vertices[4])));
+    assert_eq!(bfs_within_set(&query, vertices[0], vertices[4], &HashSet::from([vertices[4]])).err(), Some(GraphError::StartBlocked));
+}
+
+#[test]
+fn min_cost_flow_test() {
+    #[derive(Component)]
+    struct Capacities(HashMap<Entity, f32>);
+
+    //(from, to, cost, capacity), where the cheapest route 0 -> 1 -> 2 -> 3 can only carry 2 units
+    let network = [(0, 1, 1.0, 2.0), (0, 2, 4.0, 2.0), (1, 2, 1.0, 2.0), (1, 3, 5.0, 1.0), (2, 3, 1.0, 3.0)];
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &network.map(|(from, to, cost, _)| (from, to, cost)));
+    for (ind, &ent) in vertices.iter().enumerate() {
+        let capacities = network.iter().filter(|edge| edge.0 == ind).map(|&(_, to, _, capacity)| (vertices[to], capacity)).collect();
+        world.entity_mut(ent).insert(Capacities(capacities));
+    }
+    let mut sys_state: SystemState<Query<(&StandardGraphVertex, &Capacities)>> = SystemState::new(&mut world);
+    let query = sys_state.get(&world);
+    let capacity_of = |capacities: &Capacities, to: Entity| capacities.0[&to];
+
+    //2 units along the cheapest route at 3 each, then the rest along 0 -> 2 -> 3 at 5
+    assert_eq!(min_cost_flow(&query, vertices[0], vertices[3], 2.0, capacity_of), Ok(6.0));
+    assert_eq!(min_cost_flow(&query, vertices[0], vertices[3], 2.5, capacity_of), Ok(8.5));
+    assert_eq!(min_cost_flow(&query, vertices[0], vertices[3], 3.0, capacity_of), Ok(11.0));
+    //the 4th unit has to reroute a unit back along 1 -> 2, using its reverse edge at a cost of -1
+    assert_eq!(min_cost_flow(&query, vertices[0], vertices[3], 4.0, capacity_of), Ok(19.0));
+    assert_eq!(min_cost_flow(&query, vertices[0], vertices[3], 5.0, capacity_of), Err(GraphError::NoPath));
+    assert_eq!(min_cost_flow(&query, vertices[0], vertices[0], 5.0, capacity_of), Ok(0.0));
+}
+
+
+#[test]
+fn dijkstra_tolerance_test() {
+    //two routes from 0 to 3, through 1 then through 2, where the route through 2 is shorter by less than the tolerance
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 4, &[(0, 1, 1.0), (0, 2, 1.0), (1, 3, 1.0), (2, 3, 0.9999)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+    let route = |path: GraphPath<f32>| path.into_forward().into_iter().map(|(ent, _)| ent).collect::<Vec<_>>();
+
+    //without a tolerance the slightly shorter route replaces the one found first
+    let exact = dijkstra_search(&vert_query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(route(exact), vec![vertices[0], vertices[2], vertices[3]]);
+    let zero = dijkstra_search_with_tolerance(&vert_query, vertices[0], vertices[3], 0.0).unwrap();
+    assert_eq!(route(zero), vec![vertices[0], vertices[2], vertices[3]]);
+
+    //within the tolerance the routes tie, so the first path found through 1 is kept
+    let tolerant = dijkstra_search_with_tolerance(&vert_query, vertices[0], vertices[3], 1e-3).unwrap();
+    assert_eq!(tolerant.total_weight(), 2.0);
+    assert_eq!(route(tolerant), vec![vertices[0], vertices[1], vertices[3]]);
+
+    //A* resolves the near tie in the same way
+    for (x, &ent) in vertices.iter().enumerate() {
+        world.entity_mut(ent).insert(GridPosition(x as i32, 0));
+    }
+    let mut pos_sys_state: SystemState<Query<(&StandardGraphVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let pos_query = pos_sys_state.get(&world);
+    let no_heuristic = |_: &GridPosition, _: &GridPosition| Heuristic{value: 0.0};
+    let exact = a_star_search_with_tolerance(&pos_query, vertices[0], vertices[3], no_heuristic, 0.0).unwrap();
+    assert_eq!(route(exact), vec![vertices[0], vertices[2], vertices[3]]);
+    let tolerant = a_star_search_with_tolerance(&pos_query, vertices[0], vertices[3], no_heuristic, 1e-3).unwrap();
+    assert_eq!(route(tolerant), vec![vertices[0], vertices[1], vertices[3]]);
+}
+
+
+#[test]
+fn invalid_tolerance_test() {
+    //a zero weight self loop, which a negative tolerance would let 0 take as its own previous vertex
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 2, &[(0, 0, 0.0), (0, 1, 1.0)]);
+    for (x, &ent) in vertices.iter().enumerate() {
+        world.entity_mut(ent).insert(GridPosition(x as i32, 0));
+    }
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let mut pos_sys_state: SystemState<Query<(&StandardGraphVertex, &GridPosition)>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+    let pos_query = pos_sys_state.get(&world);
+    let no_heuristic = |_: &GridPosition, _: &GridPosition| Heuristic{value: 0.0};
+
+    for tolerance in [-1.0, f32::NAN] {
+        assert_eq!(dijkstra_search_with_tolerance(&vert_query, vertices[0], vertices[1], tolerance).err(), Some(GraphError::InvalidTolerance));
+        assert_eq!(a_star_search_with_tolerance(&pos_query, vertices[0], vertices[1], no_heuristic, tolerance).err(), Some(GraphError::InvalidTolerance));
+    }
+    assert_eq!(dijkstra_search_with_tolerance(&vert_query, vertices[0], vertices[1], 0.0).unwrap().len(), 2);
+    assert_eq!(a_star_search_with_tolerance(&pos_query, vertices[0], vertices[1], no_heuristic, 0.0).unwrap().len(), 2);
+}
+
+
+#[test]
+fn reachable_within_test() {
+    #[derive(Component)]
+    struct Shop(bool);
+
+    //a chain 0 -> 1 -> 2 -> 3 of unit edges, with 2 -> 5 giving a short but long winded way to 5, which is also one costly step from 0,
+    //and 4 a single step from 0 but far too far away
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0), (0, 4, 10.0), (2, 5, 0.5), (0, 5, 5.0)]);
+    for (pos, &ent) in vertices.iter().enumerate() {
+        world.entity_mut(ent).insert(Shop(pos != 1));
+    }
+
+    let mut vertex_sys_state: SystemState<Query<(&StandardGraphVertex, &Shop)>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //4 is within the step limit but not the distance limit, and 1 is in range but isn't a shop
+    let reachable = reachable_within(&vert_query, vertices[0], 3, 3.0, |shop| shop.0).unwrap();
+    assert_eq!(reachable, vec![
+        (vertices[0], Steps(0), Distance(0.0)),
+        (vertices[2], Steps(2), Distance(2.0)),
+        (vertices[5], Steps(3), Distance(2.5)),
+        (vertices[3], Steps(3), Distance(3.0))
+    ]);
+
+    //with one step fewer the only path to 5 within the distance takes too many steps, and the direct edge is too long
+    let reachable = reachable_within(&vert_query, vertices[0], 2, 3.0, |shop| shop.0).unwrap();
+    assert_eq!(reachable, vec![(vertices[0], Steps(0), Distance(0.0)), (vertices[2], Steps(2), Distance(2.0))]);
+
+    //with room for the direct edge, 5 is found at its shortest distance over 3 steps rather than the single long step
+    let reachable = reachable_within(&vert_query, vertices[0], 3, 10.0, |shop| shop.0).unwrap();
+    assert!(reachable.contains(&(vertices[5], Steps(3), Distance(2.5))));
+    assert!(reachable.contains(&(vertices[4], Steps(1), Distance(10.0))));
+}
+
+
+#[test]
+fn wire_edges_by_label_test() {
+    //vertices spawned with labels 10 to 13, where only 10 starts with a vertex component, already holding an edge to 11
+    let mut world = World::new();
+    let vertices: Vec<Entity> = (10..14).map(|value| world.spawn(GraphLabel{value}).id()).collect();
+    world.entity_mut(vertices[0]).insert(StandardGraphVertex::new_with_edges(vec![(vertices[1], 5.0)]));
+
+    //an unknown label fails before any edge is added
+    assert_eq!(wire_edges_by_label(&mut world, &[(10, 12, 1.0), (12, 20, 1.0)]), Err(GraphError::UnknownLabel(20)));
+    assert_eq!(world.get::<StandardGraphVertex>(vertices[0]).unwrap().neighbour_count(), 1);
+    assert!(world.get::<StandardGraphVertex>(vertices[2]).is_none());
+
+    wire_edges_by_label(&mut world, &[(10, 11, 1.0), (10, 12, 2.0), (11, 13, 1.0), (12, 13, 1.0), (13, 10, 1.0)]).unwrap();
+    //the existing edge keeps its weight, and the vertices without a vertex component are given one
+    assert_eq!(world.get::<StandardGraphVertex>(vertices[0]).unwrap().get_neighbours_with_weight(), vec![(vertices[1], 5.0), (vertices[2], 2.0)]);
+    assert_eq!(world.get::<StandardGraphVertex>(vertices[1]).unwrap().get_neighbours_with_weight(), vec![(vertices[3], 1.0)]);
+    assert_eq!(world.get::<StandardGraphVertex>(vertices[2]).unwrap().get_neighbours_with_weight(), vec![(vertices[3], 1.0)]);
+    assert_eq!(world.get::<StandardGraphVertex>(vertices[3]).unwrap().get_neighbours_with_weight(), vec![(vertices[0], 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+    let path = dijkstra_search(&vert_query, vertices[0], vertices[3]).unwrap();
+    assert_eq!(path.entities().collect::<Vec<_>>(), vec![vertices[3], vertices[2], vertices[0]]);
+
+    //labels must be unique to be resolved
+    world.spawn(GraphLabel{value: 11});
+    assert_eq!(wire_edges_by_label(&mut world, &[(10, 13, 1.0)]), Err(GraphError::DuplicateLabel(11)));
+}
+
+
+#[test]
+fn log_on_err_test() {
+    //a writer collecting everything logged into a shared buffer
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 3, &[(0, 1, 1.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt().with_ansi(false).with_writer(move || writer.clone()).finish();
+    tracing::subscriber::with_default(subscriber, || {
+        bfs(&vert_query, vertices[0], vertices[1]).log_on_err();
+        bfs(&vert_query, vertices[1], vertices[2]).log_on_err();
+    });
+
+    //only the failed search is logged, as a warning with the error's message
+    let logged = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(logged.lines().count(), 1);
+    assert!(logged.contains("WARN"));
+    assert!(logged.contains(&format!("graph function failed: {}", GraphError::IsolatedStart)));
+}
+
+
+#[test]
+fn bottleneck_tree_test() {
+    //1 is cheapest to reach directly, but has a smaller bottleneck via 2 and 3, and 5 can't be reached
+    let mut world = World::new();
+    let vertices = spawn_test_graph(&mut world, 6, &[(0, 1, 4.0), (0, 2, 1.0), (2, 3, 2.0), (3, 1, 3.0), (1, 4, 5.0), (2, 4, 9.0)]);
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let bottlenecks = bottleneck_tree(&vert_query, vertices[0]).unwrap();
+    let expected: HashMap<Entity, f32> = [(0, 0.0), (1, 3.0), (2, 1.0), (3, 2.0), (4, 5.0)].into_iter().map(|(pos, bottleneck)| (vertices[pos], bottleneck)).collect();
+    assert_eq!(bottlenecks, expected);
+
+    //each value matches the bottleneck of the single pair search
+    for (&ent, &bottleneck) in bottlenecks.iter() {
+        assert_eq!(bottleneck_path(&vert_query, vertices[0], ent).unwrap().1, bottleneck);
+    }
+    assert!(matches!(bottleneck_path(&vert_query, vertices[0], vertices[5]), Err(GraphError::NoPath)));
+}
+
+
+#[test]
+fn dfs_state_test() {
+    let mut world = World::new();
+    load_graph(&mut world, "./assets/test_graph.graph");
+    let entity_start = get_entity_with_label(&mut world, 1).expect("The given label should exist");
+    let entity_end = get_entity_with_label(&mut world, 5).expect("The given label should exist");
+    let unreachable = world.spawn(StandardGraphVertex::new()).id();
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    //step two vertices at a time until the search finishes, which should take several steps and give the same path as dfs
+    let mut state = DfsState::new(&vert_query, entity_start).unwrap();
+    let mut steps = 1;
+    let path = loop {
+        match state.step(&vert_query, entity_end, 2) {
+            DfsProgress::InProgress => steps += 1,
+            DfsProgress::Found(path) => break path,
+            DfsProgress::Exhausted => panic!("the end vertex should be reachable")
+        }
+    };
+    assert!(steps > 1);
+    let expected = dfs(&vert_query, entity_start, entity_end).unwrap();
+    assert_eq!(path.entities().collect::<Vec<_>>(), expected.entities().collect::<Vec<_>>());
+
+    //a vertex that can't be reached leaves the search exhausted
+    let mut state = DfsState::new(&vert_query, entity_start).unwrap();
+    while let DfsProgress::InProgress = state.step(&vert_query, unreachable, 3) {}
+    assert!(matches!(state.step(&vert_query, unreachable, 3), DfsProgress::Exhausted));
+
+    assert!(matches!(DfsState::new(&vert_query, entity_start).unwrap().step(&vert_query, entity_start, 0), DfsProgress::Found(_)));
+    assert!(matches!(DfsState::new(&vert_query, Entity::PLACEHOLDER), Err(GraphError::StartBlocked)));
+
+    //a budget of zero searches nothing, and each vertex has its neighbours fetched at most once however many steps are taken
+    let mut world = World::new();
+    let grid = spawn_counting_grid(&mut world, 4, 4);
+    let mut counting_sys_state: SystemState<Query<&CountingVertex>> = SystemState::new(&mut world);
+    let counting_query = counting_sys_state.get(&world);
+    let mut state = DfsState::new(&counting_query, grid[0]).unwrap();
+    let expansions = |query: &Query<&CountingVertex>| query.iter().map(|vert| vert.expansions.load(Ordering::Relaxed)).sum::<usize>();
+    assert_eq!(expansions(&counting_query), 1);
+    assert!(matches!(state.step(&counting_query, grid[15], 0), DfsProgress::InProgress));
+    assert_eq!(expansions(&counting_query), 1);
+    while let DfsProgress::InProgress = state.step(&counting_query, grid[15], 1) {}
+    assert!(counting_query.iter().all(|vert| vert.expansions.load(Ordering::Relaxed) <= 1));
+}
+
+
+#[test]
+fn duplicate_edges_test() {
+    let mut world = World::new();
+    let vertices: Vec<Entity> = (0..3).map(|_| world.spawn_empty().id()).collect();
+    //0 lists 1 three times and 2 once, and 1 lists 2 twice
+    world.entity_mut(vertices[0]).insert(StandardGraphVertex::new_with_edges(vec![(vertices[1], 3.0), (vertices[2], 1.0), (vertices[1], 2.0), (vertices[1], 4.0)]));
+    world.entity_mut(vertices[1]).insert(StandardGraphVertex::new_with_edges(vec![(vertices[2], 1.0), (vertices[2], 1.0)]));
+    world.entity_mut(vertices[2]).insert(StandardGraphVertex::new_with_edges(vec![(vertices[0], 1.0)]));
+
+    let mut vertex_sys_state: SystemState<Query<(Entity, &StandardGraphVertex)>> = SystemState::new(&mut world);
+    let query = vertex_sys_state.get(&world);
+    let mut duplicates = duplicate_edges(&query);
+    duplicates.sort();
+    let mut expected = vec![(vertices[0], vertices[1]), (vertices[1], vertices[2])];
+    expected.sort();
+    assert_eq!(duplicates, expected);
+
+    //collapsing keeps the smallest weight in the position of the first edge, after which one removal is enough
+    for ent in vertices.iter() {
+        world.get_mut::<StandardGraphVertex>(*ent).unwrap().dedup_edges();
+    }
+    assert_eq!(world.get::<StandardGraphVertex>(vertices[0]).unwrap().get_neighbours_with_weight(), vec![(vertices[1], 2.0), (vertices[2], 1.0)]);
+    assert_eq!(world.get::<StandardGraphVertex>(vertices[1]).unwrap().get_neighbours_with_weight(), vec![(vertices[2], 1.0)]);
+
+    let query = vertex_sys_state.get(&world);
+    assert!(duplicate_edges(&query).is_empty());
+
+    let mut vertex = world.get_mut::<StandardGraphVertex>(vertices[0]).unwrap();
+    assert!(vertex.remove_edge(vertices[1]));
+    assert!(!vertex.get_neighbours().contains(&vertices[1]));
+}
+
+
+#[test]
+fn bfs_spanning_tree_test() {
+    let mut world = World::new();
+    load_graph(&mut world, "./assets/test_graph.graph");
+    let entity_start = get_entity_with_label(&mut world, 1).expect("The given label should exist");
+    let outside = world.spawn(StandardGraphVertex::new()).id();
+
+    let mut vertex_sys_state: SystemState<Query<&StandardGraphVertex>> = SystemState::new(&mut world);
+    let vert_query = vertex_sys_state.get(&world);
+
+    let tree_edges = bfs_spanning_tree(&vert_query, entity_start).unwrap();
+    assert_eq!(tree_edges.len(), reachable_count(&vert_query, entity_start).unwrap() - 1);
+
+    //each vertex is a child at most once and never the start, and each parent was reached before its children, so there are no cycles
+    let mut reached = HashSet::from([entity_start]);
+    for &(parent, child) in tree_edges.iter() {
+        assert!(reached.contains(&parent));
+        assert!(reached.insert(child));
+    }
+    assert!(!reached.contains(&outside));
+
+    //the edges match the parents found by bfs_tree
+    let parents = bfs_tree(&vert_query, entity_start).unwrap();
+    for &(parent, child) in tree_edges.iter() {
+        assert_eq!(parents[&child], Some(parent));
+    }
+
+    assert!(bfs_spanning_tree(&vert_query, outside).unwrap().is_empty());
+}
+
+
+
+/// Helper function that returns the Entity with corresponding GraphLabel value
+fn get_entity_with_label(mut world: &mut World, label: usize) -> Option<Entity> {
+    let mut label_sys_state: SystemState<Query<(Entity, &GraphLabel)>> = SystemState::new(&mut world);
+    let label_query = label_sys_state.get(&world);
+    label_query.iter().find_map(|(ent, lab)| if lab.value == label {Some(ent)} else {None})
+}
+
+
+/// Helper function that spawns a graph of [StandardGraphVertex]s with the given (from, to, weight) edges, returning the entities by index
+fn spawn_test_graph(world: &mut World, vertex_count: usize, edges: &[(usize, usize, f32)]) -> Vec<Entity> {
+    let entities = spawn_graph(&mut world.commands(), edges, vertex_count);
+    world.flush();
+    entities
+}
+
+
+
+/// Vertex that counts the number of times its neighbours have been requested, used to measure the number of vertices a search expands
+#[derive(Component)]
+struct CountingVertex {
+    neighbours: Vec<(Entity, f32)>,
+    expansions: AtomicUsize
+}
+
+impl GraphVertex for CountingVertex {
+    fn get_neighbours(&self) -> Vec<Entity> {
+        self.expansions.fetch_add(1, Ordering::Relaxed);
+        self.neighbours.iter().map(|(ent, _)| *ent).collect()
+    }
+    fn get_neighbours_with_weight(&self) -> Vec<(Entity, f32)> {
+        self.expansions.fetch_add(1, Ordering::Relaxed);
+        self.neighbours.clone()
+    }
+    fn neighbour_count(&self) -> usize {
+        self.neighbours.len()
+    }
+}
+
+#[derive(Component)]
+struct GridPosition(i32, i32);
+
+fn euclidean_heuristic(a: &GridPosition, b: &GridPosition) -> Heuristic {
+    Heuristic{value: (((a.0 - b.0).pow(2) + (a.1 - b.1).pow(2)) as f32).sqrt()}
+}
+
+/// Helper function that spawns a 4-connected grid of [CountingVertex]s with unit weight edges, returning the entities in row-major order
+fn spawn_counting_grid(world: &mut World, width: i32, height: i32) -> Vec<Entity> {
+    let entities: Vec<Entity> = (0..width * height).map(|_| world.spawn_empty().id()).collect();
+    for y in 0..height {
+        for x in 0..width {
+            let neighbours = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].into_iter()
+            .filter(|&(nx, ny)| nx >= 0 && ny >= 0 && nx < width && ny < height)
+            .map(|(nx, ny)| (entities[(ny * width + nx) as usize], 1.0))
+            .collect();
+            world.entity_mut(entities[(y * width + x) as usize])
+            .insert((CountingVertex{neighbours, expansions: AtomicUsize::new(0)}, GridPosition(x, y)));
+        }
+    }
+    entities
+}
+
+/// Helper function that returns the total number of expansions over all [CountingVertex]s, resetting the counts to 0
+fn take_expansions(query: &Query<(&CountingVertex, &GridPosition)>) -> usize {
+    query.iter().map(|(vert, _)| vert.expansions.swap(0, Ordering::Relaxed)).sum()
+}
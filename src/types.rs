@@ -1,7 +1,9 @@
-use std::{error::Error, fmt::Display, ops::Add};
+use std::{error::Error, fmt::Display, ops::{Add, Deref}};
 
 use bevy::{ecs::query::QueryEntityError, prelude::*, utils::HashMap};
 
+use crate::graph_vertex::GraphVertex;
+
 
 #[derive(Component)]
 pub struct GraphLabel {
@@ -19,16 +21,40 @@ impl Display for InvalidPathError {
 }
 impl Error for InvalidPathError {}
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GraphError{
     NoPath,
-    InvalidEntity,
-    NegativeWeight
+    /// An entity is not a valid vertex in the searched query, either the end vertex of a search or, in strict searches, an edge's destination
+    InvalidEntity(Entity),
+    /// The start vertex of a search is not in the query, for example because it was excluded by a filter
+    StartBlocked,
+    NegativeWeight,
+    /// An edge weight was infinite or NaN
+    NonFiniteWeight,
+    NegativeCycle,
+    /// The graph contains a cycle, but the algorithm requires a directed acyclic graph
+    NotAcyclic,
+    /// No vertex has the given [`GraphLabel`] value
+    UnknownLabel(usize),
+    /// More than one vertex has the given [`GraphLabel`] value, when it was expected to be unique
+    DuplicateLabel(usize),
+    /// The search gave up after searching its maximum number of vertices
+    BudgetExceeded,
+    /// An entity has some, but not all, of the components a vertex needs in the searched query, such as the data component but not the vertex
+    IncompleteVertex(Entity),
+    /// The start vertex of a search has no outgoing edges, so no other vertex can be reached from it
+    IsolatedStart,
+    /// The tolerance given to a search was negative or NaN
+    InvalidTolerance,
+    /// A path does not start or end at the expected vertex, or uses an edge that does not exist in the graph
+    BrokenPath
 }
 
 impl From<QueryEntityError> for GraphError {
-    fn from(_: QueryEntityError) -> Self {
-        Self::InvalidEntity
+    fn from(err: QueryEntityError) -> Self {
+        match err {
+            QueryEntityError::QueryDoesNotMatch(ent) | QueryEntityError::NoSuchEntity(ent) | QueryEntityError::AliasedMutability(ent) => Self::InvalidEntity(ent)
+        }
     }
 }
 impl From<InvalidPathError> for GraphError {
@@ -40,22 +66,147 @@ impl Display for GraphError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self{
             GraphError::NoPath => write!(f, "no path could be found between the provided vertices"),
-            GraphError::InvalidEntity => write!(f, "the provided entity is not a valid GraphVertex"),
+            GraphError::InvalidEntity(ent) => write!(f, "the entity {:?} is not a valid GraphVertex", ent),
+            GraphError::StartBlocked => write!(f, "the start vertex is not a valid GraphVertex in the searched query"),
             GraphError::NegativeWeight => write!(f, "a provided edge weight was negative"),
+            GraphError::NonFiniteWeight => write!(f, "a provided edge weight was infinite or NaN"),
+            GraphError::NegativeCycle => write!(f, "the graph contains a cycle with negative total weight"),
+            GraphError::NotAcyclic => write!(f, "the graph contains a cycle, but must be acyclic"),
+            GraphError::UnknownLabel(label) => write!(f, "no vertex has the label {}", label),
+            GraphError::DuplicateLabel(label) => write!(f, "more than one vertex has the label {}", label),
+            GraphError::BudgetExceeded => write!(f, "the search exceeded its maximum number of searched vertices"),
+            GraphError::IncompleteVertex(ent) => write!(f, "the entity {:?} has only some of the components of a vertex", ent),
+            GraphError::IsolatedStart => write!(f, "the start vertex has no outgoing edges"),
+            GraphError::InvalidTolerance => write!(f, "the tolerance must be zero or positive"),
+            GraphError::BrokenPath => write!(f, "the path does not join the expected vertices using edges of the graph"),
         }
     }
 }
 impl Error for GraphError {}
 
+/// The result of a graph function that can fail with a [`GraphError`]
+pub type GraphResult<T> = Result<T, GraphError>;
+
+/// Extension for [`GraphResult`] to deal with a failed graph function inside a system that doesn't return a result
+pub trait LogOnErr {
+    /// Logs the error, if there is one, as a warning and discards the result
+    /// 
+    /// Systems usually return `()`, so an error can't be passed on with `?`. This saves matching on the result when a failure should be
+    /// noticed but not otherwise acted on.
+    /// 
+    /// # Example
+    /// 
+    /// ```ignore
+    /// //A system that marks the path home, warning rather than panicking if there isn't one
+    /// fn mark_route_home(journey: Res<Journey>, tiles: Query<&VertexType>, mut commands: Commands) {
+    ///     bfs(&tiles, journey.start, journey.home)
+    ///     .map(|path| path.into_iter().for_each(|ent| {commands.entity(ent).insert(OnRoute);}))
+    ///     .log_on_err();
+    /// }
+    /// ```
+    fn log_on_err(self);
+}
+
+impl<T> LogOnErr for GraphResult<T> {
+    fn log_on_err(self) {
+        if let Err(err) = self {
+            warn!("graph function failed: {}", err);
+        }
+    }
+}
+
+/// The direction in which edges are followed when searching the graph
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction{
+    /// Follow edges from a vertex to its neighbours, as given by [`get_neighbours`](crate::graph_vertex::GraphVertex::get_neighbours)
+    Outgoing,
+    /// Follow edges backwards, from a vertex to those that have it as a neighbour
+    Incoming,
+    /// Follow edges in both directions, treating the graph as undirected
+    Both
+}
+
 #[derive(Clone, Copy)]
 pub struct Heuristic{
     pub value: f32
 }
 
+/// A number of steps, ie edges traversed, from the start vertex of a search, as returned by 
+/// [`within_steps`](crate::graph_functions::neighbourhood::within_steps)
+/// 
+/// Kept distinct from [`Distance`] so that the compiler catches a step count being used as a distance by edge weight, or vice versa.
+/// Derefs to the `u64` it wraps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Steps(pub u64);
+
+impl Deref for Steps {
+    type Target = u64;
+    fn deref(&self) -> &u64 {
+        &self.0
+    }
+}
+impl From<u64> for Steps {
+    fn from(steps: u64) -> Self {
+        Self(steps)
+    }
+}
+impl From<Steps> for u64 {
+    fn from(steps: Steps) -> Self {
+        steps.0
+    }
+}
+
+/// The total edge weight of a path from the start vertex of a search, as returned by 
+/// [`within_distance`](crate::graph_functions::neighbourhood::within_distance)
+/// 
+/// See [`Steps`] for why this is a separate type. Derefs to the `f32` it wraps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Distance(pub f32);
+
+impl Deref for Distance {
+    type Target = f32;
+    fn deref(&self) -> &f32 {
+        &self.0
+    }
+}
+impl From<f32> for Distance {
+    fn from(distance: f32) -> Self {
+        Self(distance)
+    }
+}
+impl From<Distance> for f32 {
+    fn from(distance: Distance) -> Self {
+        distance.0
+    }
+}
+
+
+/// The type distances are summed in while searching
+/// 
+/// Edge weights are `f32`, and summing many of them in an `f32` accumulates rounding error, so on long paths two routes of the same true 
+/// length can compare unequal, or a slightly longer route can be chosen over a shorter one. The `f64-accumulation` feature sums in an `f64`
+/// instead, which keeps the error far below the precision of the edge weights themselves, at the cost of wider queue entries and some speed.
+/// Distances are still returned as `f32`, so only the comparisons made during the search are affected.
+#[cfg(not(feature = "f64-accumulation"))]
+pub(crate) type Accumulator = f32;
+#[cfg(feature = "f64-accumulation")]
+pub(crate) type Accumulator = f64;
 
 #[derive(Clone, Copy)]
 pub(crate) struct PathWeight{
-    pub weight: f32
+    pub weight: Accumulator
+}
+
+impl PathWeight{
+    pub fn new(weight: f32) -> Self {
+        Self{weight: weight as Accumulator}
+    }
+
+    /// The summed weight, rounded back to an `f32` if accumulated in an `f64`
+    #[allow(clippy::unnecessary_cast)] //only a cast with the f64-accumulation feature
+    pub fn value(self) -> f32 {
+        self.weight as f32
+    }
 }
 
 impl Add<PathWeight> for PathWeight{
@@ -70,7 +221,7 @@ impl Add<f32> for PathWeight{
     type Output = Self;
 
     fn add(self, rhs: f32) -> Self::Output {
-        Self{weight: self.weight + rhs}
+        Self{weight: self.weight + rhs as Accumulator}
     }
 }
 
@@ -78,30 +229,30 @@ impl Add<Heuristic> for PathWeight{
     type Output = Self;
 
     fn add(self, rhs: Heuristic) -> Self::Output {
-        Self{weight: self.weight + rhs.value}
+        Self{weight: self.weight + rhs.value as Accumulator}
     }
 }
 
 impl PartialEq for PathWeight{
     fn eq(&self, other: &Self) -> bool {
-        self.weight == other.weight
+        self.cmp(other) == std::cmp::Ordering::Equal
     }
 }
 impl Eq for PathWeight{}
 impl PartialOrd for PathWeight{
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.weight < other.weight {Some(std::cmp::Ordering::Less)}
-        else {Some(std::cmp::Ordering::Greater)}
+        Some(self.cmp(other))
     }
 }
 impl Ord for PathWeight{
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.weight < other.weight {std::cmp::Ordering::Less}
-        else {std::cmp::Ordering::Greater}
+        //a total order, so equal weights compare as equal and can be tie-broken by whatever they are paired with
+        self.weight.total_cmp(&other.weight)
     }
 }
 
 
+#[derive(Clone)]
 pub struct GraphPath<D>{
     path: Vec<(Entity, D)>
 }
@@ -114,6 +265,120 @@ impl<D> GraphPath<D>{
     pub fn single(start_ent: Entity, val: D) -> Self {
         Self { path: vec![(start_ent, val)] }
     }
+
+    /// The number of vertices in the path, including both the start and end vertices
+    pub fn len(&self) -> usize {
+        self.path.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Iterates over the entities of the path, in the order they are stored
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.path.iter().map(|&(ent, _)| ent)
+    }
+
+    /// Returns the [`GraphLabel`] value of each vertex of the path, in the order they are stored
+    /// 
+    /// Unlike entities, labels can be stable between sessions, so this gives a form of the path that can be logged, sent or saved and later
+    /// restored with [`GraphPath::from_label_seq`]. Vertices without a label in the query are skipped.
+    pub fn to_label_seq(&self, query: &Query<&GraphLabel>) -> Vec<usize> {
+        self.path.iter().filter_map(|(ent, _)| query.get(*ent).ok().map(|label| label.value)).collect()
+    }
+
+    /// Returns a [`Display`] for the path, writing its vertices from the start vertex to the end vertex as `1 -> 5 -> 12`
+    /// 
+    /// Each vertex is written as its [`GraphLabel`] value, or as its [`Entity::index`] if it has no label in the query, which is much easier
+    /// to read than the entities themselves when debugging.
+    pub fn display_with_labels<'a, 'w, 's>(&'a self, query: &'a Query<'w, 's, &'static GraphLabel>) -> LabelledPath<'a, 'w, 's, D> {
+        LabelledPath{path: self, query}
+    }
+
+    /// Consumes the path, returning its vertices from the start vertex to the end vertex
+    /// 
+    /// Each vertex keeps the data it was stored with, so for weighted paths the distances increase along the returned vector.
+    pub fn into_forward(self) -> Vec<(Entity, D)> {
+        let mut path = self.path;
+        path.reverse();
+        path
+    }
+}
+
+/// Writes a path using the labels of its vertices, as returned by [`GraphPath::display_with_labels`]
+pub struct LabelledPath<'a, 'w, 's, D> {
+    path: &'a GraphPath<D>,
+    query: &'a Query<'w, 's, &'static GraphLabel>
+}
+
+impl<D> Display for LabelledPath<'_, '_, '_, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        //the path is stored in reverse order, so write it backwards to start at the start vertex
+        for (pos, (ent, _)) in self.path.path.iter().rev().enumerate() {
+            if pos > 0 {write!(f, " -> ")?;}
+            match self.query.get(*ent) {
+                Ok(label) => write!(f, "{}", label.value)?,
+                Err(_) => write!(f, "{}", ent.index())?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GraphPath<()>{
+    /// Rebuilds a path from the [`GraphLabel`] values of its vertices, as given by [`GraphPath::to_label_seq`]
+    /// 
+    /// The labels should be in the stored order of the path, ie **reverse order**. The vertex data isn't saved in the labels, so the path is unweighted.
+    /// 
+    /// # Errors
+    /// 
+    /// [`GraphError::UnknownLabel`]: If no vertex in the query has one of the labels.
+    pub fn from_label_seq(labels: &[usize], query: &Query<(Entity, &GraphLabel)>) -> Result<GraphPath<()>, GraphError> {
+        let label_entities: HashMap<usize, Entity> = query.iter().map(|(ent, label)| (label.value, ent)).collect();
+        let path = labels.iter()
+        .map(|label| label_entities.get(label).map(|ent| (*ent, ())).ok_or(GraphError::UnknownLabel(*label)))
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(GraphPath::new(path))
+    }
+
+    /// Builds a path from its vertices in order from the start vertex to the end vertex, checking that each vertex has an edge to the next
+    /// 
+    /// Useful for hand-written paths, eg scripted routes or expected paths in tests, as a typo gives an error rather than a path that can't be
+    /// followed. The vertices are given start first, as returned by [`GraphPath::into_forward`], and stored in the usual **reverse order**.
+    /// 
+    /// # Errors
+    /// 
+    /// [`GraphError::InvalidEntity`]: If a vertex of the path, other than the end vertex, does not appear in the provided query.
+    /// 
+    /// [`GraphError::NoPath`]: If the path is empty, or a vertex of the path has no edge to the next vertex.
+    pub fn from_entities<V: GraphVertex>(entities: Vec<Entity>, query: &Query<&V>) -> Result<GraphPath<()>, GraphError> {
+        if entities.is_empty() {return Err(GraphError::NoPath);}
+        for pair in entities.windows(2) {
+            if !query.get(pair[0])?.get_neighbours().contains(&pair[1]) {return Err(GraphError::NoPath);}
+        }
+        Ok(GraphPath::new(entities.into_iter().rev().map(|ent| (ent, ())).collect()))
+    }
+}
+
+impl<D: Clone> GraphPath<D>{
+    /// Splits the path at the given vertex, returning the part of the path up to it and the part of the path from it onwards
+    /// 
+    /// Both parts include the given vertex and are stored in the same **reverse order** as the original path, so the first part ends at the
+    /// given vertex and the second part starts at it. The data stored with each vertex is copied unchanged, so for weighted paths the distances in the
+    /// second part are still measured from the original start vertex. Returns [`None`] if the vertex is not on the path.
+    pub fn split_at_vertex(&self, ent: Entity) -> Option<(GraphPath<D>, GraphPath<D>)> {
+        let pos = self.path.iter().position(|(path_ent, _)| *path_ent == ent)?;
+        //as the path is reversed, the vertices before the split point are stored after it
+        Some((GraphPath::new(self.path[pos..].to_vec()), GraphPath::new(self.path[..=pos].to_vec())))
+    }
+}
+
+impl GraphPath<f32>{
+    /// The total weight of the path, ie the distance stored alongside the final vertex
+    pub fn total_weight(&self) -> f32 {
+        self.path.first().map(|&(_, dist)| dist).unwrap_or(0.0)
+    }
 }
 
 pub struct VisitedNodes{
@@ -127,18 +392,41 @@ impl VisitedNodes{
         Self{nodes}
     }
 
+    /// Forgets every visited vertex except the new start vertex, keeping the allocated memory for reuse
+    pub fn reset_to_start(&mut self, start_ent: Entity) {
+        self.nodes.clear();
+        self.nodes.insert(start_ent, (None, 0, 0.0));
+    }
+
     pub fn is_visited(&self, ent: &Entity) -> bool {
         self.nodes.contains_key(ent)
     }
 
+    /// The step the vertex was visited at, or [`None`] if it hasn't been visited
+    pub fn step_of(&self, ent: &Entity) -> Option<u64> {
+        self.nodes.get(ent).map(|&(_, step, _)| step)
+    }
+
+    /// Marks the vertex as visited from the previous vertex
+    /// 
+    /// A vertex can't be its own previous vertex, so this is ignored if they are the same, eg when following a self-loop. 
+    /// This keeps the chain of previous vertices free of loops.
     pub fn insert(&mut self, ent: Entity, previous: Entity, step: u64, dist: f32) {
+        if previous == ent {return;}
         self.nodes.insert(ent, (Some(previous), step, dist));
     }
 
+    /// Replaces the previous vertex and distance of the vertex, ignored if the vertex would be its own previous vertex
     pub fn set_previous(&mut self, ent: Entity, prev_ent: Entity, new_weight: f32){
+        if prev_ent == ent {return;}
         self.nodes.entry(ent).insert((Some(prev_ent), 0, new_weight));
     }
 
+    /// Consumes the visited vertices, returning each one mapped to the vertex it was reached from, with [`None`] for the start vertex
+    pub fn into_previous_map(self) -> HashMap<Entity, Option<Entity>> {
+        self.nodes.into_iter().map(|(ent, (previous, _, _))| (ent, previous)).collect()
+    }
+
     pub fn determine_path(&self, final_vert: Entity) -> Result<GraphPath<()>, InvalidPathError> {
         let Some(&(mut to_follow, _, _)) = self.nodes.get(&final_vert) else {return Err(InvalidPathError)};
         let mut path = vec![(final_vert, ())];
@@ -155,17 +443,16 @@ impl VisitedNodes{
         Ok(GraphPath::new(path))
     }
 
+    /// Determines the path to the final vertex in **reverse order**, with each vertex paired with its own distance from the start
     pub fn determine_path_weighted(&self, final_vert: Entity) -> Result<GraphPath<f32>, InvalidPathError> {
-        let Some(&(mut to_follow, _, mut dist)) = self.nodes.get(&final_vert) else {return Err(InvalidPathError)};
-        let mut path = vec![(final_vert, dist)];
+        let mut path = Vec::new();
+        let mut to_follow = Some(final_vert);
         let max_length = self.nodes.len();
-        while to_follow.is_some(){
-            let prev = to_follow.unwrap();
-            (to_follow, dist) = match self.nodes.get(&to_follow.unwrap()){
-                Some(&(val, _, dist)) => (val, dist),
-                None => return Err(InvalidPathError)
-            };
-            path.push((prev, dist));
+        while let Some(current) = to_follow {
+            //the distance is read from the same entry as the vertex, so the two can't get out of step
+            let Some(&(previous, _, dist)) = self.nodes.get(&current) else {return Err(InvalidPathError)};
+            path.push((current, dist));
+            to_follow = previous;
             //check for a loop
             if path.len() > max_length {return Err(InvalidPathError)}
         }